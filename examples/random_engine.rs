@@ -0,0 +1,13 @@
+// Engine playing random valid moves, example of the engine protocol described in
+// engine_player. Run the game with e.g.
+// `-t engine -s first -x target/debug/examples/random_engine`.
+
+use std::io;
+
+use tic_tac_toe_game::ai_player::RandomPlayer;
+use tic_tac_toe_game::engine_player::serve_engine_protocol;
+
+fn main() {
+    serve_engine_protocol(&mut RandomPlayer::new(), io::stdin().lock(), io::stdout())
+        .expect("Failed to play the game.");
+}
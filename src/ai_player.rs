@@ -0,0 +1,216 @@
+//! Players choosing moves by themselves, for games where no human plays.
+//!
+//! Moves are game commands as sent over the network, e.g. `/place (1, 1)`, so any
+//! player can replay them on the playboard of the variant.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::io;
+use std::str::FromStr;
+
+use crate::engine_player::EngineProcess;
+use crate::game_options::{GameVariant, StartOrder};
+use crate::game_session::GameMove;
+use crate::mcts_player::{MctsOptions, MctsPlayer};
+use crate::personality::PersonalityPlayer;
+use crate::playboard::GameState;
+use crate::playboard_variant::{PlayboardVariant, PlayboardVariantFactory};
+use crate::q_learning::PolicyPlayer;
+use crate::settings::ai_options::{
+    MCTS_PLAYER_NAME, PERSONALITY_PLAYER_NAME, POLICY_PLAYER_NAME, THREAT_PLAYER_NAME,
+};
+use crate::settings::commands::{COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND};
+use crate::settings::playboard_options::{NUMERICAL_MAX_NUMBER, PLAYBOARD_ROW_COL_SIZE};
+use crate::threat_player::{BoardOptions, ThreatPlayer};
+
+pub const RANDOM_PLAYER_NAME: &str = "random";
+
+/// Player of one side of the game.
+pub trait AiPlayer {
+    /// Name shown in results.
+    fn get_name(&self) -> String;
+    /// Prepares the player for a new game, in which it plays given start order.
+    fn new_game(&mut self, variant: GameVariant, start_order: StartOrder) -> io::Result<()>;
+    /// Chooses move of the player on turn after given moves of the current game.
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String>;
+    /// Chat line the player wants to send to the oponent after new_game or choose_move.
+    fn take_chat(&mut self) -> Option<String> {
+        None
+    }
+}
+
+/// Moves of the game as game commands.
+pub fn get_move_lines(history: &[GameMove]) -> Vec<String> {
+    history
+        .iter()
+        .map(|game_move| format!("{} {}", game_move.command, game_move.data))
+        .collect()
+}
+
+/// Plays move given as game command for the player on turn. Playboard is not changed
+/// when the move is invalid.
+pub fn play_move(
+    playboard: &mut dyn PlayboardVariant,
+    game_move: &str,
+    on_turn: StartOrder,
+) -> Result<(String, GameState), String> {
+    if let Some(data) = game_move.strip_prefix(PLACE_ON_PLAYBOARD_COMMAND) {
+        playboard.place(data, on_turn)
+    } else if let Some(data) = game_move.strip_prefix(COLLAPSE_COMMAND) {
+        playboard.collapse(data, on_turn)
+    } else {
+        Err(format!("{} is not a move.", game_move))
+    }
+}
+
+/// Playboard after given moves and the start order of the player on turn. Places
+/// alternate starting with the first player, collapse is played by the player on turn
+/// before their place. Invalid moves are skipped.
+pub fn replay_moves(
+    variant: GameVariant,
+    moves: &[String],
+) -> (Box<dyn PlayboardVariant>, StartOrder) {
+    let mut playboard = PlayboardVariantFactory::create_playboard(variant);
+    let mut on_turn = StartOrder::First;
+
+    for game_move in moves {
+        if play_move(playboard.as_mut(), game_move, on_turn).is_ok()
+            && game_move.starts_with(PLACE_ON_PLAYBOARD_COMMAND)
+        {
+            on_turn = on_turn.oponent();
+        }
+    }
+
+    (playboard, on_turn)
+}
+
+/// Moves of the right kind for the playboard, not all of them are valid.
+pub fn get_candidate_moves(variant: GameVariant, playboard: &dyn PlayboardVariant) -> Vec<String> {
+    let cells: Vec<String> = (1..=PLAYBOARD_ROW_COL_SIZE)
+        .flat_map(|row| (1..=PLAYBOARD_ROW_COL_SIZE).map(move |col| format!("({}, {})", row, col)))
+        .collect();
+
+    if playboard.is_collapse_pending() {
+        return cells
+            .iter()
+            .map(|cell| format!("{}{}", COLLAPSE_COMMAND, cell))
+            .collect();
+    }
+
+    let places: Vec<String> = match variant {
+        GameVariant::Classic => cells,
+        GameVariant::Quantum => cells
+            .iter()
+            .enumerate()
+            .flat_map(|(index, first)| {
+                cells[index + 1..]
+                    .iter()
+                    .map(move |second| format!("{} {}", first, second))
+            })
+            .chain(cells.iter().cloned())
+            .collect(),
+        GameVariant::Numerical => cells
+            .iter()
+            .flat_map(|cell| {
+                (1..=NUMERICAL_MAX_NUMBER).map(move |number| format!("{} {}", cell, number))
+            })
+            .collect(),
+    };
+
+    places
+        .iter()
+        .map(|place| format!("{}{}", PLACE_ON_PLAYBOARD_COMMAND, place))
+        .collect()
+}
+
+/// Moves the player on turn may play on the playboard.
+pub fn get_playboard_moves(
+    variant: GameVariant,
+    playboard: &dyn PlayboardVariant,
+    on_turn: StartOrder,
+) -> Vec<String> {
+    get_candidate_moves(variant, playboard)
+        .into_iter()
+        .filter(|candidate| play_move(playboard.clone_box().as_mut(), candidate, on_turn).is_ok())
+        .collect()
+}
+
+/// Moves the player on turn may play after given moves.
+pub fn get_valid_moves(variant: GameVariant, moves: &[String]) -> Vec<String> {
+    let (playboard, on_turn) = replay_moves(variant, moves);
+
+    get_playboard_moves(variant, playboard.as_ref(), on_turn)
+}
+
+/// Plays random valid moves, baseline for other players.
+pub struct RandomPlayer {
+    variant: GameVariant,
+    rng: StdRng,
+}
+
+impl RandomPlayer {
+    pub fn new() -> Self {
+        Self {
+            variant: GameVariant::Classic,
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl Default for RandomPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AiPlayer for RandomPlayer {
+    fn get_name(&self) -> String {
+        RANDOM_PLAYER_NAME.to_string()
+    }
+
+    fn new_game(&mut self, variant: GameVariant, _start_order: StartOrder) -> io::Result<()> {
+        self.variant = variant;
+        Ok(())
+    }
+
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String> {
+        get_valid_moves(self.variant, moves)
+            .choose(&mut self.rng)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no valid move left"))
+    }
+}
+
+pub struct AiPlayerFactory;
+impl AiPlayerFactory {
+    /// Built-in player of given name, e.g. `random`, `threat size=15 win=5`, `mcts playouts=5000`,
+    /// `policy FILE` trained by q_learning or `personality NAME [FILE]`, other names are
+    /// commands of external engines.
+    pub fn create_ai_player(player: &str) -> io::Result<Box<dyn AiPlayer>> {
+        let (name, options) = player
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((player.trim(), ""));
+
+        match name {
+            RANDOM_PLAYER_NAME => Ok(Box::new(RandomPlayer::new())),
+            MCTS_PLAYER_NAME => match MctsOptions::from_str(options) {
+                Ok(options) => Ok(Box::new(MctsPlayer::new(options))),
+                Err(err) => Err(io::Error::new(io::ErrorKind::InvalidInput, err)),
+            },
+            THREAT_PLAYER_NAME if options.trim().is_empty() => Ok(Box::new(ThreatPlayer::new())),
+            THREAT_PLAYER_NAME => match BoardOptions::from_str(options) {
+                Ok(board) => Ok(Box::new(ThreatPlayer::for_board(board))),
+                Err(err) => Err(io::Error::new(io::ErrorKind::InvalidInput, err)),
+            },
+            POLICY_PLAYER_NAME => Ok(Box::new(PolicyPlayer::load(options.trim())?)),
+            PERSONALITY_PLAYER_NAME => {
+                let mut options = options.split_whitespace();
+                let name = options.next().unwrap_or_default();
+                Ok(Box::new(PersonalityPlayer::load(name, options.next())?))
+            }
+            _ => Ok(Box::new(EngineProcess::spawn(player)?)),
+        }
+    }
+}
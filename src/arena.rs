@@ -0,0 +1,258 @@
+//! Headless games between two AI players, for comparing engines and their settings.
+//!
+//! Both players get their own [`GameSession`], lines one sends are handled by the other
+//! one as if they came over the network, so the arena plays by the same rules.
+
+use std::time::{Duration, Instant};
+
+use crate::ai_player::{get_move_lines, AiPlayer};
+use crate::game_event::GameEvent;
+use crate::game_options::{GameVariant, StartOrder};
+use crate::game_session::{GameSession, SessionEffect};
+use crate::settings::commands::{COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND};
+use crate::settings::engine_protocol::ENGINE_MOVE_SEPARATOR;
+
+// Normal quantile of the 95 % confidence interval.
+const CONFIDENCE_QUANTILE: f64 = 1.96;
+
+/// Header of the CSV file with rows of [`ArenaGame::format_csv_row`].
+pub const ARENA_CSV_HEADER: &str =
+    "game,variant,first,second,result,winner,moves,forfeit,duration_ms";
+
+/// Players of the arena, indexed by 0 and 1 in results.
+pub type ArenaPlayers = [Box<dyn AiPlayer>; 2];
+
+/// Played game with everything needed to replay it.
+#[derive(Clone, Debug)]
+pub struct ArenaGame {
+    /// Number of the game from 1.
+    pub number: u32,
+    pub variant: GameVariant,
+    /// Index of the player who started.
+    pub first: usize,
+    /// Moves as game commands.
+    pub moves: Vec<String>,
+    /// Index of the winner, `None` on draw.
+    pub winner: Option<usize>,
+    /// Why the loser lost before the game finished, e.g. by invalid move.
+    pub forfeit: Option<String>,
+    pub duration: Duration,
+}
+
+impl ArenaGame {
+    /// Result in the usual notation from the point of the first player, e.g. `1-0`.
+    pub fn get_result(&self) -> &'static str {
+        match self.winner {
+            None => "1/2-1/2",
+            Some(winner) if winner == self.first => "1-0",
+            Some(_) => "0-1",
+        }
+    }
+
+    /// Row of the CSV file, see [`ARENA_CSV_HEADER`].
+    pub fn format_csv_row(&self, names: &[String; 2]) -> String {
+        [
+            self.number.to_string(),
+            self.variant.to_string(),
+            names[self.first].clone(),
+            names[1 - self.first].clone(),
+            self.get_result().to_string(),
+            match self.winner {
+                Some(winner) => names[winner].clone(),
+                None => String::new(),
+            },
+            self.moves.len().to_string(),
+            self.forfeit.clone().unwrap_or_default(),
+            self.duration.as_millis().to_string(),
+        ]
+        .iter()
+        .map(|field| format_csv_field(field))
+        .collect::<Vec<String>>()
+        .join(",")
+    }
+
+    /// Record of the game with players, result and moves in engine protocol notation.
+    pub fn format_record(&self, names: &[String; 2]) -> String {
+        let mut record = format!(
+            "[Game \"{}\"]\n[Variant \"{}\"]\n[First \"{}\"]\n[Second \"{}\"]\n[Result \"{}\"]\n",
+            self.number,
+            self.variant,
+            names[self.first],
+            names[1 - self.first],
+            self.get_result()
+        );
+
+        if let Some(forfeit) = &self.forfeit {
+            record += &format!("[Forfeit \"{}\"]\n", forfeit.replace('"', "'"));
+        }
+
+        record + &self.moves.join(ENGINE_MOVE_SEPARATOR) + "\n"
+    }
+}
+
+/// Results from the point of the first player of the arena.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ArenaStats {
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub forfeits: u32,
+    pub moves: u64,
+}
+
+impl ArenaStats {
+    pub fn add(&mut self, game: &ArenaGame) {
+        self.games += 1;
+        self.moves += game.moves.len() as u64;
+
+        match game.winner {
+            Some(0) => self.wins += 1,
+            Some(_) => self.losses += 1,
+            None => self.draws += 1,
+        }
+
+        if game.forfeit.is_some() {
+            self.forfeits += 1;
+        }
+    }
+
+    pub fn get_average_length(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+
+        self.moves as f64 / self.games as f64
+    }
+
+    /// Score of the first player (win 1, draw 1/2) and half-width of its 95 % confidence
+    /// interval.
+    pub fn get_score(&self) -> (f64, f64) {
+        if self.games == 0 {
+            return (0.0, 0.0);
+        }
+
+        let games = self.games as f64;
+        let score = (self.wins as f64 + self.draws as f64 / 2.0) / games;
+
+        if self.games == 1 {
+            return (score, 1.0);
+        }
+
+        let squares = (self.wins as f64 + self.draws as f64 / 4.0) / games;
+        let variance = (squares - score * score).max(0.0) * games / (games - 1.0);
+
+        (score, CONFIDENCE_QUANTILE * (variance / games).sqrt())
+    }
+}
+
+// Fields with separators or quotes are quoted.
+fn format_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn get_start_order(player: usize, first: usize) -> StartOrder {
+    if player == first {
+        StartOrder::First
+    } else {
+        StartOrder::Second
+    }
+}
+
+/// Plays one game, player of index `first` starts. Player who fails or plays invalid
+/// move loses.
+pub fn play_arena_game(
+    players: &mut ArenaPlayers,
+    number: u32,
+    variant: GameVariant,
+    first: usize,
+) -> ArenaGame {
+    let started = Instant::now();
+    let mut sessions =
+        [0, 1].map(|player| GameSession::new(get_start_order(player, first), variant));
+    let mut moves = Vec::new();
+
+    let forfeited = |loser: usize, reason: String, moves: Vec<String>| ArenaGame {
+        number,
+        variant,
+        first,
+        moves,
+        winner: Some(1 - loser),
+        forfeit: Some(reason),
+        duration: started.elapsed(),
+    };
+
+    for (player, ai_player) in players.iter_mut().enumerate() {
+        if let Err(err) = ai_player.new_game(variant, get_start_order(player, first)) {
+            return forfeited(player, err.to_string(), moves);
+        }
+    }
+
+    while let Some(on_turn) = (0..2).find(|&player| {
+        let status = sessions[player].get_game_status();
+        status.my_turn && status.game_running
+    }) {
+        let game_move = match players[on_turn].choose_move(&moves) {
+            Ok(game_move) => game_move,
+            Err(err) => return forfeited(on_turn, err.to_string(), moves),
+        };
+
+        if !game_move.starts_with(PLACE_ON_PLAYBOARD_COMMAND)
+            && !game_move.starts_with(COLLAPSE_COMMAND)
+        {
+            return forfeited(on_turn, format!("{} is not a move", game_move), moves);
+        }
+
+        for effect in sessions[on_turn].handle_local(&game_move) {
+            match effect {
+                SessionEffect::Send(line) => {
+                    sessions[1 - on_turn].handle_remote(&line);
+                }
+                SessionEffect::Emit(GameEvent::InvalidMove(msg)) => {
+                    return forfeited(
+                        on_turn,
+                        format!("invalid move {}: {}", game_move, msg),
+                        moves,
+                    )
+                }
+                SessionEffect::Emit(_) => {}
+            }
+        }
+
+        moves = get_move_lines(sessions[on_turn].get_history());
+    }
+
+    ArenaGame {
+        number,
+        variant,
+        first,
+        moves,
+        winner: (0..2).find(|&player| sessions[player].get_game_status().score.wins > 0),
+        forfeit: None,
+        duration: started.elapsed(),
+    }
+}
+
+/// Plays given number of games, players alternate in the start order and the first one
+/// starts the first game. Every game is passed to `on_game` once it is finished.
+pub fn run_arena(
+    players: &mut ArenaPlayers,
+    games: u32,
+    variant: GameVariant,
+    mut on_game: impl FnMut(&ArenaGame),
+) -> ArenaStats {
+    let mut stats = ArenaStats::default();
+
+    for number in 1..=games {
+        let game = play_arena_game(players, number, variant, (number as usize - 1) % 2);
+
+        stats.add(&game);
+        on_game(&game);
+    }
+
+    stats
+}
@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::cli_args_processing::{ArenaArguments, BookArguments, TrainArguments};
+use crate::game_messages::{
+    print_arena_game, print_arena_stats, print_book_generated, print_training_finished,
+};
+use tic_tac_toe_game::ai_player::{AiPlayer, AiPlayerFactory};
+use tic_tac_toe_game::arena::{run_arena, ArenaPlayers, ARENA_CSV_HEADER};
+use tic_tac_toe_game::engine_player::serve_engine_protocol;
+use tic_tac_toe_game::opening_book::OpeningBook;
+use tic_tac_toe_game::q_learning::Policy;
+
+fn create_output_file(path: &Option<String>) -> Option<BufWriter<File>> {
+    let path = path.as_ref()?;
+
+    match File::create(path) {
+        Ok(file) => Some(BufWriter::new(file)),
+        Err(err) => panic!("Cannot create file {}: {}.", path, err),
+    }
+}
+
+fn create_player(player: &str) -> Box<dyn AiPlayer> {
+    AiPlayerFactory::create_ai_player(player)
+        .unwrap_or_else(|err| panic!("Player {} failed to start: {}.", player, err))
+}
+
+// Results are written after every game, so they are kept when the arena is interrupted.
+pub fn run_arena_command(arguments: ArenaArguments) {
+    let mut players: ArenaPlayers = [
+        create_player(&arguments.players[0]),
+        create_player(&arguments.players[1]),
+    ];
+    let mut names = [players[0].get_name(), players[1].get_name()];
+
+    // Results of the same players are told apart by their order.
+    if names[0] == names[1] {
+        names = [format!("{} #1", names[0]), format!("{} #2", names[1])];
+    }
+
+    let mut csv_file = create_output_file(&arguments.csv_path);
+    let mut records_file = create_output_file(&arguments.records_path);
+
+    if let Some(csv_file) = csv_file.as_mut() {
+        writeln!(csv_file, "{}", ARENA_CSV_HEADER).expect("Failed to write CSV file.");
+    }
+
+    let stats = run_arena(&mut players, arguments.games, arguments.variant, |game| {
+        print_arena_game(game, &names);
+
+        if let Some(csv_file) = csv_file.as_mut() {
+            writeln!(csv_file, "{}", game.format_csv_row(&names))
+                .and_then(|_| csv_file.flush())
+                .expect("Failed to write CSV file.");
+        }
+
+        if let Some(records_file) = records_file.as_mut() {
+            writeln!(records_file, "{}", game.format_record(&names))
+                .and_then(|_| records_file.flush())
+                .expect("Failed to write game records.");
+        }
+    });
+
+    print_arena_stats(&stats, &names, arguments.variant);
+}
+
+// Built-in players are given to other games as engines by this command.
+pub fn run_engine_command(player: &str) {
+    let mut player = create_player(player);
+
+    if let Err(err) = serve_engine_protocol(player.as_mut(), io::stdin().lock(), io::stdout()) {
+        panic!("Engine protocol failed: {}.", err);
+    }
+}
+
+// Solving 4x4 board takes a while, the book is written at once in the end.
+pub fn run_book_command(arguments: BookArguments) {
+    let book = OpeningBook::generate(arguments.size, arguments.max_plies)
+        .unwrap_or_else(|err| panic!("{}", err));
+
+    let mut file = create_output_file(&Some(arguments.path.clone())).unwrap();
+    book.save(&mut file)
+        .and_then(|_| file.flush())
+        .unwrap_or_else(|err| panic!("Failed to write book {}: {}.", arguments.path, err));
+
+    print_book_generated(&book, &arguments.path);
+}
+
+pub fn run_train_command(arguments: TrainArguments) {
+    let (policy, stats) = Policy::train(&arguments.options);
+
+    let mut file = create_output_file(&Some(arguments.path.clone())).unwrap();
+    policy
+        .save(&mut file)
+        .and_then(|_| file.flush())
+        .unwrap_or_else(|err| panic!("Failed to write policy {}: {}.", arguments.path, err));
+
+    print_training_finished(&stats, &arguments.path);
+}
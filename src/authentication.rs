@@ -0,0 +1,161 @@
+//! Challenge-response authentication with password shared by both players.
+//!
+//! Password itself never travels over the connection. Server sends random challenge,
+//! client answers with HMAC of it keyed by the password and adds its own challenge,
+//! which the server answers the same way, so both sides prove they know the password.
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::io;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::time::timeout;
+
+use crate::settings::network_options::{
+    AUTHENTICATION_ACCEPTED, AUTHENTICATION_CHALLENGE, AUTHENTICATION_CHALLENGE_SIZE,
+    AUTHENTICATION_REJECTED, AUTHENTICATION_RESPONSE, AUTHENTICATION_TIMEOUT,
+};
+
+// Roles are part of the signed data, so response of one side cannot be replayed
+// as response of the other one.
+const SERVER_ROLE: &str = "server";
+const CLIENT_ROLE: &str = "client";
+
+fn create_challenge() -> String {
+    let mut challenge = [0u8; AUTHENTICATION_CHALLENGE_SIZE];
+    rand::thread_rng().fill_bytes(&mut challenge);
+
+    encode_hex(&challenge)
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn create_response(password: &str, role: &str, challenge: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(password.as_bytes())
+        .expect("HMAC accepts key of any length.");
+    mac.update(role.as_bytes());
+    mac.update(challenge.as_bytes());
+
+    mac
+}
+
+fn encode_response(password: &str, role: &str, challenge: &str) -> String {
+    encode_hex(
+        &create_response(password, role, challenge)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+// Compares in constant time.
+fn verify_response(password: &str, role: &str, challenge: &str, response: &str) -> bool {
+    let response = match (0..response.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(response.get(index..index + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()
+    {
+        Some(response) => response,
+        None => return false,
+    };
+
+    create_response(password, role, challenge)
+        .verify_slice(&response)
+        .is_ok()
+}
+
+fn rejected(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, reason)
+}
+
+async fn read_message<S: AsyncBufRead + Unpin>(stream: &mut S, prefix: &str) -> io::Result<String> {
+    let mut line = String::new();
+
+    match timeout(AUTHENTICATION_TIMEOUT, stream.read_line(&mut line)).await {
+        Err(_) => return Err(rejected("authentication timed out")),
+        Ok(Err(err)) => return Err(err),
+        Ok(Ok(0)) => return Err(rejected("connection closed during authentication")),
+        Ok(Ok(_)) => {}
+    }
+
+    let line = line.trim();
+
+    if line == AUTHENTICATION_REJECTED.trim() {
+        return Err(rejected("wrong password"));
+    }
+
+    match line.strip_prefix(prefix) {
+        Some(message) => Ok(message.to_string()),
+        None => Err(rejected("password not used by the other player")),
+    }
+}
+
+async fn write_message<S: AsyncWrite + Unpin>(stream: &mut S, message: String) -> io::Result<()> {
+    stream
+        .write_all(format!("{}\n", message).as_bytes())
+        .await?;
+    stream.flush().await
+}
+
+/// Verifies the connected client knows the password. Error kind is `PermissionDenied`
+/// when the client failed to prove it.
+pub async fn authenticate_client<S: AsyncBufRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    password: &str,
+) -> io::Result<()> {
+    let server_challenge = create_challenge();
+    write_message(
+        stream,
+        format!("{}{}", AUTHENTICATION_CHALLENGE, server_challenge),
+    )
+    .await?;
+
+    let message = read_message(stream, AUTHENTICATION_RESPONSE).await?;
+    let (response, client_challenge) = message
+        .split_once(' ')
+        .ok_or_else(|| rejected("malformed authentication response"))?;
+
+    if !verify_response(password, CLIENT_ROLE, &server_challenge, response) {
+        let _ = write_message(stream, AUTHENTICATION_REJECTED.to_string()).await;
+        return Err(rejected("wrong password"));
+    }
+
+    write_message(
+        stream,
+        format!(
+            "{}{}",
+            AUTHENTICATION_ACCEPTED,
+            encode_response(password, SERVER_ROLE, client_challenge)
+        ),
+    )
+    .await
+}
+
+/// Proves knowledge of the password to the server and verifies the server knows it too.
+pub async fn authenticate_to_server<S: AsyncBufRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    password: &str,
+) -> io::Result<()> {
+    let server_challenge = read_message(stream, AUTHENTICATION_CHALLENGE).await?;
+
+    let client_challenge = create_challenge();
+    write_message(
+        stream,
+        format!(
+            "{}{} {}",
+            AUTHENTICATION_RESPONSE,
+            encode_response(password, CLIENT_ROLE, &server_challenge),
+            client_challenge
+        ),
+    )
+    .await?;
+
+    let response = read_message(stream, AUTHENTICATION_ACCEPTED).await?;
+
+    if !verify_response(password, SERVER_ROLE, &client_challenge, &response) {
+        return Err(rejected("server does not know the password"));
+    }
+
+    Ok(())
+}
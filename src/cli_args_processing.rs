@@ -1,97 +1,379 @@
-use clap::App;
+use clap::{App, ArgMatches};
 use std::{net::IpAddr, str::FromStr};
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum HostType {
-    Server,
-    Client,
+use tic_tac_toe_game::game_options::{ConnectionOptions, GameVariant, HostType, StartOrder};
+use tic_tac_toe_game::q_learning::TrainingOptions;
+use tic_tac_toe_game::settings::ai_options::PUZZLE_PROGRESS_EXTENSION;
+use tic_tac_toe_game::tournament::{TournamentFormat, TournamentOptions};
+
+#[derive(Debug)]
+pub struct Arguments {
+    pub host_type: HostType,
+    pub port: Option<u16>,
+    /// Address server or hub listens on.
+    pub ip_addr: Option<IpAddr>,
+    /// IP address or host name client connects to.
+    pub host: Option<String>,
+    pub start_order: StartOrder,
+    pub variant: GameVariant,
+    pub connection_options: ConnectionOptions,
+    /// Port of the HTTP/JSON API, see rest_api of the library.
+    pub api_port: Option<u16>,
+    /// Address of the API, localhost by default as the API has no authentication.
+    pub api_ip_addr: Option<IpAddr>,
+    /// Command running external engine, see engine_player of the library.
+    pub engine: Option<String>,
+    /// Tournament run by the hub instead of pairing players into single games.
+    pub tournament: Option<TournamentOptions>,
+    pub line_mode: bool,
 }
 
-// Strum macros not used due to case insensitiveness.
-impl FromStr for HostType {
-    type Err = ();
+pub struct ArenaArguments {
+    /// Built-in player names or engine commands.
+    pub players: [String; 2],
+    pub games: u32,
+    pub variant: GameVariant,
+    pub csv_path: Option<String>,
+    pub records_path: Option<String>,
+}
 
-    fn from_str(host_type: &str) -> Result<HostType, Self::Err> {
-        // Do not respect letter case.
-        match host_type.to_lowercase().as_str() {
-            "server" => Ok(HostType::Server),
-            "client" => Ok(HostType::Client),
-            _ => Err(()),
-        }
-    }
+pub struct BookArguments {
+    pub size: usize,
+    /// Positions with this many symbols or more are left out.
+    pub max_plies: Option<usize>,
+    pub path: String,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum StartOrder {
-    First,
-    Second,
+pub struct TrainArguments {
+    pub options: TrainingOptions,
+    pub path: String,
 }
 
-// Strum macros not used due to case insensitiveness.
-impl FromStr for StartOrder {
-    type Err = ();
+pub struct PuzzleArguments {
+    pub path: String,
+    /// Solved puzzles are kept in this file.
+    pub progress_path: String,
+}
 
-    fn from_str(start_order: &str) -> Result<StartOrder, Self::Err> {
-        match start_order.to_lowercase().as_str() {
-            "first" => Ok(StartOrder::First),
-            "second" => Ok(StartOrder::Second),
-            _ => Err(()),
-        }
+/// What the application was asked to do.
+pub enum CliCommand {
+    Play(Arguments),
+    Arena(ArenaArguments),
+    /// Built-in player and its options served over engine protocol.
+    Engine(String),
+    Book(BookArguments),
+    Train(TrainArguments),
+    Puzzle(PuzzleArguments),
+}
+
+fn print_error(element: &str) -> String {
+    format!("{} is not specified correctly. Use -h for help.", element)
+}
+
+fn process_variant(matches: &ArgMatches) -> GameVariant {
+    match GameVariant::from_str(matches.value_of("variant").unwrap_or("classic")) {
+        Ok(variant) => variant,
+        Err(_) => panic!("{}", print_error("Game variant")),
     }
 }
 
-#[derive(Debug)]
-pub struct Arguments {
-    pub host_type: HostType,
-    pub port: Option<u16>,
-    pub ip_addr: Option<IpAddr>,
-    pub start_order: StartOrder,
+pub fn prompt_password() -> String {
+    rpassword::prompt_password("Game password: ")
+        .unwrap_or_else(|err| panic!("Password could not be read: {}", err))
 }
 
-pub fn process_cli_arguments() -> Arguments {
+pub fn process_cli_arguments() -> CliCommand {
     let yaml = load_yaml!("settings/cli.yaml");
     let matches = App::from_yaml(yaml).get_matches();
 
-    fn print_error(element: &str) -> String {
-        format!("{} is not specified correctly. Use -h for help.", element)
+    match matches.subcommand() {
+        Some(("arena", arena_matches)) => CliCommand::Arena(process_arena_arguments(arena_matches)),
+        // Player is required by clap.
+        Some(("engine", engine_matches)) => CliCommand::Engine(
+            engine_matches
+                .values_of("player")
+                .unwrap()
+                .collect::<Vec<&str>>()
+                .join(" "),
+        ),
+        Some(("book", book_matches)) => CliCommand::Book(process_book_arguments(book_matches)),
+        Some(("train", train_matches)) => CliCommand::Train(process_train_arguments(train_matches)),
+        Some(("puzzle", puzzle_matches)) => {
+            CliCommand::Puzzle(process_puzzle_arguments(puzzle_matches))
+        }
+        _ => CliCommand::Play(process_play_arguments(&matches)),
     }
+}
 
-    let host_type: HostType = match HostType::from_str(matches.value_of("hostType").unwrap()) {
-        Ok(host_type) => host_type,
-        Err(_) => panic!("{}", print_error("Host type")),
+fn process_arena_arguments(matches: &ArgMatches) -> ArenaArguments {
+    let games: u32 = match matches.value_of("games").map(str::parse::<u32>) {
+        None => 100,
+        Some(Ok(games)) if games > 0 => games,
+        Some(_) => panic!("{}", print_error("Number of games")),
     };
 
-    let port: Option<u16> = match matches.value_of("port").unwrap_or("_").parse::<u16>() {
-        Ok(value) => Some(value),
-        Err(_) => None,
+    // Both players are required by clap.
+    ArenaArguments {
+        players: ["player1", "player2"].map(|player| matches.value_of(player).unwrap().to_string()),
+        games,
+        variant: process_variant(matches),
+        csv_path: matches.value_of("csv").map(str::to_string),
+        records_path: matches.value_of("records").map(str::to_string),
+    }
+}
+
+fn process_book_arguments(matches: &ArgMatches) -> BookArguments {
+    let size: usize = match matches.value_of("size").map(str::parse::<usize>) {
+        None => 3,
+        Some(Ok(size)) => size,
+        Some(_) => panic!("{}", print_error("Board size")),
     };
 
-    if host_type != HostType::Server && port == None {
-        panic!("{}", print_error("Port"))
+    let max_plies = match matches.value_of("plies").map(str::parse::<usize>) {
+        None => None,
+        Some(Ok(plies)) if plies > 0 => Some(plies),
+        Some(_) => panic!("{}", print_error("Number of plies")),
+    };
+
+    // Output is required by clap.
+    BookArguments {
+        size,
+        max_plies,
+        path: matches.value_of("output").unwrap().to_string(),
+    }
+}
+
+// Rates and probabilities are from 0 to 1.
+fn process_fraction(matches: &ArgMatches, name: &str, element: &str, default: f64) -> f64 {
+    match matches.value_of(name).map(str::parse::<f64>) {
+        None => default,
+        Some(Ok(fraction)) if (0.0..=1.0).contains(&fraction) => fraction,
+        Some(_) => panic!("{}", print_error(element)),
     }
+}
+
+fn process_train_arguments(matches: &ArgMatches) -> TrainArguments {
+    let defaults = TrainingOptions::default();
+
+    let episodes: u32 = match matches.value_of("episodes").map(str::parse::<u32>) {
+        None => defaults.episodes,
+        Some(Ok(episodes)) if episodes > 0 => episodes,
+        Some(_) => panic!("{}", print_error("Number of episodes")),
+    };
+
+    let seed = match matches.value_of("seed").map(str::parse::<u64>) {
+        None => None,
+        Some(Ok(seed)) => Some(seed),
+        Some(_) => panic!("{}", print_error("Seed")),
+    };
+
+    // Output is required by clap.
+    TrainArguments {
+        options: TrainingOptions {
+            episodes,
+            learning_rate: process_fraction(
+                matches,
+                "learningRate",
+                "Learning rate",
+                defaults.learning_rate,
+            ),
+            discount: process_fraction(matches, "discount", "Discount", defaults.discount),
+            exploration: process_fraction(
+                matches,
+                "exploration",
+                "Exploration",
+                defaults.exploration,
+            ),
+            seed,
+        },
+        path: matches.value_of("output").unwrap().to_string(),
+    }
+}
 
-    let ip_addr: Option<IpAddr> = match matches.value_of("ipAddr").unwrap_or("_").parse::<IpAddr>()
+fn process_puzzle_arguments(matches: &ArgMatches) -> PuzzleArguments {
+    // Puzzle file is required by clap.
+    let path = matches.value_of("puzzles").unwrap().to_string();
+
+    PuzzleArguments {
+        progress_path: matches
+            .value_of("progress")
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}{}", path, PUZZLE_PROGRESS_EXTENSION)),
+        path,
+    }
+}
+
+fn process_tournament_options(
+    matches: &ArgMatches,
+    format: &str,
+    variant: GameVariant,
+) -> TournamentOptions {
+    let format: TournamentFormat = match TournamentFormat::from_str(format) {
+        Ok(format) => format,
+        Err(_) => panic!("{}", print_error("Tournament format")),
+    };
+
+    let players: usize = match matches
+        .value_of("tournamentPlayers")
+        .map(str::parse::<usize>)
     {
-        Ok(value) => Some(value),
-        Err(_) => None,
+        Some(Ok(players)) if players >= 2 => players,
+        _ => panic!("{}", print_error("Number of tournament players")),
+    };
+
+    let best_of: u32 = match matches.value_of("bestOf").map(str::parse::<u32>) {
+        None => 3,
+        Some(Ok(best_of)) if best_of > 0 => best_of,
+        Some(_) => panic!("{}", print_error("Number of games of the match")),
+    };
+
+    TournamentOptions {
+        format,
+        players,
+        best_of,
+        variant,
+    }
+}
+
+fn process_play_arguments(matches: &ArgMatches) -> Arguments {
+    let host_type: HostType = match HostType::from_str(matches.value_of("hostType").unwrap()) {
+        Ok(host_type) => host_type,
+        Err(_) => panic!("{}", print_error("Host type")),
     };
 
-    if host_type == HostType::Client && ip_addr == None {
-        panic!("{}", print_error("Ip address"))
-    } else if host_type == HostType::Server && ip_addr != None {
-        println!("Ip address specified for server will be ignored. Invalid option.")
+    let port: Option<u16> = matches.value_of("port").unwrap_or("_").parse::<u16>().ok();
+
+    if host_type == HostType::Client && port.is_none() {
+        panic!("{}", print_error("Port"))
     }
 
-    let start_order: StartOrder =
-        match StartOrder::from_str(matches.value_of("startOrder").unwrap()) {
+    let address: Option<&str> = matches.value_of("ipAddr");
+
+    // Client resolves host names itself, listeners need literal address.
+    let (ip_addr, host): (Option<IpAddr>, Option<String>) = match host_type {
+        HostType::Client => match address {
+            Some(address) => (None, Some(address.to_string())),
+            None => panic!("{}", print_error("Ip address")),
+        },
+        HostType::Discover | HostType::Engine => {
+            if address.is_some() {
+                println!(
+                    "Ip address specified for {} will be ignored. Invalid option.",
+                    matches.value_of("hostType").unwrap()
+                )
+            }
+            (None, None)
+        }
+        HostType::Server | HostType::Hub => match address.map(str::parse::<IpAddr>) {
+            Some(Ok(ip_addr)) => (Some(ip_addr), None),
+            Some(Err(_)) => panic!("{}", print_error("Ip address")),
+            None => (None, None),
+        },
+    };
+
+    // Hub does not play, discovered game decides the order by itself.
+    let start_order: StartOrder = match matches.value_of("startOrder") {
+        None if host_type == HostType::Hub || host_type == HostType::Discover => StartOrder::First,
+        start_order => match StartOrder::from_str(start_order.unwrap_or_default()) {
             Ok(start_order) => start_order,
             Err(_) => panic!("{}", print_error("Start order")),
-        };
+        },
+    };
+
+    let web_port: Option<u16> = match matches.value_of("webPort") {
+        None => None,
+        Some(_) if host_type != HostType::Server => {
+            println!("Web port can be specified only for server, it will be ignored.");
+            None
+        }
+        Some(web_port) => match web_port.parse::<u16>() {
+            Ok(web_port) => Some(web_port),
+            Err(_) => panic!("{}", print_error("Web port")),
+        },
+    };
+
+    let api_port: Option<u16> = match matches.value_of("apiPort") {
+        None => None,
+        Some(_) if cfg!(not(feature = "rest-api")) => {
+            println!("Built without rest-api feature, API port will be ignored.");
+            None
+        }
+        Some(_) if host_type == HostType::Hub => {
+            println!("Hub keeps no games, API port will be ignored.");
+            None
+        }
+        Some(api_port) => match api_port.parse::<u16>() {
+            Ok(api_port) => Some(api_port),
+            Err(_) => panic!("{}", print_error("API port")),
+        },
+    };
+
+    let api_ip_addr: Option<IpAddr> = match matches.value_of("apiIpAddr") {
+        None => None,
+        Some(_) if api_port.is_none() => {
+            println!("API is not served, API ip address will be ignored.");
+            None
+        }
+        Some(api_ip_addr) => match api_ip_addr.parse::<IpAddr>() {
+            Ok(api_ip_addr) => Some(api_ip_addr),
+            Err(_) => panic!("{}", print_error("API ip address")),
+        },
+    };
+
+    let engine: Option<String> = match matches.value_of("engine") {
+        None if host_type == HostType::Engine => panic!("{}", print_error("Engine")),
+        Some(_) if host_type == HostType::Hub => {
+            println!("Hub does not play, engine will be ignored.");
+            None
+        }
+        engine => engine.map(str::to_string),
+    };
+
+    let variant: GameVariant = process_variant(matches);
+
+    let tournament: Option<TournamentOptions> = match matches.value_of("tournament") {
+        None => None,
+        Some(_) if host_type != HostType::Hub => {
+            println!("Tournament can be run only by hub, it will be ignored.");
+            None
+        }
+        Some(format) => Some(process_tournament_options(matches, format, variant)),
+    };
+
+    let password: Option<String> = match matches.value_of("password") {
+        _ if !matches.is_present("password") => None,
+        Some(password) => Some(password.to_string()),
+        None => Some(prompt_password()),
+    };
+
+    if password.as_deref() == Some("") {
+        panic!("{}", print_error("Password"))
+    }
 
     Arguments {
         host_type,
         port,
         ip_addr,
+        host,
         start_order,
+        variant,
+        connection_options: ConnectionOptions {
+            tls: matches.is_present("tls"),
+            password,
+            game_name: matches
+                .value_of("name")
+                .unwrap_or("Tic tac toe game")
+                .to_string(),
+            nickname: match matches.value_of("nickname") {
+                Some(nickname) => nickname.to_string(),
+                None => std::env::var("USER").unwrap_or_else(|_| "player".to_string()),
+            },
+            web_port,
+        },
+        api_port,
+        api_ip_addr,
+        engine,
+        tournament,
+        line_mode: matches.is_present("lineMode"),
     }
 }
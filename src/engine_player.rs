@@ -0,0 +1,614 @@
+//! External engine playing instead of the local player.
+//!
+//! Engine is any program speaking the line based engine protocol on its stdin and
+//! stdout:
+//!
+//! - `newgame <variant> <first|second>` starts a new game, engine plays given order.
+//! - `position <moves>` sets moves of the current game, which are game commands
+//!   separated by `; `, e.g. `position /place (1, 1); /place (2, 2)`. Places alternate
+//!   starting with the first player, collapse is played by the player on turn before
+//!   their place.
+//! - `go` asks for a move, engine answers `bestmove <command>`, e.g.
+//!   `bestmove /place (3, 3)`.
+//! - `quit` ends the engine.
+//!
+//! Engine may write `chat <message>` any time, the message is sent to the oponent by
+//! `/pm`. Other lines written by the engine are ignored, so it may log to stdout freely.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::str::FromStr;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::ai_player::{get_move_lines, AiPlayer};
+use crate::game_event::{GameEvent, GameEventHandler, Player};
+use crate::game_options::{GameVariant, StartOrder};
+use crate::game_session::{GameStatus, SharedGameSession};
+use crate::host_type_objects::{CommandSender, HostTypeObject, NetworkGame};
+use crate::settings::commands::{
+    AGREE_COMMAND, COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND, PRIVATE_MESSAGE_COMMAND,
+};
+use crate::settings::engine_protocol::*;
+
+enum EngineInput {
+    Attach(SharedGameSession, CommandSender),
+    Event(GameEvent),
+    // Line written by the engine.
+    Line(String),
+    Exited,
+    Stop,
+}
+
+/// Position of the game in engine protocol, moves without the `position` keyword.
+pub fn format_engine_position(moves: &[String]) -> String {
+    moves
+        .iter()
+        .map(|game_move| game_move.replace(';', ","))
+        .collect::<Vec<String>>()
+        .join(ENGINE_MOVE_SEPARATOR)
+}
+
+/// Line setting the position, `position` alone when there are no moves yet.
+pub fn format_position_line(moves: &[String]) -> String {
+    if moves.is_empty() {
+        ENGINE_POSITION.trim_end().to_string()
+    } else {
+        format!("{}{}", ENGINE_POSITION, format_engine_position(moves))
+    }
+}
+
+/// Moves of the position line, None when the line does not set the position.
+pub fn parse_position_line(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+
+    let position = match line.strip_prefix(ENGINE_POSITION) {
+        Some(position) => position,
+        None if line == ENGINE_POSITION.trim_end() => "",
+        None => return None,
+    };
+
+    Some(
+        position
+            .split(ENGINE_MOVE_SEPARATOR.trim())
+            .map(|game_move| game_move.trim().to_string())
+            .filter(|game_move| !game_move.is_empty())
+            .collect(),
+    )
+}
+
+fn format_new_game_line(variant: GameVariant, start_order: StartOrder) -> String {
+    format!("{}{} {}", ENGINE_NEW_GAME, variant, start_order)
+}
+
+fn write_chat(player: &mut dyn AiPlayer, output: &mut impl Write) -> io::Result<()> {
+    if let Some(chat) = player.take_chat() {
+        writeln!(output, "{}{}", ENGINE_CHAT, chat)?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Answers the engine protocol read from input by moves of the player, so built-in
+/// players may be run as engines. Ends on `quit` or end of input.
+pub fn serve_engine_protocol(
+    player: &mut dyn AiPlayer,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut moves: Vec<String> = Vec::new();
+
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if let Some(new_game) = line.strip_prefix(ENGINE_NEW_GAME) {
+            let mut options = new_game.split_whitespace();
+            let variant = options
+                .next()
+                .and_then(|variant| GameVariant::from_str(variant).ok())
+                .unwrap_or(GameVariant::Classic);
+            let start_order = options
+                .next()
+                .and_then(|start_order| StartOrder::from_str(start_order).ok())
+                .unwrap_or(StartOrder::First);
+
+            player.new_game(variant, start_order)?;
+            moves.clear();
+            write_chat(player, &mut output)?;
+        } else if let Some(position) = parse_position_line(line) {
+            moves = position;
+        } else if line == ENGINE_GO {
+            // Engine without move stays silent, the game reports it.
+            if let Ok(best_move) = player.choose_move(&moves) {
+                write_chat(player, &mut output)?;
+                writeln!(output, "{}{}", ENGINE_BEST_MOVE, best_move)?;
+                output.flush()?;
+            }
+        } else if line == ENGINE_QUIT {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// Command is split on whitespace into program and its arguments.
+fn spawn_engine_process(command: &str) -> io::Result<(Child, ChildStdin, ChildStdout)> {
+    let mut args = command.split_whitespace();
+    let program = args
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "engine command is empty"))?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    // Both are piped above.
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+
+    Ok((child, stdin, stdout))
+}
+
+// Engine is killed when it does not quit in time.
+fn quit_engine_process(mut child: Child, mut stdin: ChildStdin) {
+    let _ = writeln!(stdin, "{}", ENGINE_QUIT).and_then(|_| stdin.flush());
+    drop(stdin);
+
+    let deadline = Instant::now() + ENGINE_QUIT_TIMEOUT;
+
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            _ => return,
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// State of the thread talking to the engine.
+struct EngineDriver {
+    child: Child,
+    stdin: ChildStdin,
+    variant: GameVariant,
+    start_order: StartOrder,
+    game: Option<(SharedGameSession, CommandSender)>,
+    // Position the engine was asked about, it is not asked again until the game moves.
+    asked_position: Option<String>,
+    thinking: bool,
+    // Move of the engine not confirmed by the session yet.
+    played_move: Option<String>,
+    failed: bool,
+    event_handler: GameEventHandler,
+}
+
+impl EngineDriver {
+    fn run(mut self, inputs: Receiver<EngineInput>) {
+        while let Ok(input) = inputs.recv() {
+            match input {
+                EngineInput::Attach(arc_session, command_sender) => {
+                    self.game = Some((arc_session, command_sender));
+                    self.start_new_game();
+                }
+                EngineInput::Event(event) => self.handle_event(event),
+                EngineInput::Line(line) => self.handle_line(&line),
+                EngineInput::Exited => {
+                    self.fail("engine exited".to_string());
+                }
+                EngineInput::Stop => break,
+            }
+
+            self.ask_for_move();
+        }
+
+        self.quit();
+    }
+
+    fn handle_event(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::GameRestarted => self.start_new_game(),
+            GameEvent::GameAssigned { start_order } => {
+                self.start_order = start_order;
+                self.start_new_game();
+            }
+            GameEvent::NewGameRequested {
+                player: Player::Oponent,
+            } => self.send_command(AGREE_COMMAND.to_string()),
+            GameEvent::Placed {
+                player: Player::You,
+                ..
+            }
+            | GameEvent::Collapsed {
+                player: Player::You,
+                ..
+            } => self.played_move = None,
+            GameEvent::InvalidMove(msg) => {
+                if let Some(played_move) = self.played_move.take() {
+                    self.fail(format!("invalid move {}: {}", played_move, msg));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) {
+        if let Some(chat) = line.trim().strip_prefix(ENGINE_CHAT) {
+            self.send_command(format!("{}{}", PRIVATE_MESSAGE_COMMAND, chat.trim()));
+            return;
+        }
+
+        let played_move = match line.trim().strip_prefix(ENGINE_BEST_MOVE) {
+            Some(played_move) if self.thinking => played_move.trim().to_string(),
+            _ => return,
+        };
+
+        self.thinking = false;
+
+        // Game moved on (e.g. restarted) while the engine was thinking.
+        if self.get_position() != self.asked_position {
+            self.asked_position = None;
+            return;
+        }
+
+        if !played_move.starts_with(PLACE_ON_PLAYBOARD_COMMAND)
+            && !played_move.starts_with(COLLAPSE_COMMAND)
+        {
+            self.fail(format!("{} is not a move", played_move));
+            return;
+        }
+
+        self.played_move = Some(played_move.clone());
+        self.send_command(played_move);
+    }
+
+    fn get_position(&self) -> Option<String> {
+        let (arc_session, _) = self.game.as_ref()?;
+        let session = arc_session.lock().unwrap();
+
+        Some(format_position_line(&get_move_lines(session.get_history())))
+    }
+
+    fn start_new_game(&mut self) {
+        self.asked_position = None;
+        self.played_move = None;
+
+        let line = format_new_game_line(self.variant, self.start_order);
+        self.write_line(&line);
+    }
+
+    fn ask_for_move(&mut self) {
+        if self.failed || self.thinking {
+            return;
+        }
+
+        let status = match &self.game {
+            Some((arc_session, _)) => arc_session.lock().unwrap().get_game_status(),
+            None => return,
+        };
+
+        if !status.my_turn || !status.game_running || !status.connected {
+            return;
+        }
+
+        let position = self.get_position();
+        if position == self.asked_position {
+            return;
+        }
+
+        if let Some(line) = &position {
+            self.write_line(line);
+        }
+        self.write_line(ENGINE_GO);
+        self.asked_position = position;
+        self.thinking = true;
+    }
+
+    fn send_command(&self, command: String) {
+        if let Some((_, command_sender)) = &self.game {
+            command_sender(command);
+        }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.failed {
+            return;
+        }
+
+        let written = writeln!(self.stdin, "{}", line).and_then(|_| self.stdin.flush());
+
+        if let Err(err) = written {
+            self.fail(format!("writing to engine failed: {}", err));
+        }
+    }
+
+    // Engine is not asked anything more, the game waits for the local player.
+    fn fail(&mut self, msg: String) {
+        if !self.failed {
+            self.failed = true;
+            (self.event_handler)(GameEvent::EngineFailed(msg));
+        }
+    }
+
+    fn quit(self) {
+        quit_engine_process(self.child, self.stdin);
+    }
+}
+
+/// External engine process making moves of the local player. It plays in any game it is
+/// attached to, over the network as well as in [`EngineGame`].
+pub struct EnginePlayer {
+    input_sender: Sender<EngineInput>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EnginePlayer {
+    /// Starts the engine. Command is split on whitespace into program and its
+    /// arguments. Failures of the engine are passed to `event_handler`.
+    pub fn spawn(
+        command: &str,
+        variant: GameVariant,
+        start_order: StartOrder,
+        event_handler: GameEventHandler,
+    ) -> io::Result<Self> {
+        let (child, stdin, stdout) = spawn_engine_process(command)?;
+
+        let (input_sender, inputs) = channel();
+
+        // Reads the engine, so the driver never blocks on it.
+        let line_sender = input_sender.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                match line {
+                    Ok(line) => {
+                        if line_sender.send(EngineInput::Line(line)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            let _ = line_sender.send(EngineInput::Exited);
+        });
+
+        let driver = EngineDriver {
+            child,
+            stdin,
+            variant,
+            start_order,
+            game: None,
+            asked_position: None,
+            thinking: false,
+            played_move: None,
+            failed: false,
+            event_handler,
+        };
+        let thread = thread::spawn(move || driver.run(inputs));
+
+        Ok(Self {
+            input_sender,
+            thread: Some(thread),
+        })
+    }
+
+    /// Event handler of the game the engine plays, events are passed also to the wrapped
+    /// handler.
+    pub fn wrap_event_handler(&self, event_handler: GameEventHandler) -> GameEventHandler {
+        let input_sender = self.input_sender.clone();
+
+        Arc::new(move |event: GameEvent| {
+            let _ = input_sender.send(EngineInput::Event(event.clone()));
+            event_handler(event);
+        })
+    }
+
+    /// Lets the engine play the game, whose events go through [`Self::wrap_event_handler`].
+    pub fn attach(&self, host_type_object: &dyn HostTypeObject) {
+        let _ = self.input_sender.send(EngineInput::Attach(
+            host_type_object.get_session(),
+            host_type_object.get_command_sender(),
+        ));
+    }
+
+    /// Asks the engine to quit and waits for it, it is killed when it does not quit in time.
+    pub fn stop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.input_sender.send(EngineInput::Stop);
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for EnginePlayer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Game of the local player against the engine, played within this process.
+pub struct EngineGame {
+    game: NetworkGame,
+    engine_game: NetworkGame,
+    engine_player: EnginePlayer,
+}
+
+impl EngineGame {
+    /// Starts the engine given by command, see [`EnginePlayer::spawn`]. Engine plays the
+    /// other start order than the local player.
+    pub fn start(
+        command: &str,
+        start_order: StartOrder,
+        variant: GameVariant,
+        event_handler: GameEventHandler,
+    ) -> io::Result<Self> {
+        let engine_player = EnginePlayer::spawn(
+            command,
+            variant,
+            start_order.oponent(),
+            event_handler.clone(),
+        )?;
+
+        let (game, engine_game) = NetworkGame::local_pair(
+            start_order,
+            variant,
+            event_handler,
+            engine_player.wrap_event_handler(Arc::new(|_| {})),
+        );
+        engine_player.attach(&engine_game);
+
+        Ok(Self {
+            game,
+            engine_game,
+            engine_player,
+        })
+    }
+}
+
+impl HostTypeObject for EngineGame {
+    fn get_session(&self) -> SharedGameSession {
+        self.game.get_session()
+    }
+
+    fn get_game_status(&self) -> GameStatus {
+        self.game.get_game_status()
+    }
+
+    fn send_message(&self, msg: String) {
+        self.game.send_message(msg);
+    }
+
+    fn get_command_sender(&self) -> CommandSender {
+        self.game.get_command_sender()
+    }
+
+    fn stop(&mut self) {
+        self.game.stop();
+        self.engine_game.stop();
+        self.engine_player.stop();
+    }
+}
+
+/// Engine asked for moves directly, for games played without network, e.g. by arena.
+pub struct EngineProcess {
+    command: String,
+    // Taken when the engine quits.
+    process: Option<(Child, ChildStdin)>,
+    // Lines are read by their own thread, so waiting for them may time out.
+    lines: Receiver<io::Result<String>>,
+    move_timeout: Duration,
+    // Latest chat line written by the engine.
+    chat: Option<String>,
+}
+
+impl EngineProcess {
+    /// Starts the engine, see [`EnginePlayer::spawn`].
+    pub fn spawn(command: &str) -> io::Result<Self> {
+        Self::spawn_with_move_timeout(command, ENGINE_MOVE_TIMEOUT)
+    }
+
+    /// Starts the engine which forfeits when it does not answer `go` in `move_timeout`.
+    pub fn spawn_with_move_timeout(command: &str, move_timeout: Duration) -> io::Result<Self> {
+        let (child, stdin, stdout) = spawn_engine_process(command)?;
+
+        let (line_sender, lines) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if line_sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Self {
+            command: command.to_string(),
+            process: Some((child, stdin)),
+            lines,
+            move_timeout,
+            chat: None,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        match &mut self.process {
+            Some((_, stdin)) => {
+                writeln!(stdin, "{}", line)?;
+                stdin.flush()
+            }
+            None => Err(io::Error::new(io::ErrorKind::BrokenPipe, "engine quit")),
+        }
+    }
+
+    fn quit(&mut self) {
+        if let Some((child, stdin)) = self.process.take() {
+            quit_engine_process(child, stdin);
+        }
+    }
+}
+
+impl AiPlayer for EngineProcess {
+    fn get_name(&self) -> String {
+        self.command.clone()
+    }
+
+    fn new_game(&mut self, variant: GameVariant, start_order: StartOrder) -> io::Result<()> {
+        self.write_line(&format_new_game_line(variant, start_order))
+    }
+
+    // Blocks until the engine answers or the move timeout expires.
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String> {
+        self.write_line(&format_position_line(moves))?;
+        self.write_line(ENGINE_GO)?;
+
+        let deadline = Instant::now() + self.move_timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line?,
+                // Late answer would be taken for the move of the next game, so the
+                // engine is not asked anything more.
+                Err(RecvTimeoutError::Timeout) => {
+                    self.quit();
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "engine did not answer in time",
+                    ));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "engine exited",
+                    ))
+                }
+            };
+
+            let line = line.trim();
+
+            if let Some(best_move) = line.strip_prefix(ENGINE_BEST_MOVE) {
+                return Ok(best_move.trim().to_string());
+            }
+            if let Some(chat) = line.strip_prefix(ENGINE_CHAT) {
+                self.chat = Some(chat.trim().to_string());
+            }
+        }
+    }
+
+    fn take_chat(&mut self) -> Option<String> {
+        self.chat.take()
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        self.quit();
+    }
+}
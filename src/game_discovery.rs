@@ -0,0 +1,188 @@
+//! Finding games on local network.
+//!
+//! Waiting server repeatedly sends [`GameAnnouncement`] over UDP to multicast group,
+//! broadcast address and loopback, clients listen for them on the discovery port.
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::time::interval;
+
+use crate::game_options::{GameVariant, StartOrder};
+use crate::settings::network_options::{
+    DISCOVERY_ANNOUNCE_INTERVAL, DISCOVERY_MESSAGE_PREFIX, DISCOVERY_MULTICAST_ADDR, DISCOVERY_PORT,
+};
+
+/// Game as announced by the server.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameAnnouncement {
+    pub game_name: String,
+    pub nickname: String,
+    pub port: u16,
+    pub variant: GameVariant,
+    /// Start order of the server player.
+    pub start_order: StartOrder,
+    /// False when the second player already connected.
+    pub open: bool,
+    pub tls: bool,
+    pub password: bool,
+}
+
+impl GameAnnouncement {
+    // Fields are separated by ';', names are written last so they may contain '='.
+    fn encode(&self) -> String {
+        let clean = |text: &str| text.replace(';', ",");
+
+        format!(
+            "{}port={};variant={};start_order={};state={};tls={};password={};game_name={};nickname={}",
+            DISCOVERY_MESSAGE_PREFIX,
+            self.port,
+            self.variant,
+            self.start_order,
+            if self.open { "open" } else { "full" },
+            self.tls as u8,
+            self.password as u8,
+            clean(&self.game_name),
+            clean(&self.nickname),
+        )
+    }
+
+    fn decode(message: &str) -> Option<Self> {
+        let fields: HashMap<&str, &str> = message
+            .strip_prefix(DISCOVERY_MESSAGE_PREFIX)?
+            .split(';')
+            .filter_map(|field| field.split_once('='))
+            .collect();
+
+        Some(Self {
+            game_name: fields.get("game_name")?.to_string(),
+            nickname: fields.get("nickname")?.to_string(),
+            port: fields.get("port")?.parse().ok()?,
+            variant: GameVariant::from_str(fields.get("variant")?).ok()?,
+            start_order: StartOrder::from_str(fields.get("start_order")?).ok()?,
+            open: *fields.get("state")? == "open",
+            tls: *fields.get("tls")? == "1",
+            password: *fields.get("password")? == "1",
+        })
+    }
+}
+
+/// Announcement received from the server with given address.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiscoveredGame {
+    pub ip_addr: IpAddr,
+    pub announcement: GameAnnouncement,
+}
+
+/// Announces the game until the task is dropped, `arc_open` tells whether the second
+/// player may still join. Announcements are sent from the address the game listens on,
+/// so discovering players connect to that one.
+pub async fn announce_game(
+    mut announcement: GameAnnouncement,
+    bind_ip_addr: IpAddr,
+    arc_open: Arc<AtomicBool>,
+) {
+    // Announcements are sent over IPv4 only.
+    let source_ip_addr = match bind_ip_addr {
+        IpAddr::V4(ip_addr) => ip_addr,
+        IpAddr::V6(_) => Ipv4Addr::UNSPECIFIED,
+    };
+
+    let socket = match tokio::net::UdpSocket::bind((source_ip_addr, 0)).await {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+    let _ = socket.set_broadcast(true);
+
+    let targets = [
+        SocketAddr::from((DISCOVERY_MULTICAST_ADDR, DISCOVERY_PORT)),
+        SocketAddr::from((Ipv4Addr::BROADCAST, DISCOVERY_PORT)),
+        SocketAddr::from((Ipv4Addr::LOCALHOST, DISCOVERY_PORT)),
+    ];
+
+    let mut timer = interval(DISCOVERY_ANNOUNCE_INTERVAL);
+
+    loop {
+        timer.tick().await;
+
+        announcement.open = arc_open.load(Ordering::Relaxed);
+        let message = announcement.encode();
+
+        // Some targets are unreachable on most networks, others still get it.
+        for target in targets {
+            let _ = socket.send_to(message.as_bytes(), target).await;
+        }
+    }
+}
+
+// More players on one machine may discover at the same time.
+fn bind_discovery_socket() -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT)).into())?;
+
+    let socket: UdpSocket = socket.into();
+    // Without multicast route only broadcast and loopback announcements arrive.
+    let _ = socket.join_multicast_v4(&DISCOVERY_MULTICAST_ADDR, &Ipv4Addr::UNSPECIFIED);
+
+    Ok(socket)
+}
+
+/// Listens for announcements for given time, returns every game once with its
+/// latest state.
+pub fn discover_games(duration: std::time::Duration) -> io::Result<Vec<DiscoveredGame>> {
+    let socket = bind_discovery_socket()?;
+    let deadline = Instant::now() + duration;
+
+    let mut games: Vec<DiscoveredGame> = Vec::new();
+    let mut buffer = [0u8; 1024];
+
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if remaining.is_zero() {
+            break;
+        }
+
+        socket.set_read_timeout(Some(remaining))?;
+
+        let (size, sender) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                break
+            }
+            Err(err) => return Err(err),
+        };
+
+        let announcement = match GameAnnouncement::decode(&String::from_utf8_lossy(&buffer[..size]))
+        {
+            Some(announcement) => announcement,
+            None => continue,
+        };
+
+        let game = DiscoveredGame {
+            ip_addr: sender.ip(),
+            announcement,
+        };
+
+        // Loopback announcement duplicates the network one of the local server.
+        match games.iter_mut().find(|known| {
+            known.announcement.port == game.announcement.port
+                && (known.ip_addr == game.ip_addr
+                    || known.ip_addr.is_loopback()
+                    || game.ip_addr.is_loopback())
+        }) {
+            Some(known) if game.ip_addr.is_loopback() => known.announcement = game.announcement,
+            Some(known) => *known = game,
+            None => games.push(game),
+        }
+    }
+
+    Ok(games)
+}
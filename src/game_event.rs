@@ -0,0 +1,92 @@
+//! Events through which the engine tells front-end what happened.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::game_options::StartOrder;
+use crate::playboard::GameState;
+
+/// Player from the local player's point of view.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "rest-api",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Player {
+    You,
+    Oponent,
+}
+
+impl Player {
+    pub fn other(self) -> Player {
+        match self {
+            Player::You => Player::Oponent,
+            Player::Oponent => Player::You,
+        }
+    }
+}
+
+/// Everything front-end may want to show to the player.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "rest-api",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum GameEvent {
+    /// Server listens and waits for the second player, who can reach it on any of
+    /// given addresses.
+    WaitingForOponent { ip_addrs: Vec<IpAddr>, port: u16 },
+    /// Server serves browser client on given port, the oponent may join from it.
+    WaitingForBrowserOponent { ip_addrs: Vec<IpAddr>, port: u16 },
+    /// Server uses TLS certificate with given fingerprint.
+    CertificateFingerprint { fingerprint: String },
+    /// Client accepted server certificate, `newly_pinned` when the host was unknown.
+    CertificateTrusted {
+        fingerprint: String,
+        newly_pinned: bool,
+    },
+    /// Server refused connection and keeps waiting for another one.
+    ConnectionRejected { addr: SocketAddr, reason: String },
+    /// Connection with the second player was established.
+    Connected { addr: SocketAddr },
+    /// Move was placed, `place` describes it in variant specific way.
+    Placed {
+        player: Player,
+        place: String,
+        game_state: GameState,
+    },
+    /// Entanglement cycle of quantum variant collapsed.
+    Collapsed { player: Player, place: String },
+    /// Game finished, winner is `None` on draw.
+    GameFinished { winner: Option<Player> },
+    /// Playboard changed, carries its line based rendering.
+    PlayboardChanged { printable: String },
+    /// Move was rejected, message explains why.
+    InvalidMove(String),
+    /// Local player tried to move out of turn.
+    NotYourTurn,
+    /// Local player tried to move in finished game.
+    GameNotRunning,
+    /// Chat message from the oponent.
+    PrivateMessage(String),
+    /// Player asked for a new game, the other one must agree.
+    NewGameRequested { player: Player },
+    /// Both players agreed and the playboard was cleared.
+    GameRestarted,
+    /// Hub started new game of tournament match, local player plays given start order.
+    GameAssigned { start_order: StartOrder },
+    /// Oponent closed the connection.
+    Disconnected,
+    /// Message could not be delivered to the oponent.
+    SendFailed,
+    /// External engine failed or broke the engine protocol, message explains how.
+    EngineFailed(String),
+    /// Game is being stopped.
+    Stopping,
+}
+
+/// Receives game events. It is called also from the thread reading the connection,
+/// so it must not block for long.
+pub type GameEventHandler = Arc<dyn Fn(GameEvent) + Send + Sync>;
@@ -0,0 +1,944 @@
+//! Server pairing any number of clients into games.
+//!
+//! Players queue for an oponent by `/queue`, see [`crate::matchmaking`]. Hub starts
+//! every game by `/newgame` with the start order of the player and referees it by
+//! sessions of both players, so only valid moves are relayed and results update
+//! ratings of the players. Players are back in the lobby after the game.
+//!
+//! Hub may run a [`crate::tournament`] instead. Players register by nickname and play
+//! matches of games started the same way.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinSet;
+use tokio::time::{sleep_until, timeout, Instant};
+
+use crate::game_event::GameEvent;
+use crate::game_options::{GameVariant, StartOrder};
+use crate::game_session::{GameSession, SessionEffect};
+use crate::host_type_objects_utility::{
+    accept_connection, bind_tcp_listener, get_bind_ip_addr, get_first_free_port,
+    get_reachable_ip_addrs,
+};
+use crate::matchmaking::{update_ratings, MatchQueue, QueueEntry, QueueRequest, INITIAL_RATING};
+use crate::settings::commands::{
+    COLLAPSE_COMMAND, LEAVE_COMMAND, NEW_GAME_COMMAND, PLACE_ON_PLAYBOARD_COMMAND,
+    PRIVATE_MESSAGE_COMMAND, QUEUE_COMMAND, REGISTER_COMMAND,
+};
+use crate::settings::network_options::{
+    HUB_MOVE_TIMEOUT, HUB_WAITING_TIMEOUT, LOBBY_REQUEST_BUFFER_SIZE, TOURNAMENT_TIEBREAK_GAMES,
+};
+use crate::tournament::{MatchResult, Pairing, Tournament, TournamentFormat, TournamentOptions};
+
+/// Everything hub operator may want to see.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HubEvent {
+    Listening {
+        ip_addrs: Vec<IpAddr>,
+        port: u16,
+    },
+    PlayerConnected {
+        addr: SocketAddr,
+    },
+    PlayerQueued {
+        nickname: String,
+        /// Arguments of the queue command.
+        request: String,
+    },
+    PlayerLeftQueue {
+        nickname: String,
+    },
+    /// Nobody joined the queued player in time.
+    PlayerTimedOut {
+        addr: SocketAddr,
+    },
+    GameStarted {
+        first: SocketAddr,
+        second: SocketAddr,
+    },
+    GameFinished {
+        first: SocketAddr,
+        second: SocketAddr,
+        /// Winner and new ratings of the players.
+        result: String,
+    },
+    /// Hub waits for players of the tournament.
+    RegistrationOpened {
+        format: TournamentFormat,
+        players: usize,
+    },
+    PlayerRegistered {
+        nickname: String,
+        addr: SocketAddr,
+    },
+    RoundStarted {
+        round: u32,
+        pairings: Vec<String>,
+    },
+    MatchFinished {
+        result: String,
+    },
+    /// Standings or bracket after the round, one line each.
+    TournamentStandings {
+        lines: Vec<String>,
+    },
+    TournamentFinished {
+        winner: Option<String>,
+    },
+}
+
+pub type HubEventHandler = Arc<dyn Fn(HubEvent) + Send + Sync>;
+
+async fn send_hub_message<W: AsyncWriteExt + Unpin>(stream: &mut W, msg: &str) -> io::Result<()> {
+    stream
+        .write_all(format!("{}{}\n", PRIVATE_MESSAGE_COMMAND, msg).as_bytes())
+        .await
+}
+
+type PlayerReader = Lines<BufReader<OwnedReadHalf>>;
+
+struct HubPlayer {
+    // Identifier of the connection, nickname may change.
+    id: u64,
+    nickname: String,
+    addr: SocketAddr,
+    reader: PlayerReader,
+    writer: OwnedWriteHalf,
+}
+
+// Players of the tournament by their index, None when disconnected.
+type TournamentSeats = Vec<Option<HubPlayer>>;
+
+enum GameOutcome {
+    // Index of the winner in the match, None on draw.
+    Finished(Option<usize>),
+    // Index of the player who disconnected.
+    Disconnected(usize),
+}
+
+async fn send_line(writer: &mut OwnedWriteHalf, line: &str) -> io::Result<()> {
+    writer.write_all(format!("{}\n", line).as_bytes()).await
+}
+
+async fn broadcast_hub_message(seats: &mut TournamentSeats, msg: &str) {
+    for player in seats.iter_mut().flatten() {
+        let _ = send_hub_message(&mut player.writer, msg).await;
+    }
+}
+
+// Waits for unique nickname of the player, who is then passed to the tournament.
+async fn register_player(
+    stream: TcpStream,
+    addr: SocketAddr,
+    id: u64,
+    nicknames: Arc<Mutex<HashSet<String>>>,
+    sender: mpsc::Sender<HubPlayer>,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader).lines();
+
+    let msg = format!(
+        "Tournament is open, register with {}<nickname>.",
+        REGISTER_COMMAND
+    );
+    if send_hub_message(&mut writer, &msg).await.is_err() {
+        return;
+    }
+
+    while let Ok(Some(line)) = reader.next_line().await {
+        let nickname = match line.trim().strip_prefix(REGISTER_COMMAND.trim()) {
+            Some(nickname) if !nickname.trim().is_empty() => nickname.trim().to_string(),
+            _ => {
+                let msg = format!("Register with {}<nickname> first.", REGISTER_COMMAND);
+                let _ = send_hub_message(&mut writer, &msg).await;
+                continue;
+            }
+        };
+
+        if !nicknames.lock().unwrap().insert(nickname.clone()) {
+            let msg = format!("Nickname {} is taken, choose another one.", nickname);
+            let _ = send_hub_message(&mut writer, &msg).await;
+            continue;
+        }
+
+        let player = HubPlayer {
+            id,
+            nickname,
+            addr,
+            reader,
+            writer,
+        };
+
+        // Tournament started meanwhile.
+        if let Err(mpsc::error::SendError(player)) = sender.send(player).await {
+            refuse_late_player(player).await;
+        }
+        return;
+    }
+}
+
+async fn refuse_late_player(mut player: HubPlayer) {
+    let msg = "Tournament already started, try again next time.";
+    let _ = send_hub_message(&mut player.writer, msg).await;
+}
+
+// Throws away lines sent before the game started, false when the player disconnected.
+async fn drain_lines(reader: &mut PlayerReader) -> bool {
+    loop {
+        match timeout(Duration::ZERO, reader.next_line()).await {
+            Ok(Ok(Some(_))) => {}
+            Ok(_) => return false,
+            Err(_) => return true,
+        }
+    }
+}
+
+fn get_start_order(player: usize, first: usize) -> StartOrder {
+    if player == first {
+        StartOrder::First
+    } else {
+        StartOrder::Second
+    }
+}
+
+// Lines of the player are checked by their own session and handled by the session of
+// the oponent as remote ones, the same way the arena plays.
+async fn play_match_game(
+    players: &mut [HubPlayer; 2],
+    first: usize,
+    variant: GameVariant,
+) -> GameOutcome {
+    let mut sessions =
+        [0, 1].map(|player| GameSession::new(get_start_order(player, first), variant));
+
+    for (index, player) in players.iter_mut().enumerate() {
+        let line = format!("{}{}", NEW_GAME_COMMAND, get_start_order(index, first));
+
+        if !drain_lines(&mut player.reader).await
+            || send_line(&mut player.writer, &line).await.is_err()
+        {
+            return GameOutcome::Disconnected(index);
+        }
+    }
+
+    let mut deadline = Instant::now() + HUB_MOVE_TIMEOUT;
+
+    loop {
+        let on_turn = (0..2)
+            .find(|&player| sessions[player].get_game_status().my_turn)
+            .unwrap_or(first);
+        let [first_player, second_player] = players;
+
+        let (index, line) = tokio::select! {
+            line = first_player.reader.next_line() => (0, line),
+            line = second_player.reader.next_line() => (1, line),
+            _ = sleep_until(deadline) => {
+                let msg = format!("{} did not move in time and lost the game.", players[on_turn].nickname);
+                for player in players.iter_mut() {
+                    let _ = send_hub_message(&mut player.writer, &msg).await;
+                }
+                return GameOutcome::Finished(Some(1 - on_turn));
+            }
+        };
+
+        let line = match line {
+            Ok(Some(line)) => line.trim().to_string(),
+            _ => return GameOutcome::Disconnected(index),
+        };
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if !line.starts_with(PRIVATE_MESSAGE_COMMAND)
+            && !line.starts_with(PLACE_ON_PLAYBOARD_COMMAND)
+            && !line.starts_with(COLLAPSE_COMMAND)
+        {
+            let msg = "Only moves and messages are relayed in tournament games.";
+            let _ = send_hub_message(&mut players[index].writer, msg).await;
+            continue;
+        }
+
+        if !line.starts_with(PRIVATE_MESSAGE_COMMAND) {
+            let mut rejected = Some("It is not your turn.".to_string());
+
+            for effect in sessions[index].handle_local(&line) {
+                match effect {
+                    SessionEffect::Send(line) => {
+                        sessions[1 - index].handle_remote(&line);
+                        rejected = None;
+                    }
+                    SessionEffect::Emit(GameEvent::InvalidMove(msg)) => rejected = Some(msg),
+                    SessionEffect::Emit(_) => {}
+                }
+            }
+
+            if let Some(msg) = rejected {
+                let _ = send_hub_message(&mut players[index].writer, &msg).await;
+                continue;
+            }
+
+            deadline = Instant::now() + HUB_MOVE_TIMEOUT;
+        }
+
+        if send_line(&mut players[1 - index].writer, &line)
+            .await
+            .is_err()
+        {
+            return GameOutcome::Disconnected(1 - index);
+        }
+
+        if !sessions[0].get_game_status().game_running {
+            return GameOutcome::Finished(
+                (0..2).find(|&player| sessions[player].get_game_status().score.wins > 0),
+            );
+        }
+    }
+}
+
+// What the matchmaker decided about the queued player.
+enum Assignment {
+    // Player hands the connection over to the oponent, who runs the game.
+    Join(oneshot::Sender<HubPlayer>),
+    // Player runs the game with the oponent, who waited shorter and plays second.
+    Host(oneshot::Receiver<HubPlayer>, GameVariant),
+    TimedOut,
+}
+
+enum LobbyRequest {
+    Queue(QueueEntry, oneshot::Sender<Assignment>),
+    Leave(u64),
+}
+
+// Shared by tasks of all players who are not in a tournament.
+#[derive(Clone)]
+struct Lobby {
+    nicknames: Arc<Mutex<HashSet<String>>>,
+    // Rating by nickname together with the connection which earned it, kept for the
+    // hub run so another connection cannot take over the nickname.
+    ratings: Arc<Mutex<HashMap<String, (u64, f64)>>>,
+    requests: mpsc::Sender<LobbyRequest>,
+    // Players back from the game of the other player, see run_hub_loop.
+    returning: mpsc::Sender<HubPlayer>,
+    event_handler: HubEventHandler,
+}
+
+impl Lobby {
+    fn get_rating(&self, nickname: &str) -> f64 {
+        self.ratings
+            .lock()
+            .unwrap()
+            .get(nickname)
+            .map_or(INITIAL_RATING, |(_, rating)| *rating)
+    }
+
+    fn is_rated_for_other(&self, player: &HubPlayer, nickname: &str) -> bool {
+        matches!(self.ratings.lock().unwrap().get(nickname), Some((id, _)) if *id != player.id)
+    }
+
+    fn format_player(&self, player: &HubPlayer) -> String {
+        format!(
+            "{} ({:.0})",
+            player.nickname,
+            self.get_rating(&player.nickname)
+        )
+    }
+
+    async fn register(&self, player: &mut HubPlayer, nickname: &str) {
+        let msg = if nickname.is_empty() {
+            format!("Register with {}<nickname>.", REGISTER_COMMAND)
+        } else if self.is_rated_for_other(player, nickname) {
+            format!(
+                "Nickname {} has rating of another player, choose another one.",
+                nickname
+            )
+        } else if !self.nicknames.lock().unwrap().insert(nickname.to_string()) {
+            format!("Nickname {} is taken, choose another one.", nickname)
+        } else {
+            self.nicknames.lock().unwrap().remove(&player.nickname);
+            player.nickname = nickname.to_string();
+
+            (self.event_handler)(HubEvent::PlayerRegistered {
+                nickname: player.nickname.clone(),
+                addr: player.addr,
+            });
+
+            format!("Registered as {}.", self.format_player(player))
+        };
+
+        let _ = send_hub_message(&mut player.writer, &msg).await;
+    }
+
+    fn disconnect(&self, player: &HubPlayer) {
+        self.nicknames.lock().unwrap().remove(&player.nickname);
+    }
+}
+
+// Pairs queued players, the one who waited longer hosts the game.
+async fn run_matchmaker(mut requests: mpsc::Receiver<LobbyRequest>) {
+    let mut queue = MatchQueue::default();
+    let mut waiting: HashMap<u64, (Instant, oneshot::Sender<Assignment>)> = HashMap::new();
+
+    loop {
+        let deadline = waiting.values().map(|(deadline, _)| *deadline).min();
+
+        tokio::select! {
+            request = requests.recv() => match request {
+                None => return,
+                Some(LobbyRequest::Queue(entry, assignment)) => loop {
+                    let oponent = match queue.join(entry) {
+                        Some(oponent) => oponent,
+                        None => {
+                            waiting.insert(entry.id, (Instant::now() + HUB_WAITING_TIMEOUT, assignment));
+                            break;
+                        }
+                    };
+
+                    // Host left meanwhile, the player is paired with the next one.
+                    let host = match waiting.remove(&oponent.id) {
+                        Some((_, host)) => host,
+                        None => continue,
+                    };
+                    let (sender, receiver) = oneshot::channel();
+
+                    if host.send(Assignment::Host(receiver, entry.variant)).is_ok() {
+                        let _ = assignment.send(Assignment::Join(sender));
+                        break;
+                    }
+                },
+                Some(LobbyRequest::Leave(id)) => {
+                    queue.leave(id);
+                    waiting.remove(&id);
+                }
+            },
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                let now = Instant::now();
+                let expired: Vec<u64> = waiting
+                    .iter()
+                    .filter(|(_, (deadline, _))| *deadline <= now)
+                    .map(|(id, _)| *id)
+                    .collect();
+
+                for id in expired {
+                    queue.leave(id);
+                    if let Some((_, assignment)) = waiting.remove(&id) {
+                        let _ = assignment.send(Assignment::TimedOut);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Plays one rated game, the host plays first. Returns the host when they are still
+// connected, the guest returns to the lobby by their own task.
+async fn host_lobby_game(
+    mut players: [HubPlayer; 2],
+    variant: GameVariant,
+    lobby: &Lobby,
+) -> Option<HubPlayer> {
+    for index in 0..2 {
+        let msg = format!(
+            "Matched with {}, {} game starts, you play {}.",
+            lobby.format_player(&players[1 - index]),
+            variant,
+            get_start_order(index, 0)
+        );
+        let _ = send_hub_message(&mut players[index].writer, &msg).await;
+    }
+
+    let addrs = [players[0].addr, players[1].addr];
+    (lobby.event_handler)(HubEvent::GameStarted {
+        first: addrs[0],
+        second: addrs[1],
+    });
+
+    let (winner, disconnected) = match play_match_game(&mut players, 0, variant).await {
+        GameOutcome::Finished(winner) => (winner, None),
+        GameOutcome::Disconnected(index) => (Some(1 - index), Some(index)),
+    };
+
+    let ratings = update_ratings(
+        [0, 1].map(|index| lobby.get_rating(&players[index].nickname)),
+        winner,
+    );
+    for (player, rating) in players.iter().zip(ratings) {
+        lobby
+            .ratings
+            .lock()
+            .unwrap()
+            .insert(player.nickname.clone(), (player.id, rating));
+    }
+
+    let result = format!(
+        "{}, ratings {} and {}",
+        match winner {
+            Some(winner) => format!("{} won", players[winner].nickname),
+            None => "draw".to_string(),
+        },
+        lobby.format_player(&players[0]),
+        lobby.format_player(&players[1])
+    );
+    let msg = format!(
+        "Game finished: {}. Queue again with {}.",
+        result, QUEUE_COMMAND
+    );
+
+    for player in players.iter_mut() {
+        let _ = send_hub_message(&mut player.writer, &msg).await;
+    }
+
+    (lobby.event_handler)(HubEvent::GameFinished {
+        first: addrs[0],
+        second: addrs[1],
+        result,
+    });
+
+    let [host, guest] = players;
+
+    if disconnected == Some(1) {
+        lobby.disconnect(&guest);
+    } else {
+        let _ = lobby.returning.send(guest).await;
+    }
+
+    if disconnected == Some(0) {
+        lobby.disconnect(&host);
+        return None;
+    }
+
+    Some(host)
+}
+
+// Waits in the queue until the player is paired, leaves it or disconnects. Returns
+// the player when they are back in the lobby.
+async fn queue_player(
+    mut player: HubPlayer,
+    request: QueueRequest,
+    lobby: &Lobby,
+) -> Option<HubPlayer> {
+    let entry = QueueEntry {
+        id: player.id,
+        rating: lobby.get_rating(&player.nickname),
+        variant: request.variant.unwrap_or(GameVariant::Classic),
+        rating_range: request.rating_range,
+    };
+    let (sender, mut assignment) = oneshot::channel();
+
+    if lobby
+        .requests
+        .send(LobbyRequest::Queue(entry, sender))
+        .await
+        .is_err()
+    {
+        return Some(player);
+    }
+
+    (lobby.event_handler)(HubEvent::PlayerQueued {
+        nickname: player.nickname.clone(),
+        request: request.to_string(),
+    });
+
+    let msg = format!(
+        "Waiting for oponent of {} game, {} leaves the queue.",
+        entry.variant, LEAVE_COMMAND
+    );
+    let _ = send_hub_message(&mut player.writer, &msg).await;
+
+    loop {
+        tokio::select! {
+            assigned = &mut assignment => {
+                let msg = match assigned {
+                    Ok(Assignment::Join(host)) => match host.send(player) {
+                        Ok(()) => return None,
+                        Err(returned) => {
+                            player = returned;
+                            "Oponent left the queue, queue again."
+                        }
+                    },
+                    Ok(Assignment::Host(guest, variant)) => match guest.await {
+                        Ok(guest) => return host_lobby_game([player, guest], variant, lobby).await,
+                        Err(_) => "Oponent left the queue, queue again.",
+                    },
+                    Ok(Assignment::TimedOut) | Err(_) => {
+                        (lobby.event_handler)(HubEvent::PlayerTimedOut { addr: player.addr });
+                        "Nobody joined, try again later."
+                    }
+                };
+
+                let _ = send_hub_message(&mut player.writer, msg).await;
+                return Some(player);
+            }
+            line = player.reader.next_line() => match line {
+                Ok(Some(line)) if line.trim() == LEAVE_COMMAND => {
+                    let _ = lobby.requests.send(LobbyRequest::Leave(player.id)).await;
+                    (lobby.event_handler)(HubEvent::PlayerLeftQueue {
+                        nickname: player.nickname.clone(),
+                    });
+
+                    let _ = send_hub_message(&mut player.writer, "You left the queue.").await;
+                    return Some(player);
+                }
+                Ok(Some(_)) => {
+                    let msg = format!("You are in the queue, {} leaves it.", LEAVE_COMMAND);
+                    let _ = send_hub_message(&mut player.writer, &msg).await;
+                }
+                _ => {
+                    let _ = lobby.requests.send(LobbyRequest::Leave(player.id)).await;
+                    lobby.disconnect(&player);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+// Handles commands of the player who is neither queued nor playing.
+async fn run_lobby_player(mut player: HubPlayer, lobby: Lobby) {
+    while let Ok(Some(line)) = player.reader.next_line().await {
+        let line = line.trim();
+
+        if let Some(nickname) = line.strip_prefix(REGISTER_COMMAND.trim()) {
+            lobby.register(&mut player, nickname.trim()).await;
+        } else if let Some(arguments) = line.strip_prefix(QUEUE_COMMAND) {
+            match QueueRequest::from_str(arguments) {
+                Ok(request) => match queue_player(player, request, &lobby).await {
+                    Some(returned) => player = returned,
+                    None => return,
+                },
+                Err(msg) => {
+                    let _ = send_hub_message(&mut player.writer, &msg).await;
+                }
+            }
+        } else if !line.is_empty() {
+            let msg = format!(
+                "Queue for oponent with {} first, {} is not in the queue.",
+                QUEUE_COMMAND, player.nickname
+            );
+            let _ = send_hub_message(&mut player.writer, &msg).await;
+        }
+    }
+
+    lobby.disconnect(&player);
+}
+
+async fn welcome_player(mut player: HubPlayer, lobby: Lobby) {
+    let msg = format!(
+        "Welcome {}, queue for oponent with {} [variant] [min-max rating], keep your rating with {}<nickname> until you disconnect.",
+        player.nickname, QUEUE_COMMAND, REGISTER_COMMAND
+    );
+
+    if send_hub_message(&mut player.writer, &msg).await.is_ok() {
+        run_lobby_player(player, lobby).await;
+    } else {
+        lobby.disconnect(&player);
+    }
+}
+
+async fn run_hub_loop(listener: TcpListener, event_handler: HubEventHandler) -> io::Result<()> {
+    let (requests, request_receiver) = mpsc::channel(LOBBY_REQUEST_BUFFER_SIZE);
+    tokio::spawn(run_matchmaker(request_receiver));
+
+    let (returning, mut returning_receiver) = mpsc::channel(LOBBY_REQUEST_BUFFER_SIZE);
+
+    let lobby = Lobby {
+        nicknames: Arc::new(Mutex::new(HashSet::new())),
+        ratings: Arc::new(Mutex::new(HashMap::new())),
+        requests,
+        returning,
+        event_handler: event_handler.clone(),
+    };
+    let mut next_id = 0;
+
+    loop {
+        tokio::select! {
+            accepted = accept_connection(&listener) => {
+                let (stream, addr) = accepted?;
+                event_handler(HubEvent::PlayerConnected { addr });
+
+                next_id += 1;
+                let nickname = format!("guest{}", next_id);
+                lobby.nicknames.lock().unwrap().insert(nickname.clone());
+
+                let (reader, writer) = stream.into_split();
+                let player = HubPlayer {
+                    id: next_id,
+                    nickname,
+                    addr,
+                    reader: BufReader::new(reader).lines(),
+                    writer,
+                };
+
+                tokio::spawn(welcome_player(player, lobby.clone()));
+            }
+            Some(player) = returning_receiver.recv() => {
+                tokio::spawn(run_lobby_player(player, lobby.clone()));
+            }
+        }
+    }
+}
+
+// Plays games until one player wins the majority of them. Drawn knockout match
+// continues by deciding games, then the better seed advances.
+async fn play_match(
+    players: [usize; 2],
+    seats: [Option<HubPlayer>; 2],
+    options: TournamentOptions,
+) -> (MatchResult, [Option<HubPlayer>; 2]) {
+    let mut result = MatchResult::new(players);
+
+    let mut match_players = match seats {
+        [Some(first), Some(second)] => [first, second],
+        seats => {
+            result.forfeit = seats
+                .iter()
+                .position(Option::is_none)
+                .map(|index| players[index]);
+            return (result, seats);
+        }
+    };
+
+    let mut games = 0;
+
+    loop {
+        if result.wins.iter().any(|&wins| wins > options.best_of / 2) {
+            break;
+        }
+
+        if games >= options.best_of {
+            if options.format == TournamentFormat::RoundRobin || result.wins[0] != result.wins[1] {
+                break;
+            }
+
+            if games >= options.best_of + TOURNAMENT_TIEBREAK_GAMES {
+                result.decided_by_seed = true;
+                break;
+            }
+        }
+
+        let first = (games % 2) as usize;
+
+        match play_match_game(&mut match_players, first, options.variant).await {
+            GameOutcome::Finished(winner) => {
+                let msg = match winner {
+                    Some(winner) => {
+                        result.wins[winner] += 1;
+                        format!("{} won the game.", match_players[winner].nickname)
+                    }
+                    None => {
+                        result.draws += 1;
+                        "Game ended in a draw.".to_string()
+                    }
+                };
+                let msg = format!(
+                    "{} Match score {} {}-{} {}.",
+                    msg,
+                    match_players[0].nickname,
+                    result.wins[0],
+                    result.wins[1],
+                    match_players[1].nickname
+                );
+
+                for player in match_players.iter_mut() {
+                    let _ = send_hub_message(&mut player.writer, &msg).await;
+                }
+            }
+            GameOutcome::Disconnected(index) => {
+                result.forfeit = Some(players[index]);
+
+                let [first, second] = match_players;
+                let mut seats = [Some(first), Some(second)];
+                seats[index] = None;
+
+                return (result, seats);
+            }
+        }
+
+        games += 1;
+    }
+
+    let [first, second] = match_players;
+    (result, [Some(first), Some(second)])
+}
+
+// Match which panicked is lost by both players, their connections are gone with it.
+async fn play_match_apart(
+    players: [usize; 2],
+    seats: [Option<HubPlayer>; 2],
+    options: TournamentOptions,
+) -> (MatchResult, [Option<HubPlayer>; 2]) {
+    match tokio::spawn(play_match(players, seats, options)).await {
+        Ok(finished) => finished,
+        Err(_) => {
+            let mut result = MatchResult::new(players);
+            result.double_forfeit = true;
+            (result, [None, None])
+        }
+    }
+}
+
+async fn run_tournament(
+    mut seats: TournamentSeats,
+    options: TournamentOptions,
+    event_handler: HubEventHandler,
+) {
+    let names = seats
+        .iter()
+        .flatten()
+        .map(|player| player.nickname.clone())
+        .collect();
+    let mut tournament = Tournament::new(options.format, names);
+
+    while let Some(round) = tournament.start_round() {
+        let number = round.number;
+        let pairings = round.pairings.clone();
+        let formatted: Vec<String> = pairings
+            .iter()
+            .map(|pairing| tournament.format_pairing(pairing))
+            .collect();
+
+        let msg = format!("Round {}: {}.", number, formatted.join("; "));
+        broadcast_hub_message(&mut seats, &msg).await;
+        event_handler(HubEvent::RoundStarted {
+            round: number,
+            pairings: formatted,
+        });
+
+        let mut matches = JoinSet::new();
+
+        for pairing in pairings {
+            if let Pairing::Match(players) = pairing {
+                let match_seats = players.map(|player| seats[player].take());
+                matches.spawn(play_match_apart(players, match_seats, options));
+            }
+        }
+
+        while let Some(finished) = matches.join_next().await {
+            let (result, match_seats) = finished.expect("Match task does not panic.");
+
+            for (player, seat) in result.players.into_iter().zip(match_seats) {
+                seats[player] = seat;
+            }
+
+            let formatted = tournament.format_result(&result);
+            broadcast_hub_message(&mut seats, &format!("Match finished: {}.", formatted)).await;
+            event_handler(HubEvent::MatchFinished { result: formatted });
+
+            tournament.record_result(result);
+        }
+
+        let lines = tournament.format_summary();
+        for line in &lines {
+            broadcast_hub_message(&mut seats, line).await;
+        }
+        event_handler(HubEvent::TournamentStandings { lines });
+    }
+
+    let winner = tournament
+        .get_winner()
+        .map(|winner| tournament.get_name(winner).to_string());
+
+    let msg = match &winner {
+        Some(winner) => format!("Tournament finished, {} won.", winner),
+        None => "Tournament finished without winner.".to_string(),
+    };
+    broadcast_hub_message(&mut seats, &msg).await;
+    event_handler(HubEvent::TournamentFinished { winner });
+}
+
+// Registers players until the tournament is full, then plays it.
+async fn run_tournament_loop(
+    listener: TcpListener,
+    options: TournamentOptions,
+    event_handler: HubEventHandler,
+) -> io::Result<()> {
+    let nicknames = Arc::new(Mutex::new(HashSet::new()));
+    let (sender, mut receiver) = mpsc::channel(options.players);
+    let mut seats: TournamentSeats = Vec::new();
+    let mut next_id = 0;
+
+    event_handler(HubEvent::RegistrationOpened {
+        format: options.format,
+        players: options.players,
+    });
+
+    while seats.len() < options.players {
+        tokio::select! {
+            accepted = accept_connection(&listener) => {
+                let (stream, addr) = accepted?;
+                event_handler(HubEvent::PlayerConnected { addr });
+
+                next_id += 1;
+                tokio::spawn(register_player(stream, addr, next_id, nicknames.clone(), sender.clone()));
+            }
+            Some(player) = receiver.recv() => {
+                event_handler(HubEvent::PlayerRegistered {
+                    nickname: player.nickname.clone(),
+                    addr: player.addr,
+                });
+
+                let msg = format!(
+                    "{} registered, {} of {} players.",
+                    player.nickname,
+                    seats.len() + 1,
+                    options.players
+                );
+                seats.push(Some(player));
+                broadcast_hub_message(&mut seats, &msg).await;
+            }
+        }
+    }
+
+    // Late players are refused by the closed listener, those registering meanwhile by
+    // the closed channel.
+    drop(listener);
+    receiver.close();
+    while let Ok(player) = receiver.try_recv() {
+        refuse_late_player(player).await;
+    }
+    drop(receiver);
+
+    run_tournament(seats, options, event_handler).await;
+
+    Ok(())
+}
+
+/// Runs the hub until accepting connections fails, or until the tournament is over
+/// when given. Listens on the same address as server of the direct game.
+pub fn run_hub(
+    ip_addr: Option<IpAddr>,
+    port: Option<u16>,
+    tournament: Option<TournamentOptions>,
+    event_handler: HubEventHandler,
+) -> io::Result<()> {
+    let port: u16 = match port {
+        Some(port) => port,
+        None => get_first_free_port(),
+    };
+
+    let bind_ip_addr = get_bind_ip_addr(ip_addr);
+
+    // Games are relayed on all cores.
+    Runtime::new()?.block_on(async {
+        let listener = bind_tcp_listener(SocketAddr::new(bind_ip_addr, port))?;
+
+        event_handler(HubEvent::Listening {
+            ip_addrs: get_reachable_ip_addrs(bind_ip_addr),
+            port,
+        });
+
+        match tournament {
+            Some(options) => run_tournament_loop(listener, options, event_handler).await,
+            None => run_hub_loop(listener, event_handler).await,
+        }
+    })
+}
@@ -0,0 +1,672 @@
+use colored::*;
+use std::fmt::Display;
+use std::net::{IpAddr, SocketAddr};
+
+use tic_tac_toe_game::arena::{ArenaGame, ArenaStats};
+use tic_tac_toe_game::game_discovery::DiscoveredGame;
+use tic_tac_toe_game::game_event::{GameEvent, Player};
+use tic_tac_toe_game::game_hub::HubEvent;
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::mcts_player::MctsAnalysis;
+use tic_tac_toe_game::opening_book::{BookMove, OpeningBook};
+use tic_tac_toe_game::playboard::{GameState, Playboard};
+use tic_tac_toe_game::puzzle::{PositionEditor, PuzzleGame, PuzzleProgress};
+use tic_tac_toe_game::q_learning::TrainingStats;
+use tic_tac_toe_game::settings::ai_options::HINT_SHOWN_MOVES;
+use tic_tac_toe_game::settings::commands::*;
+
+use crate::game_output::{print_output, print_playboard};
+
+fn get_player_name(player: Player) -> &'static str {
+    match player {
+        Player::You => "You",
+        Player::Oponent => "Your oponent",
+    }
+}
+
+// Listener on any address is reachable on all addresses of the machine.
+fn format_ip_addrs(ip_addrs: &[IpAddr]) -> String {
+    let formatted = ip_addrs
+        .iter()
+        .map(|ip_addr| ip_addr.to_string().magenta().bold().to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    match ip_addrs.len() {
+        1 => format!("IP address {}", formatted),
+        _ => format!("IP addresses {}", formatted),
+    }
+}
+
+pub fn print_server_game_setup(ip_addrs: &[IpAddr], port: u16) {
+    // todo: refactor
+    println!(
+        "{} is running on {} and {}.\n{}\n",
+        "Tic tac toe game".magenta().bold(),
+        format_ip_addrs(ip_addrs),
+        port.to_string().magenta().bold(),
+        "Connect second player as a client.".yellow().bold()
+    );
+}
+
+#[cfg(feature = "rest-api")]
+pub fn print_game_api_address(addr: SocketAddr) {
+    println!(
+        "Game API is running on {}.\n",
+        format!("http://{}/games", addr).magenta().bold()
+    );
+}
+
+// Turns game events of the engine into messages for the player.
+pub fn print_game_event(event: GameEvent) {
+    match event {
+        GameEvent::WaitingForOponent { ip_addrs, port } => {
+            print_server_game_setup(&ip_addrs, port)
+        }
+        GameEvent::WaitingForBrowserOponent { ip_addrs, port } => println!(
+            "{}\n{}\n",
+            "Second player may also join from web browser on:".yellow().bold(),
+            ip_addrs
+                .iter()
+                .map(|ip_addr| format!("http://{}/", SocketAddr::new(*ip_addr, port))
+                    .magenta()
+                    .bold()
+                    .to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        ),
+        GameEvent::CertificateFingerprint { fingerprint } => println!(
+            "Connection is encrypted, certificate fingerprint is\n{}\nSecond player should check the client shows the same one.\n",
+            fingerprint.yellow().bold()
+        ),
+        GameEvent::CertificateTrusted {
+            fingerprint,
+            newly_pinned,
+        } => println!(
+            "{}\n{}\n",
+            if newly_pinned {
+                "Server is new, its certificate fingerprint was pinned. Check it matches the one shown on the server:"
+            } else {
+                "Connection is encrypted, server certificate matches the pinned fingerprint:"
+            },
+            fingerprint.yellow().bold()
+        ),
+        GameEvent::ConnectionRejected { addr, reason } => println!(
+            "{}",
+            format!("Connection from {} rejected: {}", addr, reason).red()
+        ),
+        GameEvent::Connected { addr } => println!("Connected with second player {}.\n", addr),
+        GameEvent::Placed {
+            player,
+            place,
+            game_state,
+        } => {
+            let msg = format!("{} placed on {}.", get_player_name(player), place);
+            print_output(msg.green().bold());
+
+            if game_state == GameState::CollapsePending {
+                let msg = match player {
+                    Player::You => {
+                        "Entanglement cycle closed. Your oponent chooses where it collapses."
+                            .to_string()
+                    }
+                    Player::Oponent => format!(
+                        "Entanglement cycle closed. Choose where it collapses by {}(row, col).",
+                        COLLAPSE_COMMAND
+                    ),
+                };
+                print_output(msg.yellow().bold());
+            }
+        }
+        GameEvent::Collapsed { player, place } => {
+            let msg = format!(
+                "{} collapsed entanglement cycle on {}.",
+                get_player_name(player),
+                place
+            );
+            print_output(msg.green().bold());
+        }
+        GameEvent::GameFinished {
+            winner: Some(winner),
+        } => {
+            let msg = format!(
+                "\nGame over. {} won!{}.\nYou can play again by {}.",
+                get_player_name(winner),
+                match winner {
+                    Player::You => "",
+                    Player::Oponent => " ( ＾◡ ＾)っ 💗",
+                },
+                PLAY_AGAIN_COMMAND
+            );
+            print_output(msg.magenta().bold());
+        }
+        GameEvent::GameFinished { winner: None } => {
+            let msg = format!("\nDraw.\nYou can play again by {}.", PLAY_AGAIN_COMMAND);
+            print_output(msg.magenta().bold());
+        }
+        GameEvent::PlayboardChanged { printable } => print_playboard(&printable),
+        GameEvent::InvalidMove(msg) => print_output(msg.red().bold()),
+        GameEvent::NotYourTurn => print_output(
+            "It is not your turn, you cannot place symbol. Wait for your oponent."
+                .red()
+                .bold(),
+        ),
+        GameEvent::GameNotRunning => {
+            let msg = format!(
+                "You can no longer play symbol; game finished.\nYou can start another one with {}.",
+                PLAY_AGAIN_COMMAND
+            );
+            print_output(msg.red().bold());
+        }
+        GameEvent::PrivateMessage(msg) => print_output(msg),
+        GameEvent::NewGameRequested {
+            player: Player::You,
+        } => print_output(
+            format!(
+                "Sending new game request, your oponent must agree by {}.",
+                AGREE_COMMAND
+            )
+            .green()
+            .bold(),
+        ),
+        GameEvent::NewGameRequested {
+            player: Player::Oponent,
+        } => print_output(
+            format!(
+                "Your oponent wants to restart the game, you can agree with {}",
+                AGREE_COMMAND
+            )
+            .green()
+            .bold(),
+        ),
+        GameEvent::GameRestarted => {
+            print_output("\nRestarting game for both players...".green().bold())
+        }
+        GameEvent::GameAssigned { start_order } => {
+            let msg = format!(
+                "\nHub started new game, you play {}.",
+                match start_order {
+                    StartOrder::First => "first",
+                    StartOrder::Second => "second",
+                }
+            );
+            print_output(msg.green().bold())
+        }
+        GameEvent::Disconnected => print_output(
+            "Your oponent disconnected. You can quit with /quit."
+                .red()
+                .bold(),
+        ),
+        GameEvent::SendFailed => print_output(
+            "Message could not be sent, connection is closed."
+                .red()
+                .bold(),
+        ),
+        GameEvent::EngineFailed(msg) => {
+            print_output(format!("Engine failed: {}.", msg).red().bold())
+        }
+        GameEvent::Stopping => print_output("Stopping tic tac toe game..."),
+    }
+}
+
+pub fn print_hub_event(event: HubEvent) {
+    match event {
+        HubEvent::Listening { ip_addrs, port } => println!(
+            "{} is running on {} and {}.\n{}\n",
+            "Tic tac toe hub".magenta().bold(),
+            format_ip_addrs(&ip_addrs),
+            port.to_string().magenta().bold(),
+            "Connect players as clients."
+                .yellow()
+                .bold()
+        ),
+        HubEvent::PlayerConnected { addr } => println!("Player connected from {}.", addr),
+        HubEvent::PlayerQueued { nickname, request } => {
+            println!("Player {} queued for {} game.", nickname, request)
+        }
+        HubEvent::PlayerLeftQueue { nickname } => println!("Player {} left the queue.", nickname),
+        HubEvent::PlayerTimedOut { addr } => {
+            println!("Nobody joined player {} in time, they left the queue.", addr)
+        }
+        HubEvent::GameStarted { first, second } => println!(
+            "{}",
+            format!("Game started between {} and {}.", first, second).green()
+        ),
+        HubEvent::GameFinished {
+            first,
+            second,
+            result,
+        } => println!(
+            "Game between {} and {} finished: {}.",
+            first, second, result
+        ),
+        HubEvent::RegistrationOpened { format, players } => println!(
+            "{}",
+            format!(
+                "Registration to {} tournament of {} players is open, clients register by {}nickname.",
+                format, players, REGISTER_COMMAND
+            )
+            .yellow()
+            .bold()
+        ),
+        HubEvent::PlayerRegistered { nickname, addr } => {
+            println!("Player {} registered from {}.", nickname.magenta().bold(), addr)
+        }
+        HubEvent::RoundStarted { round, pairings } => println!(
+            "\n{}\n{}",
+            format!("Round {}:", round).magenta().bold(),
+            pairings.join("\n")
+        ),
+        HubEvent::MatchFinished { result } => {
+            println!("{}", format!("Match finished: {}.", result).green())
+        }
+        HubEvent::TournamentStandings { lines } => {
+            println!("\n{}\n{}", "Standings:".magenta().bold(), lines.join("\n"))
+        }
+        HubEvent::TournamentFinished { winner } => println!(
+            "\n{}",
+            match winner {
+                Some(winner) => format!("Tournament finished, {} won.", winner),
+                None => "Tournament finished without winner.".to_string(),
+            }
+            .yellow()
+            .bold()
+        ),
+    }
+}
+
+pub fn print_discovered_games(games: &[DiscoveredGame]) {
+    if games.is_empty() {
+        println!(
+            "{}",
+            "No game found on local network, start server first."
+                .yellow()
+                .bold()
+        );
+        return;
+    }
+
+    println!("{}", "Games found on local network:\n".magenta().bold());
+
+    for (index, game) in games.iter().enumerate() {
+        let announcement = &game.announcement;
+        let mut features = vec![announcement.variant.to_string()];
+
+        if announcement.tls {
+            features.push("tls".to_string());
+        }
+        if announcement.password {
+            features.push("password".to_string());
+        }
+
+        let state = if announcement.open {
+            "open".green().bold()
+        } else {
+            "full".red().bold()
+        };
+
+        println!(
+            "{:>3}. {} hosted by {} on {}:{} ({}) {}",
+            index + 1,
+            announcement.game_name.magenta().bold(),
+            announcement.nickname,
+            game.ip_addr,
+            announcement.port,
+            features.join(", "),
+            state
+        );
+    }
+
+    println!();
+}
+
+pub fn print_arena_game(game: &ArenaGame, names: &[String; 2]) {
+    let result = match game.winner {
+        Some(winner) => format!("{} won", names[winner]).green().bold(),
+        None => "draw".yellow().bold(),
+    };
+
+    println!(
+        "Game {} ({} started): {} after {} moves.{}",
+        game.number,
+        names[game.first],
+        result,
+        game.moves.len(),
+        match &game.forfeit {
+            Some(forfeit) => format!(" Forfeit: {}.", forfeit).red().to_string(),
+            None => String::new(),
+        }
+    );
+}
+
+pub fn print_arena_stats(stats: &ArenaStats, names: &[String; 2], variant: GameVariant) {
+    let (score, confidence) = stats.get_score();
+
+    println!(
+        "\n{}\n{}: {} wins, {} draws, {} losses\n{}: {} wins, {} draws, {} losses\nForfeited games: {}\nAverage game length: {:.1} moves\nScore of {}: {} (95 % confidence interval)\n",
+        format!("Arena results after {} games of {} variant:", stats.games, variant)
+            .magenta()
+            .bold(),
+        names[0].magenta().bold(),
+        stats.wins,
+        stats.draws,
+        stats.losses,
+        names[1].magenta().bold(),
+        stats.losses,
+        stats.draws,
+        stats.wins,
+        stats.forfeits,
+        stats.get_average_length(),
+        names[0],
+        format!("{:.3} ± {:.3}", score, confidence).yellow().bold()
+    );
+}
+
+pub fn print_book_generated(book: &OpeningBook, path: &str) {
+    println!(
+        "{}",
+        format!(
+            "Book of {} positions of {}x{} board written to {}.",
+            book.len(),
+            book.get_size(),
+            book.get_size(),
+            path
+        )
+        .green()
+    );
+}
+
+pub fn print_training_finished(stats: &TrainingStats, path: &str) {
+    println!(
+        "{}\nFirst player won {}, second player won {}, {} draws.\nPolicy of {} positions written to {}.",
+        format!("Training finished after {} self-play games.", stats.episodes).green(),
+        stats.first_wins,
+        stats.second_wins,
+        stats.draws,
+        stats.positions,
+        path
+    );
+}
+
+pub fn print_puzzles_loaded(count: usize, solved: usize, path: &str) {
+    print_output(format!(
+        "{} {} of {} puzzles from {} solved. Write {} if unsure what to do.\n",
+        "Puzzle mode.".magenta().bold(),
+        solved,
+        count,
+        path,
+        HELP_COMMAND.magenta().bold()
+    ));
+}
+
+fn get_symbol_name(start_order: StartOrder) -> &'static str {
+    match start_order {
+        StartOrder::First => "X",
+        StartOrder::Second => "O",
+    }
+}
+
+pub fn print_puzzle(game: &PuzzleGame, number: usize) {
+    let puzzle = game.get_puzzle();
+
+    print_output(game.get_playboard().get_board_printable());
+    print_output(
+        format!(
+            "Puzzle {}: {} to play and win in {}, {} moves left.",
+            number,
+            get_symbol_name(puzzle.get_on_turn()),
+            puzzle.get_moves(),
+            game.get_moves_left()
+        )
+        .yellow()
+        .bold(),
+    );
+}
+
+pub fn print_puzzle_progress(progress: PuzzleProgress, game: &PuzzleGame, number: usize) {
+    match progress {
+        PuzzleProgress::Solved => {
+            print_output(game.get_playboard().get_board_printable());
+            print_output(format!("Puzzle {} solved.", number).green().bold());
+        }
+        PuzzleProgress::Replied(row, col) => {
+            print_output(format!("Defender replies ({}, {}).", row, col));
+            print_puzzle(game, number);
+        }
+        PuzzleProgress::Failed(reply) => {
+            if let Some((row, col)) = reply {
+                print_output(format!("Defender replies ({}, {}).", row, col));
+            }
+
+            print_output(game.get_playboard().get_board_printable());
+            print_output(
+                format!(
+                    "The move does not win in {} moves. Try again with {} or go on with {}.",
+                    game.get_puzzle().get_moves(),
+                    RETRY_COMMAND,
+                    SKIP_COMMAND
+                )
+                .red()
+                .bold(),
+            );
+        }
+    }
+}
+
+pub fn print_all_puzzles_solved() {
+    print_output(
+        format!(
+            "All puzzles are solved. Build a new one with {}.",
+            SETUP_COMMAND
+        )
+        .green()
+        .bold(),
+    );
+}
+
+pub fn print_setup_position(editor: &PositionEditor) {
+    print_setup_playboard(editor.get_playboard());
+    print_output(
+        format!(
+            "Setup: {} on turn. Finish with {} {} or throw away with {} {}.",
+            get_symbol_name(editor.get_on_turn()),
+            SETUP_COMMAND,
+            SETUP_DONE,
+            SETUP_COMMAND,
+            SETUP_CANCEL
+        )
+        .yellow()
+        .bold(),
+    );
+}
+
+pub fn print_setup_unfinished() {
+    print_output(
+        format!(
+            "Finish the position with {} {} or {} {} first.",
+            SETUP_COMMAND, SETUP_DONE, SETUP_COMMAND, SETUP_CANCEL
+        )
+        .yellow()
+        .bold(),
+    );
+}
+
+fn print_setup_playboard(playboard: &Playboard) {
+    print_output(format!(
+        "{}\nPosition {}",
+        playboard.get_board_printable(),
+        playboard.get_notation()
+    ));
+}
+
+pub fn print_puzzle_created(number: usize, path: &str) {
+    print_output(
+        format!("Position added as puzzle {} to {}.", number, path)
+            .green()
+            .bold(),
+    );
+}
+
+// Invalid move or position of the puzzle mode.
+pub fn print_puzzle_error(msg: impl Display) {
+    print_output(msg.to_string().red().bold());
+}
+
+pub fn print_puzzle_command_outside_puzzle_mode(command: &str) {
+    print_output(
+        format!(
+            "{} works only in puzzle mode, start it by the puzzle subcommand.",
+            command
+        )
+        .yellow()
+        .bold(),
+    );
+}
+
+pub fn print_unknown_puzzle_command(line: &str) {
+    print_puzzle_error(format!(
+        "{} is not a puzzle command, see {}.",
+        line, HELP_COMMAND
+    ));
+}
+
+pub fn print_puzzle_help() {
+    print_output(format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        "Valid commands to use:\n".magenta().bold(),
+        format!(
+            "{}(2, 1)   Plays move of the puzzle on row 2 and col 1, the solver defends.",
+            PLACE_ON_PLAYBOARD_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{}          Starts the puzzle again. {} goes to the next unsolved one.",
+            RETRY_COMMAND, SKIP_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{} x (1, 1)   Builds new puzzle, places x or o or clears cell with {} (1, 1). {} alone empties playboard.",
+            SETUP_COMMAND, SETUP_CLEAR, SETUP_CLEAR
+        )
+        .green()
+        .bold(),
+        format!(
+            "{} {} second   Chooses player on turn. {} x.o/.x./... sets whole position.",
+            SETUP_COMMAND, SETUP_TURN, SETUP_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{} {}   Adds the position as puzzle when the player on turn forces the win, {} {} throws it away.",
+            SETUP_COMMAND, SETUP_DONE, SETUP_COMMAND, SETUP_CANCEL
+        )
+        .green()
+        .bold(),
+        format!("{}           Quits puzzle mode.", QUIT_COMMAND)
+            .green()
+            .bold(),
+    ));
+}
+
+pub fn print_book_hint(book_move: &BookMove) {
+    print_output(format!(
+        "{} {}\nOpening book says the move leads to {} with perfect play.",
+        "Hint:".yellow().bold(),
+        book_move.get_command().yellow().bold(),
+        book_move.result
+    ));
+}
+
+pub fn print_hint(analysis: &MctsAnalysis) {
+    let best_move = match analysis.get_best_move() {
+        Some(best_move) => best_move,
+        None => return print_output("There is no move to suggest."),
+    };
+
+    let moves: Vec<String> = analysis
+        .moves
+        .iter()
+        .take(HINT_SHOWN_MOVES)
+        .map(|analysis| {
+            format!(
+                "{} scores {:.0} % in {} playouts",
+                analysis.game_move,
+                analysis.score * 100.0,
+                analysis.visits
+            )
+        })
+        .collect();
+
+    print_output(format!(
+        "{} {}\n{}",
+        "Hint:".yellow().bold(),
+        best_move.yellow().bold(),
+        moves.join("\n")
+    ));
+}
+
+pub fn print_game_welcome_message() {
+    print_output(format!(
+        "Welcome to {}. Write {} if unsure what to do and {}.\n",
+        "tic tac toe game".magenta().bold(),
+        HELP_COMMAND.magenta().bold(),
+        "enjoy the game".magenta().bold(),
+    ));
+}
+
+pub fn print_game_help() {
+    print_output(format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        "Valid commands to use:\n".magenta().bold(),
+        format!(
+            "{}            Writes message for other player.",
+            PRIVATE_MESSAGE_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{}(2, 1)   Places player symbol to playboard. In this example on row 2 and col 1.",
+            PLACE_ON_PLAYBOARD_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{}(2, 1) (3, 3)   Quantum variant places spooky mark to two cells.\n{}(2, 1)   Quantum variant chooses cell for entanglement cycle collapse.\n{}(2, 1) 5   Numerical variant places number 5, odd numbers for first player and even for second.",
+            PLACE_ON_PLAYBOARD_COMMAND, COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND
+        )
+        .green()
+        .bold(),
+        format!("{}          Clears window chat history.", CLEAR_COMMAND)
+            .green()
+            .bold(),
+        format!("{}           Quits application.", QUIT_COMMAND)
+            .green()
+            .bold(),
+        format!(
+            "{}      Sends request for game restart. Oponent must agree with {}",
+            PLAY_AGAIN_COMMAND, AGREE_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{}nick     Registers on the hub under nickname nick, for tournament and ratings.",
+            REGISTER_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{} 1400-1600   Queues on the hub for oponent, optionally of given rating range. {} leaves the queue.",
+            QUEUE_COMMAND, LEAVE_COMMAND
+        )
+        .green()
+        .bold(),
+        format!(
+            "{} playouts=5000   Suggests move found by Monte Carlo tree search, options as of mcts player.",
+            HINT_COMMAND
+        )
+        .green()
+        .bold(),
+    ));
+}
@@ -0,0 +1,133 @@
+//! Options both players choose before the game starts.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Role of the application in the connection.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HostType {
+    /// Waits for second player to connect.
+    Server,
+    /// Connects to a running server or hub.
+    Client,
+    /// Pairs connecting clients into games, does not play itself.
+    Hub,
+    /// Finds games announced on local network and joins the chosen one as client.
+    Discover,
+    /// Plays locally against external engine, see [`crate::engine_player`].
+    Engine,
+}
+
+// Strum macros not used due to case insensitiveness.
+impl FromStr for HostType {
+    type Err = ();
+
+    fn from_str(host_type: &str) -> Result<HostType, Self::Err> {
+        // Do not respect letter case.
+        match host_type.to_lowercase().as_str() {
+            "server" => Ok(HostType::Server),
+            "client" => Ok(HostType::Client),
+            "hub" => Ok(HostType::Hub),
+            "discover" => Ok(HostType::Discover),
+            "engine" => Ok(HostType::Engine),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Order of the player in the game. First player plays `X` (odd numbers in
+/// numerical variant), second player plays `O` (even numbers).
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "rest-api",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum StartOrder {
+    First,
+    Second,
+}
+
+impl StartOrder {
+    /// Start order of the other player.
+    pub fn oponent(self) -> StartOrder {
+        match self {
+            StartOrder::First => StartOrder::Second,
+            StartOrder::Second => StartOrder::First,
+        }
+    }
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for StartOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StartOrder::First => write!(f, "first"),
+            StartOrder::Second => write!(f, "second"),
+        }
+    }
+}
+
+// Strum macros not used due to case insensitiveness.
+impl FromStr for StartOrder {
+    type Err = ();
+
+    fn from_str(start_order: &str) -> Result<StartOrder, Self::Err> {
+        match start_order.to_lowercase().as_str() {
+            "first" => Ok(StartOrder::First),
+            "second" => Ok(StartOrder::Second),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How the connection between players is established.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConnectionOptions {
+    /// Encrypts the connection with TLS, both players must enable it.
+    pub tls: bool,
+    /// Password both players must know, verified before the game starts.
+    pub password: Option<String>,
+    /// Name of the game announced by the server on local network.
+    pub game_name: String,
+    /// Name of the local player announced with the game.
+    pub nickname: String,
+    /// Port on which server serves browser client and accepts its WebSocket.
+    pub web_port: Option<u16>,
+}
+
+/// Rules of the game, both players must use the same variant.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameVariant {
+    /// Standard tic tac toe.
+    Classic,
+    /// Every move places spooky mark into two cells, see [`crate::quantum_playboard`].
+    Quantum,
+    /// Players place numbers, line summing to 15 wins, see [`crate::numerical_playboard`].
+    Numerical,
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for GameVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameVariant::Classic => write!(f, "classic"),
+            GameVariant::Quantum => write!(f, "quantum"),
+            GameVariant::Numerical => write!(f, "numerical"),
+        }
+    }
+}
+
+// Strum macros not used due to case insensitiveness.
+impl FromStr for GameVariant {
+    type Err = ();
+
+    fn from_str(variant: &str) -> Result<GameVariant, Self::Err> {
+        match variant.to_lowercase().as_str() {
+            "classic" => Ok(GameVariant::Classic),
+            "quantum" => Ok(GameVariant::Quantum),
+            "numerical" => Ok(GameVariant::Numerical),
+            _ => Err(()),
+        }
+    }
+}
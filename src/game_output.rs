@@ -0,0 +1,51 @@
+use rustyline::ExternalPrinter;
+use std::fmt::Display;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+enum OutputRedirection {
+    // Text user interface shows messages in chat pane and playboard on its own.
+    TextUserInterface(Sender<String>),
+    // Line editor prints messages above its prompt.
+    LineEditor(Box<dyn ExternalPrinter + Send>),
+}
+
+// When not set, messages are printed to stdout.
+static OUTPUT_REDIRECTION: Mutex<Option<OutputRedirection>> = Mutex::new(None);
+
+pub fn redirect_output_to_text_user_interface(sender: Sender<String>) {
+    *OUTPUT_REDIRECTION.lock().unwrap() = Some(OutputRedirection::TextUserInterface(sender));
+}
+
+pub fn redirect_output_to_line_editor(printer: impl ExternalPrinter + Send + 'static) {
+    *OUTPUT_REDIRECTION.lock().unwrap() = Some(OutputRedirection::LineEditor(Box::new(printer)));
+}
+
+pub fn restore_output() {
+    *OUTPUT_REDIRECTION.lock().unwrap() = None;
+}
+
+pub fn print_output(msg: impl Display) {
+    match &mut *OUTPUT_REDIRECTION.lock().unwrap() {
+        Some(OutputRedirection::TextUserInterface(sender)) => {
+            for line in msg.to_string().lines() {
+                // Receiver is gone only when interface is shutting down.
+                let _ = sender.send(line.to_string());
+            }
+        }
+        Some(OutputRedirection::LineEditor(printer)) => {
+            if printer.print(format!("{}\n", msg)).is_err() {
+                println!("{}", msg);
+            }
+        }
+        None => println!("{}", msg),
+    }
+}
+
+pub fn print_playboard(printable: &str) {
+    if let Some(OutputRedirection::TextUserInterface(_)) = &*OUTPUT_REDIRECTION.lock().unwrap() {
+        return;
+    }
+
+    print_output(printable);
+}
@@ -0,0 +1,410 @@
+//! Rules of one network game independent of the transport.
+//!
+//! [`GameSession`] consumes commands of the local player and messages of the oponent
+//! and returns [`SessionEffect`]s the transport must carry out, so the same session
+//! drives every front-end and connection type.
+
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::game_event::{GameEvent, Player};
+use crate::game_options::{GameVariant, StartOrder};
+use crate::matchmaking::QueueRequest;
+use crate::playboard::GameState;
+use crate::playboard_variant::{PlayboardVariant, PlayboardVariantFactory};
+use crate::settings::commands::*;
+
+/// Session shared between front-end and the thread reading the connection.
+pub type SharedGameSession = Arc<Mutex<GameSession>>;
+
+/// Results of the games played in one session.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Score {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// Snapshot of the game for the front-end.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct GameStatus {
+    pub my_turn: bool,
+    pub game_running: bool,
+    pub connected: bool,
+    pub score: Score,
+}
+
+/// Move played in the current game.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameMove {
+    pub player: Player,
+    /// Command the move was played by, e.g. `/place`.
+    pub command: String,
+    /// Description of the move returned by the playboard.
+    pub place: String,
+    /// Move as written by the player (command stripped), the command replays it.
+    pub data: String,
+    /// Time the player spent on the move.
+    pub duration: Duration,
+}
+
+/// Time both players spent on their moves in the current game.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct GameClocks {
+    pub you: Duration,
+    pub oponent: Duration,
+}
+
+/// What the transport must do after the session handled an input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SessionEffect {
+    /// Send the line (without new line) to the oponent.
+    Send(String),
+    /// Pass the event to the front-end.
+    Emit(GameEvent),
+}
+
+pub struct GameSession {
+    playboard: Box<dyn PlayboardVariant>,
+    variant: GameVariant,
+    start_order: StartOrder,
+    my_turn: bool,
+    game_running: bool,
+    // Oponent asked for a new game.
+    new_game_req: bool,
+    // Local player asked for a new game.
+    new_game_desirable: bool,
+    connected: bool,
+    score: Score,
+    history: Vec<GameMove>,
+    clocks: GameClocks,
+    // Clock of the player on turn runs from the last move.
+    last_move_time: Instant,
+}
+
+impl GameSession {
+    pub fn new(start_order: StartOrder, variant: GameVariant) -> Self {
+        Self {
+            playboard: PlayboardVariantFactory::create_playboard(variant),
+            variant,
+            start_order,
+            my_turn: start_order == StartOrder::First,
+            game_running: true,
+            new_game_req: false,
+            new_game_desirable: false,
+            connected: true,
+            score: Score::default(),
+            history: Vec::new(),
+            clocks: GameClocks::default(),
+            last_move_time: Instant::now(),
+        }
+    }
+
+    pub fn get_playboard(&self) -> &dyn PlayboardVariant {
+        self.playboard.as_ref()
+    }
+
+    pub fn get_variant(&self) -> GameVariant {
+        self.variant
+    }
+
+    pub fn get_game_status(&self) -> GameStatus {
+        GameStatus {
+            my_turn: self.my_turn,
+            game_running: self.game_running,
+            connected: self.connected,
+            score: self.score,
+        }
+    }
+
+    /// Moves of the current game in the order they were played.
+    pub fn get_history(&self) -> &[GameMove] {
+        &self.history
+    }
+
+    pub fn get_clocks(&self) -> GameClocks {
+        self.clocks
+    }
+
+    /// Handles command typed by the local player, e.g. `/place (1, 1)`.
+    pub fn handle_local(&mut self, line: &str) -> Vec<SessionEffect> {
+        let line = line.trim();
+        let mut effects = Vec::new();
+
+        if line.starts_with(PLACE_ON_PLAYBOARD_COMMAND) || line.starts_with(COLLAPSE_COMMAND) {
+            if !self.can_play_symbol(&mut effects) {
+                return effects;
+            }
+
+            let played = if line.starts_with(PLACE_ON_PLAYBOARD_COMMAND) {
+                self.place_on_board(line, Player::You, &mut effects)
+            } else {
+                self.collapse_on_board(line, Player::You, &mut effects)
+            };
+
+            if !played {
+                return effects;
+            }
+        } else if line == PLAY_AGAIN_COMMAND {
+            effects.push(SessionEffect::Emit(GameEvent::NewGameRequested {
+                player: Player::You,
+            }));
+            self.new_game_desirable = true;
+        } else if line == AGREE_COMMAND {
+            if !self.new_game_req {
+                return effects;
+            }
+
+            self.restart_game(&mut effects);
+        } else if line.starts_with(NEW_GAME_COMMAND) {
+            effects.push(SessionEffect::Emit(GameEvent::InvalidMove(format!(
+                "{} is sent only by hub.",
+                NEW_GAME_COMMAND.trim()
+            ))));
+            return effects;
+        } else if let Some(arguments) = line.strip_prefix(QUEUE_COMMAND) {
+            match self.complete_queue_request(arguments) {
+                Ok(line) => effects.push(SessionEffect::Send(line)),
+                Err(msg) => effects.push(SessionEffect::Emit(GameEvent::InvalidMove(msg))),
+            }
+            return effects;
+        }
+
+        effects.push(SessionEffect::Send(line.to_string()));
+
+        effects
+    }
+
+    /// Handles line received from the oponent.
+    pub fn handle_remote(&mut self, line: &str) -> Vec<SessionEffect> {
+        let line = line.trim();
+        let mut effects = Vec::new();
+
+        if let Some(msg) = line.strip_prefix(PRIVATE_MESSAGE_COMMAND) {
+            effects.push(SessionEffect::Emit(GameEvent::PrivateMessage(
+                msg.to_string(),
+            )));
+        } else if line.starts_with(PLACE_ON_PLAYBOARD_COMMAND) || line.starts_with(COLLAPSE_COMMAND)
+        {
+            // Peer is not trusted to keep the turns.
+            if self.my_turn || !self.game_running {
+                effects.push(SessionEffect::Emit(GameEvent::InvalidMove(format!(
+                    "Oponent played {} out of turn, the move was ignored.",
+                    line
+                ))));
+            } else if line.starts_with(COLLAPSE_COMMAND) {
+                self.collapse_on_board(line, Player::Oponent, &mut effects);
+            } else if self.place_on_board(line, Player::Oponent, &mut effects) {
+                self.my_turn = true;
+            }
+        } else if line == PLAY_AGAIN_COMMAND {
+            effects.push(SessionEffect::Emit(GameEvent::NewGameRequested {
+                player: Player::Oponent,
+            }));
+            self.new_game_req = true;
+        } else if line == AGREE_COMMAND && self.new_game_desirable {
+            self.restart_game(&mut effects);
+        } else if let Some(start_order) = line.strip_prefix(NEW_GAME_COMMAND) {
+            if let Ok(start_order) = StartOrder::from_str(start_order) {
+                self.start_new_game(start_order, &mut effects);
+            }
+        }
+
+        effects
+    }
+
+    /// Handles closed connection.
+    pub fn handle_disconnect(&mut self) -> Vec<SessionEffect> {
+        self.connected = false;
+
+        vec![SessionEffect::Emit(GameEvent::Disconnected)]
+    }
+
+    // Hub pairs players of the same variant, which is the variant of this session.
+    fn complete_queue_request(&self, arguments: &str) -> Result<String, String> {
+        let mut request = QueueRequest::from_str(arguments)?;
+
+        match request.variant {
+            Some(variant) if variant != self.variant => Err(format!(
+                "Game runs {} variant, start it with -v {} to queue for {} games.",
+                self.variant, variant, variant
+            )),
+            _ => {
+                request.variant = Some(self.variant);
+                Ok(format!("{} {}", QUEUE_COMMAND, request))
+            }
+        }
+    }
+
+    fn get_start_order(&self, player: Player) -> StartOrder {
+        match player {
+            Player::You => self.start_order,
+            Player::Oponent => self.start_order.oponent(),
+        }
+    }
+
+    fn can_play_symbol(&self, effects: &mut Vec<SessionEffect>) -> bool {
+        if !self.game_running {
+            effects.push(SessionEffect::Emit(GameEvent::GameNotRunning));
+            return false;
+        }
+
+        if !self.my_turn {
+            effects.push(SessionEffect::Emit(GameEvent::NotYourTurn));
+            return false;
+        }
+
+        true
+    }
+
+    // Returns false when the move was rejected.
+    fn place_on_board(
+        &mut self,
+        line: &str,
+        player: Player,
+        effects: &mut Vec<SessionEffect>,
+    ) -> bool {
+        let start_order = self.get_start_order(player);
+        let data = &line[PLACE_ON_PLAYBOARD_COMMAND.len()..];
+
+        match self.playboard.place(data, start_order) {
+            Err(msg) => {
+                effects.push(SessionEffect::Emit(GameEvent::InvalidMove(msg)));
+                return false;
+            }
+            Ok((place, game_state)) => {
+                self.record_move(player, PLACE_ON_PLAYBOARD_COMMAND, &place, data);
+
+                effects.push(SessionEffect::Emit(GameEvent::Placed {
+                    player,
+                    place,
+                    game_state,
+                }));
+
+                self.finish_game(game_state, player, effects);
+            }
+        }
+
+        if player == Player::You {
+            self.my_turn = false;
+        }
+
+        self.emit_playboard_changed(effects);
+
+        true
+    }
+
+    // Returns false when the collapse was rejected.
+    fn collapse_on_board(
+        &mut self,
+        line: &str,
+        player: Player,
+        effects: &mut Vec<SessionEffect>,
+    ) -> bool {
+        let start_order = self.get_start_order(player);
+        let data = &line[COLLAPSE_COMMAND.len()..];
+
+        match self.playboard.collapse(data, start_order) {
+            Err(msg) => {
+                effects.push(SessionEffect::Emit(GameEvent::InvalidMove(msg)));
+                return false;
+            }
+            Ok((place, game_state)) => {
+                self.record_move(player, COLLAPSE_COMMAND, &place, data);
+
+                effects.push(SessionEffect::Emit(GameEvent::Collapsed { player, place }));
+
+                // Collapse can complete line of any player.
+                let winner = match self.playboard.winner() {
+                    Some(winner) if winner != start_order => player.other(),
+                    _ => player,
+                };
+                self.finish_game(game_state, winner, effects);
+            }
+        }
+
+        self.emit_playboard_changed(effects);
+
+        true
+    }
+
+    fn record_move(&mut self, player: Player, command: &str, place: &str, data: &str) {
+        let now = Instant::now();
+        let duration = now.duration_since(self.last_move_time);
+        self.last_move_time = now;
+
+        match player {
+            Player::You => self.clocks.you += duration,
+            Player::Oponent => self.clocks.oponent += duration,
+        }
+
+        self.history.push(GameMove {
+            player,
+            command: command.trim().to_string(),
+            place: place.to_string(),
+            data: data.trim().to_string(),
+            duration,
+        });
+    }
+
+    fn finish_game(
+        &mut self,
+        game_state: GameState,
+        winner: Player,
+        effects: &mut Vec<SessionEffect>,
+    ) {
+        let winner = match game_state {
+            GameState::GameOver => {
+                if winner == Player::You {
+                    self.score.wins += 1;
+                } else {
+                    self.score.losses += 1;
+                }
+
+                Some(winner)
+            }
+            GameState::Draw => {
+                self.score.draws += 1;
+
+                None
+            }
+            _ => return,
+        };
+
+        self.game_running = false;
+        effects.push(SessionEffect::Emit(GameEvent::GameFinished { winner }));
+    }
+
+    fn restart_game(&mut self, effects: &mut Vec<SessionEffect>) {
+        effects.push(SessionEffect::Emit(GameEvent::GameRestarted));
+        self.clear_game();
+    }
+
+    // Game started by hub, players may play the other start order than before.
+    fn start_new_game(&mut self, start_order: StartOrder, effects: &mut Vec<SessionEffect>) {
+        effects.push(SessionEffect::Emit(GameEvent::GameAssigned { start_order }));
+
+        self.start_order = start_order;
+        self.my_turn = start_order == StartOrder::First;
+        self.clear_game();
+        self.emit_playboard_changed(effects);
+    }
+
+    fn clear_game(&mut self) {
+        self.playboard.clear_board();
+        self.history.clear();
+        self.clocks = GameClocks::default();
+        self.last_move_time = Instant::now();
+
+        self.game_running = true;
+        self.new_game_req = false;
+        self.new_game_desirable = false;
+    }
+
+    fn emit_playboard_changed(&self, effects: &mut Vec<SessionEffect>) {
+        effects.push(SessionEffect::Emit(GameEvent::PlayboardChanged {
+            printable: self.playboard.get_board_printable(),
+        }));
+    }
+}
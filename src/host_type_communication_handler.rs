@@ -1,13 +1,146 @@
-use crate::cli_args_processing::Arguments;
-use crate::host_type_objects::{HostTypeObject, HostTypeObjectFactory};
-use crate::host_type_objects_utility::{print_game_help, print_game_welcome_message};
-use crate::settings::commands::{
-    AGREE_COMMAND, CLEAR_COMMAND, HELP_COMMAND, PLAY_AGAIN_COMMAND, QUIT_COMMAND,
+use std::io::IsTerminal;
+#[cfg(feature = "rest-api")]
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::cli_args_processing::{prompt_password, Arguments};
+#[cfg(feature = "rest-api")]
+use crate::game_messages::print_game_api_address;
+use crate::game_messages::{
+    print_book_hint, print_discovered_games, print_game_event, print_game_help,
+    print_game_welcome_message, print_hint, print_hub_event,
+    print_puzzle_command_outside_puzzle_mode,
+};
+use crate::game_output::print_output;
+use crate::line_editor::run_line_editor;
+use crate::text_user_interface::run_text_user_interface;
+use tic_tac_toe_game::ai_player::get_move_lines;
+use tic_tac_toe_game::engine_player::{EngineGame, EnginePlayer};
+use tic_tac_toe_game::game_discovery::discover_games;
+use tic_tac_toe_game::game_event::GameEventHandler;
+use tic_tac_toe_game::game_hub::run_hub;
+use tic_tac_toe_game::game_options::HostType;
+use tic_tac_toe_game::host_type_objects::{HostTypeObject, NetworkGame};
+use tic_tac_toe_game::mcts_player::{search_position, MctsOptions};
+use tic_tac_toe_game::opening_book::get_book_move;
+#[cfg(feature = "rest-api")]
+use tic_tac_toe_game::rest_api::{ApiGameHandle, GameApi};
+use tic_tac_toe_game::settings::commands::{
+    CLEAR_COMMAND, HELP_COMMAND, HINT_COMMAND, PUZZLE_COMMANDS, QUIT_COMMAND,
 };
+use tic_tac_toe_game::settings::network_options::DISCOVERY_LISTEN_DURATION;
 
-pub fn handle_host_type_communication(arguments: Arguments) {
-    let mut host_type_object: Box<dyn HostTypeObject> =
-        HostTypeObjectFactory::create_host_type_object(arguments);
+pub struct HostTypeObjectFactory;
+impl HostTypeObjectFactory {
+    pub fn create_host_type_object(
+        arguments: Arguments,
+        event_handler: GameEventHandler,
+    ) -> Box<dyn HostTypeObject> {
+        match arguments.host_type {
+            HostType::Server => Box::new(NetworkGame::listen(
+                arguments.ip_addr,
+                arguments.port,
+                arguments.start_order,
+                arguments.variant,
+                arguments.connection_options,
+                event_handler,
+            )),
+            // Port and address are required for client by argument processing.
+            HostType::Client => Box::new(NetworkGame::connect(
+                arguments.port.unwrap(),
+                &arguments.host.unwrap(),
+                arguments.start_order,
+                arguments.variant,
+                arguments.connection_options,
+                event_handler,
+            )),
+            // Engine is required for this host type by argument processing.
+            HostType::Engine => Box::new(
+                EngineGame::start(
+                    &arguments.engine.unwrap(),
+                    arguments.start_order,
+                    arguments.variant,
+                    event_handler,
+                )
+                .unwrap_or_else(|err| panic!("Engine failed to start: {}.", err)),
+            ),
+            HostType::Hub => unreachable!("Hub does not play, it is run by run_hub."),
+            HostType::Discover => {
+                unreachable!("Discovered game is joined as client, see choose_discovered_game.")
+            }
+        }
+    }
+}
+
+// Searches the current position for the local player, options as of mcts player.
+fn show_hint(options: &str, host_type_object: &dyn HostTypeObject) {
+    let options = match MctsOptions::from_str(options) {
+        Ok(options) if options.board.is_some() => {
+            print_output("Hint searches the playboard of the game, board size cannot be set.");
+            return;
+        }
+        Ok(options) => options,
+        Err(msg) => {
+            print_output(msg);
+            return;
+        }
+    };
+
+    let (variant, moves) = {
+        let session = host_type_object.get_session();
+        let session = session.lock().unwrap();
+        let status = session.get_game_status();
+
+        if !status.game_running || !status.my_turn {
+            print_output("Hint is available only on your turn in running game.");
+            return;
+        }
+
+        (session.get_variant(), get_move_lines(session.get_history()))
+    };
+
+    match get_book_move(variant, &moves) {
+        Some(book_move) => print_book_hint(&book_move),
+        None => print_hint(&search_position(variant, &moves, &options)),
+    }
+}
+
+// Returns false when application should quit.
+pub fn process_command(line: String, host_type_object: &mut Box<dyn HostTypeObject>) -> bool {
+    let (command, options) = line.split_once(' ').unwrap_or((&line, ""));
+
+    if command == HINT_COMMAND {
+        show_hint(options, host_type_object.as_ref());
+    } else if line == HELP_COMMAND {
+        print_game_help();
+    } else if line == CLEAR_COMMAND {
+        if clearscreen::clear().is_err() {
+            print_output("Failed to clear window.");
+        }
+
+        print_game_help();
+    } else if line == QUIT_COMMAND {
+        return false;
+    } else if PUZZLE_COMMANDS.contains(&command) {
+        print_puzzle_command_outside_puzzle_mode(command);
+    } else {
+        host_type_object.send_message(line);
+    }
+
+    true
+}
+
+fn run_line_interface(host_type_object: &mut Box<dyn HostTypeObject>) {
+    if std::io::stdin().is_terminal() {
+        match run_line_editor(host_type_object) {
+            Ok(()) => return,
+            Err(err) => print_output(format!(
+                "Line editor failed: {}. Continuing without it.",
+                err
+            )),
+        }
+    }
 
     print_game_welcome_message();
 
@@ -18,28 +151,190 @@ pub fn handle_host_type_communication(arguments: Arguments) {
             break;
         }
 
-        line = line.trim().to_string();
+        if !process_command(line.trim().to_string(), host_type_object) {
+            break;
+        }
+
+        line = String::new();
+    }
+}
+
+// Lets the player pick one of the games announced on local network and turns the
+// arguments into client ones joining it. None when there is nothing to join.
+fn choose_discovered_game(arguments: Arguments) -> Option<Arguments> {
+    println!("Looking for games on local network...");
+
+    let games = match discover_games(DISCOVERY_LISTEN_DURATION) {
+        Ok(games) => games,
+        Err(err) => {
+            println!("Discovery failed: {}.", err);
+            return None;
+        }
+    };
+
+    print_discovered_games(&games);
+
+    if games.is_empty() {
+        return None;
+    }
+
+    let game = loop {
+        println!("Choose number of the game to join, empty line to quit:");
 
-        if line == HELP_COMMAND {
-            print_game_help();
-        } else if line == CLEAR_COMMAND {
-            if clearscreen::clear().is_err() {
-                println!("Failed to clear window.");
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+            return None;
+        }
+
+        match line.trim().parse::<usize>() {
+            Ok(number) if (1..=games.len()).contains(&number) => {
+                let game = &games[number - 1];
+
+                if game.announcement.open {
+                    break game.clone();
+                }
+
+                println!("Game {} is full already.", number);
             }
+            _ => println!("Invalid number {}.", line.trim()),
+        }
+    };
 
-            print_game_help();
-        } else if line == PLAY_AGAIN_COMMAND {
-            host_type_object.new_game_request(line + "\n");
-        } else if line == AGREE_COMMAND {
-            host_type_object.new_game_agreement(line + "\n");
-        } else if line == QUIT_COMMAND {
-            break;
-        } else {
-            host_type_object.send_message(line + "\n");
+    let announcement = game.announcement;
+    let mut connection_options = arguments.connection_options;
+
+    // Server tells how to connect, password is the only thing player must know.
+    connection_options.tls = announcement.tls;
+    if !announcement.password {
+        connection_options.password = None;
+    } else if connection_options.password.is_none() {
+        let password = prompt_password();
+
+        if password.is_empty() {
+            println!("Game requires password.");
+            return None;
         }
 
-        line = String::new();
+        connection_options.password = Some(password);
+    }
+
+    Some(Arguments {
+        host_type: HostType::Client,
+        port: Some(announcement.port),
+        ip_addr: None,
+        host: Some(game.ip_addr.to_string()),
+        start_order: announcement.start_order.oponent(),
+        variant: announcement.variant,
+        connection_options,
+        api_port: arguments.api_port,
+        api_ip_addr: arguments.api_ip_addr,
+        engine: arguments.engine,
+        tournament: None,
+        line_mode: arguments.line_mode,
+    })
+}
+
+// Moves posted to the API are not authenticated, so it is reachable by the local player
+// only unless its address is given explicitly.
+#[cfg(feature = "rest-api")]
+fn start_game_api(arguments: &Arguments) -> Option<(GameApi, ApiGameHandle)> {
+    let api_port = arguments.api_port?;
+    let ip_addr = arguments
+        .api_ip_addr
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+    match GameApi::start(SocketAddr::new(ip_addr, api_port)) {
+        Ok(game_api) => {
+            print_game_api_address(game_api.get_addr());
+            let api_game = game_api.add_game(arguments.variant);
+
+            Some((game_api, api_game))
+        }
+        Err(err) => {
+            println!("Game API failed to start: {}. Continuing without it.", err);
+            None
+        }
+    }
+}
+
+pub fn handle_host_type_communication(arguments: Arguments) {
+    if arguments.host_type == HostType::Hub {
+        let event_handler = Arc::new(print_hub_event);
+
+        if let Err(err) = run_hub(
+            arguments.ip_addr,
+            arguments.port,
+            arguments.tournament,
+            event_handler,
+        ) {
+            println!("Hub stopped: {}.", err);
+        }
+        return;
+    }
+
+    let arguments = if arguments.host_type == HostType::Discover {
+        match choose_discovered_game(arguments) {
+            Some(arguments) => arguments,
+            None => return,
+        }
+    } else {
+        arguments
+    };
+
+    let line_mode =
+        arguments.line_mode || !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal();
+    let variant = arguments.variant;
+
+    #[cfg(feature = "rest-api")]
+    let game_api = start_game_api(&arguments);
+
+    let event_handler: GameEventHandler = Arc::new(print_game_event);
+    #[cfg(feature = "rest-api")]
+    let event_handler = match &game_api {
+        Some((_, api_game)) => api_game.wrap_event_handler(event_handler),
+        None => event_handler,
+    };
+
+    // Engine plays instead of the local player, in local game it is the oponent.
+    let mut engine_player = match &arguments.engine {
+        Some(engine) if arguments.host_type != HostType::Engine => Some(
+            EnginePlayer::spawn(
+                engine,
+                arguments.variant,
+                arguments.start_order,
+                event_handler.clone(),
+            )
+            .unwrap_or_else(|err| panic!("Engine failed to start: {}.", err)),
+        ),
+        _ => None,
+    };
+
+    let event_handler = match &engine_player {
+        Some(engine_player) => engine_player.wrap_event_handler(event_handler),
+        None => event_handler,
+    };
+
+    let mut host_type_object: Box<dyn HostTypeObject> =
+        HostTypeObjectFactory::create_host_type_object(arguments, event_handler);
+
+    if let Some(engine_player) = &engine_player {
+        engine_player.attach(host_type_object.as_ref());
+    }
+
+    #[cfg(feature = "rest-api")]
+    if let Some((_, api_game)) = &game_api {
+        api_game.attach(host_type_object.as_ref());
+    }
+
+    if line_mode {
+        run_line_interface(&mut host_type_object);
+    } else {
+        run_text_user_interface(&mut host_type_object, variant);
     }
 
     host_type_object.stop();
+
+    if let Some(engine_player) = engine_player.as_mut() {
+        engine_player.stop();
+    }
 }
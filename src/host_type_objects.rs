@@ -1,386 +1,487 @@
-use local_ip_address::local_ip;
-use std::io::Write;
-use std::net::{IpAddr, Shutdown, TcpListener, TcpStream};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::thread::JoinHandle;
-
-use crate::cli_args_processing::{Arguments, HostType, StartOrder};
-use crate::host_type_objects_handlers::{
-    handle_client, handle_server, place_on_board, restart_game,
+use tokio::io::{duplex, split, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{lookup_host, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::timeout;
+use tokio_rustls::TlsAcceptor;
+
+use crate::authentication::{authenticate_client, authenticate_to_server};
+use crate::game_discovery::{announce_game, GameAnnouncement};
+use crate::game_event::{GameEvent, GameEventHandler};
+use crate::game_options::{ConnectionOptions, GameVariant, StartOrder};
+use crate::game_session::{GameSession, GameStatus, SessionEffect, SharedGameSession};
+use crate::host_type_objects_utility::{
+    accept_connection, bind_tcp_listener, create_runtime, get_bind_ip_addr, get_first_free_port,
+    get_reachable_ip_addrs,
 };
-use crate::host_type_objects_utility::{get_first_free_port, print_server_game_setup};
-use crate::playboard::Playboard;
-use crate::settings::commands::{AGREE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND, PLAY_AGAIN_COMMAND};
-use colored::Colorize;
-
-fn run_func_in_thread(
-    func_host_type_object_handler: fn(
-        Arc<Mutex<TcpStream>>,
-        Arc<AtomicBool>,
-        Arc<AtomicBool>,
-        Arc<Mutex<Playboard>>,
-        StartOrder,
-        Arc<AtomicBool>,
-        Arc<AtomicBool>,
-        Arc<AtomicBool>,
-    ),
-    stream: TcpStream,
-    start_order: StartOrder,
-) -> (
-    JoinHandle<()>,
-    Arc<Mutex<TcpStream>>,
-    Arc<AtomicBool>,
-    Arc<AtomicBool>,
-    Arc<Mutex<Playboard>>,
-    Arc<AtomicBool>,
-    Arc<AtomicBool>,
-    Arc<AtomicBool>,
-) {
-    let arc_stream = Arc::new(Mutex::new(stream));
-    let arc_run_app = Arc::new(AtomicBool::new(true));
-    let arc_run_game = Arc::new(AtomicBool::new(true));
-    let arc_playboard = Arc::new(Mutex::new(Playboard::new()));
-    let arc_my_turn = Arc::new(AtomicBool::new(start_order == StartOrder::First));
-    let arc_new_game_req = Arc::new(AtomicBool::new(false));
-    let arc_new_game_desirable = Arc::new(AtomicBool::new(false));
-
-    let handler_thread;
-    {
-        let arc_stream = arc_stream.clone();
-        let arc_run_app = arc_run_app.clone();
-        let arc_run_game = arc_run_game.clone();
-        let arc_playboard = arc_playboard.clone();
-        let arc_my_turn = arc_my_turn.clone();
-        let arc_new_game_req = arc_new_game_req.clone();
-        let arc_new_game_desirable = arc_new_game_desirable.clone();
-
-        handler_thread = thread::spawn(move || {
-            func_host_type_object_handler(
-                arc_stream,
-                arc_run_app,
-                arc_run_game,
-                arc_playboard,
-                start_order,
-                arc_my_turn,
-                arc_new_game_req,
-                arc_new_game_desirable,
-            );
-        });
-    }
+use crate::secure_connection::{connect_tls, ServerIdentity};
+use crate::settings::network_options::{CONNECT_TIMEOUT, TLS_HANDSHAKE_TIMEOUT};
+use crate::web_transport::{get_web_client_page, run_web_listener, WebGameConnection};
 
-    (
-        handler_thread,
-        arc_stream,
-        arc_run_app,
-        arc_run_game,
-        arc_playboard,
-        arc_my_turn,
-        arc_new_game_req,
-        arc_new_game_desirable,
-    )
-}
+// Lines waiting between both sides of the local game.
+const LOCAL_GAME_BUFFER_SIZE: usize = 4096;
 
+/// Passes commands of the local player to the game from any thread.
+pub type CommandSender = Arc<dyn Fn(String) + Send + Sync>;
+
+/// One side of the network game. Commands are the lines defined in
+/// [`crate::settings::commands`].
 pub trait HostTypeObject {
+    fn get_session(&self) -> SharedGameSession;
+    fn get_game_status(&self) -> GameStatus;
+    /// Passes command of the local player to the event loop, which applies it to the
+    /// session and sends it to the oponent.
     fn send_message(&self, msg: String);
-    fn new_game_request(&mut self, msg: String);
-    fn new_game_agreement(&mut self, msg: String);
+    /// Sender of commands for other front-ends, it does not keep the game running.
+    fn get_command_sender(&self) -> CommandSender;
+    /// Closes the connection and waits for the event loop.
     fn stop(&mut self);
 }
 
-pub struct Server {
-    handler_thread: Option<JoinHandle<()>>,
-    arc_stream: Arc<Mutex<TcpStream>>,
-    arc_run_app: Arc<AtomicBool>,
-    arc_run_game: Arc<AtomicBool>,
-    arc_playboard: Arc<Mutex<Playboard>>,
-    start_order: StartOrder,
-    arc_my_turn: Arc<AtomicBool>,
-    arc_new_game_req: Arc<AtomicBool>,
-    arc_new_game_desirable: Arc<AtomicBool>,
+// Plain tcp, TLS or bridged WebSocket stream.
+pub(crate) trait GameStream: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<S: AsyncRead + AsyncWrite + Send + Unpin> GameStream for S {}
+
+// Write half is None when oponent already closed the connection.
+async fn apply_session_effects<W: AsyncWrite + Unpin>(
+    effects: Vec<SessionEffect>,
+    write_half: Option<&mut W>,
+    event_handler: &GameEventHandler,
+) {
+    let mut write_half = write_half;
+
+    for effect in effects {
+        match effect {
+            SessionEffect::Send(line) => {
+                let sent = match write_half.as_mut() {
+                    Some(write_half) => write_half
+                        .write_all(format!("{}\n", line).as_bytes())
+                        .await
+                        .is_ok(),
+                    None => false,
+                };
+
+                if !sent {
+                    event_handler(GameEvent::SendFailed);
+                }
+            }
+            SessionEffect::Emit(event) => event_handler(event),
+        }
+    }
 }
 
-impl Server {
-    pub fn new(port: Option<u16>, start_order: StartOrder) -> Self {
-        let port: u16 = match port {
-            Some(port) => port,
-            None => get_first_free_port(),
+// Event loop of one game, waits for lines of the oponent and commands of the local
+// player at once. Ends when front-end drops the command sender.
+async fn run_connection(
+    stream: Box<dyn GameStream>,
+    arc_session: SharedGameSession,
+    mut commands: UnboundedReceiver<String>,
+    event_handler: GameEventHandler,
+) {
+    let (read_half, mut write_half) = split(stream);
+    let mut lines = BufReader::new(read_half).lines();
+    let mut connected = true;
+
+    loop {
+        let effects = tokio::select! {
+            line = lines.next_line(), if connected => match line {
+                Ok(Some(line)) => arc_session.lock().unwrap().handle_remote(&line),
+                _ => {
+                    connected = false;
+                    arc_session.lock().unwrap().handle_disconnect()
+                }
+            },
+            command = commands.recv() => match command {
+                Some(command) => arc_session.lock().unwrap().handle_local(&command),
+                None => break,
+            },
         };
 
-        let local_ip = local_ip().unwrap();
-
-        print_server_game_setup(local_ip, port);
+        apply_session_effects(
+            effects,
+            Some(&mut write_half).filter(|_| connected),
+            &event_handler,
+        )
+        .await;
+    }
 
-        let listener = TcpListener::bind(format!("{}:{}", local_ip, port))
-            .expect("Cannot bind socket for tcp listener.");
+    let _ = write_half.shutdown().await;
+}
 
-        let (stream, client_addr) = listener
-            .accept()
-            .expect("Failed to accept client connection.");
+// Tries all addresses the host name resolves to, error of the last one is returned.
+async fn connect_to_host(host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut last_error = io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} does not resolve to any address", host),
+    );
+
+    for addr in lookup_host((host, port)).await? {
+        match timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(err)) => last_error = err,
+            Err(_) => {
+                last_error = io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("connecting to {} timed out", addr),
+                )
+            }
+        }
+    }
 
-        println!("Second player connected from {}.\n", client_addr);
+    Err(last_error)
+}
 
-        let (
-            handler_thread,
-            arc_stream,
-            arc_run_app,
-            arc_run_game,
-            arc_playboard,
-            arc_my_turn,
-            arc_new_game_req,
-            arc_new_game_desirable,
-        ) = run_func_in_thread(handle_client, stream, start_order);
+// TLS handshake and authentication of the client run in their own task, so client which
+// does not answer cannot keep others from connecting. Error is the rejection reason.
+async fn prepare_client_connection(
+    (stream, client_addr): WebGameConnection,
+    tls_acceptor: Option<TlsAcceptor>,
+    password: Option<String>,
+) -> (Result<Box<dyn GameStream>, String>, SocketAddr) {
+    let stream: Box<dyn GameStream> = match tls_acceptor {
+        None => stream,
+        Some(tls_acceptor) => {
+            match timeout(TLS_HANDSHAKE_TIMEOUT, tls_acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => Box::new(stream),
+                Ok(Err(err)) => {
+                    return (Err(format!("TLS handshake failed: {}", err)), client_addr)
+                }
+                Err(_) => return (Err("TLS handshake timed out".to_string()), client_addr),
+            }
+        }
+    };
 
-        drop(listener);
+    // Buffered reader is kept, it may already hold the first game messages.
+    let mut stream = BufReader::new(stream);
 
-        Self {
-            handler_thread: Some(handler_thread),
-            arc_stream,
-            arc_run_app,
-            arc_run_game,
-            arc_playboard,
-            start_order,
-            arc_my_turn,
-            arc_new_game_req,
-            arc_new_game_desirable,
+    if let Some(password) = password {
+        if let Err(err) = authenticate_client(&mut stream, &password).await {
+            return (Err(format!("authentication failed: {}", err)), client_addr);
         }
     }
-}
 
-impl HostTypeObject for Server {
-    fn send_message(&self, msg: String) {
-        if msg.starts_with(PLACE_ON_PLAYBOARD_COMMAND) {
-            if !self.arc_run_game.load(Ordering::Relaxed) {
-                let msg = format!("You can no longer play symbol; game finished.\nYou can start another one with {}.", PLAY_AGAIN_COMMAND);
-                println!("{}", msg.red().bold());
-                return;
-            }
+    (Ok(Box::new(stream)), client_addr)
+}
 
-            if !self.arc_my_turn.load(Ordering::Relaxed) {
-                println!(
-                    "{}",
-                    "It is not your turn, you cannot place symbol. Wait for your oponent."
-                        .red()
-                        .bold()
-                );
-                return;
-            }
+/// Game played over tcp connection, from either side of it.
+pub struct NetworkGame {
+    runtime: Runtime,
+    connection_task: Option<JoinHandle<()>>,
+    // Server keeps announcing the game as full while it is running.
+    announcement_task: Option<JoinHandle<()>>,
+    command_sender: Option<UnboundedSender<String>>,
+    arc_session: SharedGameSession,
+    event_handler: GameEventHandler,
+}
 
-            let arc_playboard = self.arc_playboard.clone();
-            place_on_board(
-                msg.clone(),
-                arc_playboard,
-                &self.arc_run_game,
-                self.start_order,
-                "You".to_string(),
-            );
+impl NetworkGame {
+    /// Waits for the second player, blocks until the connection is accepted. Listens on
+    /// address of the network interface unless other address is given, '::' listens on
+    /// all addresses of both IP versions.
+    pub fn listen(
+        ip_addr: Option<IpAddr>,
+        port: Option<u16>,
+        start_order: StartOrder,
+        variant: GameVariant,
+        connection_options: ConnectionOptions,
+        event_handler: GameEventHandler,
+    ) -> Self {
+        let port: u16 = match port {
+            Some(port) => port,
+            None => get_first_free_port(),
+        };
 
-            self.arc_my_turn.store(false, Ordering::Relaxed);
-        }
+        let bind_ip_addr = get_bind_ip_addr(ip_addr);
 
-        let mut guard_stream = self.arc_stream.lock().unwrap();
+        let tls_acceptor = connection_options.tls.then(|| {
+            let identity =
+                ServerIdentity::load_or_create().expect("Failed to load TLS certificate.");
 
-        guard_stream.write(msg.as_bytes()).unwrap();
-        guard_stream.flush().expect("Flush failed.");
-    }
+            event_handler(GameEvent::CertificateFingerprint {
+                fingerprint: identity.get_fingerprint(),
+            });
 
-    fn new_game_request(&mut self, msg: String) {
-        println!(
-            "{}",
-            format!(
-                "Sending new game request, your oponent must agree by {}.",
-                AGREE_COMMAND
-            )
-            .green()
-            .bold()
-        );
-        self.arc_new_game_desirable.store(true, Ordering::Relaxed);
-
-        self.send_message(msg);
-    }
+            identity
+                .create_acceptor()
+                .expect("Failed to set up TLS with the certificate.")
+        });
 
-    fn new_game_agreement(&mut self, msg: String) {
-        if self.arc_new_game_req.load(Ordering::Relaxed) {
-            let arc_playboard = self.arc_playboard.clone();
+        let runtime = create_runtime();
 
-            restart_game(
-                arc_playboard,
-                &self.arc_run_game,
-                &self.arc_new_game_req,
-                &self.arc_new_game_desirable,
-            );
+        let bind = |port: u16| {
+            let addr = SocketAddr::new(bind_ip_addr, port);
 
-            self.send_message(msg);
-        }
-    }
+            runtime.block_on(async {
+                bind_tcp_listener(addr).unwrap_or_else(|err| {
+                    panic!("Cannot bind socket for tcp listener on {}: {}", addr, err)
+                })
+            })
+        };
 
-    fn stop(&mut self) {
-        println!("Stopping tic tac toe game...");
+        let listener = bind(port);
 
-        let guard_stream = self.arc_stream.lock().unwrap();
+        event_handler(GameEvent::WaitingForOponent {
+            ip_addrs: get_reachable_ip_addrs(bind_ip_addr),
+            port,
+        });
 
-        // stop reading thread
-        self.arc_run_app.store(false, Ordering::Relaxed);
+        // Sender is kept here, so receiving waits forever without web listener.
+        let (web_game_sender, mut web_game_receiver) = unbounded_channel();
 
-        guard_stream
-            .shutdown(Shutdown::Both)
-            .expect("Stream shutdown failed.");
+        let web_listener_task = connection_options.web_port.map(|web_port| {
+            let web_listener = bind(web_port);
 
-        if let Some(handler_thread) = self.handler_thread.take() {
-            handler_thread.join().expect("Failed to join thread.");
-        }
-    }
-}
+            event_handler(GameEvent::WaitingForBrowserOponent {
+                ip_addrs: get_reachable_ip_addrs(bind_ip_addr),
+                port: web_port,
+            });
 
-pub struct Client {
-    handler_thread: Option<JoinHandle<()>>,
-    arc_stream: Arc<Mutex<TcpStream>>,
-    arc_run_app: Arc<AtomicBool>,
-    arc_run_game: Arc<AtomicBool>,
-    arc_playboard: Arc<Mutex<Playboard>>,
-    start_order: StartOrder,
-    arc_my_turn: Arc<AtomicBool>,
-    arc_new_game_req: Arc<AtomicBool>,
-    arc_new_game_desirable: Arc<AtomicBool>,
-}
+            runtime.spawn(run_web_listener(
+                web_listener,
+                get_web_client_page(variant, start_order.oponent()),
+                web_game_sender.clone(),
+            ))
+        });
 
-impl Client {
-    pub fn new(port: Option<u16>, ip_addr: Option<IpAddr>, start_order: StartOrder) -> Self {
-        assert!(port.is_some());
-        assert!(ip_addr.is_some());
-
-        let ip_addr = ip_addr.unwrap();
-        let port = port.unwrap();
-
-        let stream = TcpStream::connect(format!("{}:{}", ip_addr, port))
-            .expect("Client failed to connect to the server.");
-
-        println!(
-            "Successfully connected to player one to {} on port {}.\n",
-            ip_addr, port
-        );
-
-        let (
-            handler_thread,
-            arc_stream,
-            arc_run_app,
-            arc_run_game,
-            arc_playboard,
-            arc_my_turn,
-            arc_new_game_req,
-            arc_new_game_desirable,
-        ) = run_func_in_thread(handle_server, stream, start_order);
+        let arc_open = Arc::new(AtomicBool::new(true));
+        let announcement_task = runtime.spawn(announce_game(
+            GameAnnouncement {
+                game_name: connection_options.game_name.clone(),
+                nickname: connection_options.nickname.clone(),
+                port,
+                variant,
+                start_order,
+                open: true,
+                tls: connection_options.tls,
+                password: connection_options.password.is_some(),
+            },
+            bind_ip_addr,
+            arc_open.clone(),
+        ));
+
+        let stream = runtime.block_on(async {
+            // Clients in TLS handshake or authentication, aborted when the game starts.
+            let mut pending_clients = JoinSet::new();
+
+            loop {
+                tokio::select! {
+                    accepted = accept_connection(&listener) => {
+                        let (stream, client_addr) =
+                            accepted.expect("Failed to accept client connection.");
+
+                        pending_clients.spawn(prepare_client_connection(
+                            (Box::new(stream), client_addr),
+                            tls_acceptor.clone(),
+                            connection_options.password.clone(),
+                        ));
+                    }
+                    // Browser connections are never encrypted by the game itself.
+                    Some(web_connection) = web_game_receiver.recv() => {
+                        pending_clients.spawn(prepare_client_connection(
+                            web_connection,
+                            None,
+                            connection_options.password.clone(),
+                        ));
+                    }
+                    Some(prepared) = pending_clients.join_next() => {
+                        match prepared.expect("Client connection task failed.") {
+                            (Ok(stream), client_addr) => {
+                                arc_open.store(false, Ordering::Relaxed);
+                                event_handler(GameEvent::Connected { addr: client_addr });
+
+                                break stream;
+                            }
+                            (Err(reason), client_addr) => {
+                                event_handler(GameEvent::ConnectionRejected {
+                                    addr: client_addr,
+                                    reason,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        });
 
-        Self {
-            handler_thread: Some(handler_thread),
-            arc_stream,
-            arc_run_app,
-            arc_run_game,
-            arc_playboard,
-            start_order,
-            arc_my_turn,
-            arc_new_game_req,
-            arc_new_game_desirable,
+        // Other browsers cannot join the running game.
+        if let Some(web_listener_task) = web_listener_task {
+            web_listener_task.abort();
         }
-    }
-}
 
-impl HostTypeObject for Client {
-    fn send_message(&self, msg: String) {
-        if msg.starts_with(PLACE_ON_PLAYBOARD_COMMAND) {
-            if !self.arc_run_game.load(Ordering::Relaxed) {
-                let msg = format!("You can no longer play symbol; game finished.\nYou can start another one with {}.", PLAY_AGAIN_COMMAND);
-                println!("{}", msg.red().bold());
-                return;
-            }
+        let mut network_game = Self::new(runtime, stream, start_order, variant, event_handler);
+        network_game.announcement_task = Some(announcement_task);
 
-            if !self.arc_my_turn.load(Ordering::Relaxed) {
-                println!(
-                    "{}",
-                    "It is not your turn, you cannot place symbol. Wait for your oponent."
-                        .red()
-                        .bold()
-                );
-                return;
-            }
+        network_game
+    }
 
-            let arc_playboard = self.arc_playboard.clone();
-            if place_on_board(
-                msg.clone(),
-                arc_playboard,
-                &self.arc_run_game,
-                self.start_order,
-                "You".to_string(),
-            ) {
-                self.arc_my_turn.store(false, Ordering::Relaxed);
+    /// Connects to the server run by the second player or to the hub. Host is either
+    /// IP address or name resolved to it.
+    pub fn connect(
+        port: u16,
+        host: &str,
+        start_order: StartOrder,
+        variant: GameVariant,
+        connection_options: ConnectionOptions,
+        event_handler: GameEventHandler,
+    ) -> Self {
+        let runtime = create_runtime();
+
+        let stream = runtime.block_on(async {
+            let stream = connect_to_host(host, port)
+                .await
+                .unwrap_or_else(|err| panic!("Client failed to connect to the server: {}.", err));
+
+            let addr = stream
+                .peer_addr()
+                .expect("Failed to get address of the server.");
+
+            let stream: Box<dyn GameStream> = if connection_options.tls {
+                let (stream, trusted_certificate) = connect_tls(stream, addr)
+                    .await
+                    .unwrap_or_else(|err| panic!("TLS handshake with the server failed: {}", err));
+
+                event_handler(GameEvent::CertificateTrusted {
+                    fingerprint: trusted_certificate.fingerprint,
+                    newly_pinned: trusted_certificate.newly_pinned,
+                });
+
+                Box::new(stream)
+            } else {
+                Box::new(stream)
+            };
+
+            let mut stream = BufReader::new(stream);
+
+            if let Some(password) = &connection_options.password {
+                authenticate_to_server(&mut stream, password)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Authentication with the server failed: {}.", err)
+                    });
             }
-        }
 
-        let mut guard_stream = self.arc_stream.lock().unwrap();
+            event_handler(GameEvent::Connected { addr });
 
-        guard_stream.write(msg.as_bytes()).unwrap();
-        guard_stream.flush().expect("Flush failed.");
-    }
+            Box::new(stream) as Box<dyn GameStream>
+        });
 
-    fn new_game_request(&mut self, msg: String) {
-        println!(
-            "{}",
-            format!(
-                "Sending new game request, your oponent must agree by {}.",
-                AGREE_COMMAND
-            )
-            .green()
-            .bold()
-        );
-        self.arc_new_game_desirable.store(true, Ordering::Relaxed);
-
-        self.send_message(msg);
+        Self::new(runtime, stream, start_order, variant, event_handler)
     }
 
-    fn new_game_agreement(&mut self, msg: String) {
-        if self.arc_new_game_req.load(Ordering::Relaxed) {
-            let arc_playboard = self.arc_playboard.clone();
+    /// Both sides of a game played within this process, connected in memory. Second one
+    /// is played by the oponent, e.g. by [`crate::engine_player::EnginePlayer`].
+    pub fn local_pair(
+        start_order: StartOrder,
+        variant: GameVariant,
+        event_handler: GameEventHandler,
+        oponent_event_handler: GameEventHandler,
+    ) -> (Self, Self) {
+        let (stream, oponent_stream) = duplex(LOCAL_GAME_BUFFER_SIZE);
+
+        (
+            Self::new(
+                create_runtime(),
+                Box::new(stream),
+                start_order,
+                variant,
+                event_handler,
+            ),
+            Self::new(
+                create_runtime(),
+                Box::new(oponent_stream),
+                start_order.oponent(),
+                variant,
+                oponent_event_handler,
+            ),
+        )
+    }
 
-            restart_game(
-                arc_playboard,
-                &self.arc_run_game,
-                &self.arc_new_game_req,
-                &self.arc_new_game_desirable,
-            );
+    fn new(
+        runtime: Runtime,
+        stream: Box<dyn GameStream>,
+        start_order: StartOrder,
+        variant: GameVariant,
+        event_handler: GameEventHandler,
+    ) -> Self {
+        let arc_session = Arc::new(Mutex::new(GameSession::new(start_order, variant)));
+        let (command_sender, command_receiver) = unbounded_channel();
+
+        let connection_task = runtime.spawn(run_connection(
+            stream,
+            arc_session.clone(),
+            command_receiver,
+            event_handler.clone(),
+        ));
 
-            self.send_message(msg);
+        Self {
+            runtime,
+            connection_task: Some(connection_task),
+            announcement_task: None,
+            command_sender: Some(command_sender),
+            arc_session,
+            event_handler,
         }
     }
+}
 
-    fn stop(&mut self) {
-        println!("Stopping tic tac toe game...");
-
-        let guard_stream = self.arc_stream.lock().unwrap();
+impl HostTypeObject for NetworkGame {
+    fn get_session(&self) -> SharedGameSession {
+        self.arc_session.clone()
+    }
 
-        // stop reading thread
-        self.arc_run_app.store(false, Ordering::Relaxed);
+    fn get_game_status(&self) -> GameStatus {
+        self.arc_session.lock().unwrap().get_game_status()
+    }
 
-        guard_stream
-            .shutdown(Shutdown::Both)
-            .expect("Stream shutdown failed.");
+    fn send_message(&self, msg: String) {
+        let sent = match &self.command_sender {
+            Some(command_sender) => command_sender.send(msg).is_ok(),
+            None => false,
+        };
 
-        if let Some(handler_thread) = self.handler_thread.take() {
-            handler_thread.join().expect("Failed to join thread.");
+        if !sent {
+            (self.event_handler)(GameEvent::SendFailed);
         }
     }
-}
 
-pub struct HostTypeObjectFactory;
-impl HostTypeObjectFactory {
-    pub fn create_host_type_object(arguments: Arguments) -> Box<dyn HostTypeObject> {
-        match arguments.host_type {
-            HostType::Server => Box::new(Server::new(arguments.port, arguments.start_order)),
-            HostType::Client => Box::new(Client::new(
-                arguments.port,
-                arguments.ip_addr,
-                arguments.start_order,
-            )),
+    fn get_command_sender(&self) -> CommandSender {
+        let command_sender = self
+            .command_sender
+            .as_ref()
+            .map(|command_sender| command_sender.downgrade());
+        let event_handler = self.event_handler.clone();
+
+        Arc::new(move |msg| {
+            let sent = match command_sender.as_ref().and_then(|sender| sender.upgrade()) {
+                Some(command_sender) => command_sender.send(msg).is_ok(),
+                None => false,
+            };
+
+            if !sent {
+                event_handler(GameEvent::SendFailed);
+            }
+        })
+    }
+
+    fn stop(&mut self) {
+        (self.event_handler)(GameEvent::Stopping);
+
+        if let Some(announcement_task) = self.announcement_task.take() {
+            announcement_task.abort();
+        }
+
+        // Closed command channel ends the event loop.
+        self.command_sender.take();
+
+        if let Some(connection_task) = self.connection_task.take() {
+            self.runtime
+                .block_on(connection_task)
+                .expect("Failed to join connection task.");
         }
     }
 }
@@ -1,9 +1,12 @@
-use colored::*;
 use core::panic;
+use local_ip_address::{list_afinet_netifas, local_ip};
 use portpicker::is_free_tcp;
-use std::net::IpAddr;
-
-use crate::settings::commands::*;
+use socket2::{Domain, Socket, Type};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::{Builder, Runtime};
 
 pub fn get_first_free_port() -> u16 {
     const MIN_DYNAMIC_PRIVATE_PORT: u16 = 49152;
@@ -18,53 +21,83 @@ pub fn get_first_free_port() -> u16 {
     panic!("Port not specified and none other port is free.")
 }
 
-pub fn print_server_game_setup(local_ip: IpAddr, port: u16) {
-    // todo: refactor
-    println!(
-        "{} is running on IP address {} and {}.\n{}\n",
-        "Tic tac toe game".magenta().bold(),
-        local_ip.to_string().magenta().bold(),
-        port.to_string().magenta().bold(),
-        "Connect second player as a client.".yellow().bold()
-    );
+// Files of the game are kept in home directory, in working directory when it is unknown.
+pub fn get_home_file_path(file_name: &str) -> PathBuf {
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(file_name),
+        None => PathBuf::from(file_name),
+    }
+}
+
+// Event loops run on their own worker thread, so front-end can block on user input.
+pub fn create_runtime() -> Runtime {
+    Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("Failed to create async runtime.")
+}
+
+// Address of the network interface when none is chosen, any address when even that
+// one is unknown.
+pub fn get_bind_ip_addr(ip_addr: Option<IpAddr>) -> IpAddr {
+    ip_addr.unwrap_or_else(|| local_ip().unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)))
+}
+
+/// Addresses the other player can use to reach listener bound to given address.
+pub fn get_reachable_ip_addrs(bind_ip_addr: IpAddr) -> Vec<IpAddr> {
+    if !bind_ip_addr.is_unspecified() {
+        return vec![bind_ip_addr];
+    }
+
+    let interface_addrs = list_afinet_netifas().unwrap_or_default();
+
+    let mut ip_addrs: Vec<IpAddr> = interface_addrs
+        .into_iter()
+        .map(|(_, ip_addr)| ip_addr)
+        // Listener on '::' is dual-stack, the one on '0.0.0.0' accepts only IPv4.
+        .filter(|ip_addr| bind_ip_addr.is_ipv6() || ip_addr.is_ipv4())
+        // Link-local IPv6 addresses are unusable without interface scope.
+        .filter(|ip_addr| match ip_addr {
+            IpAddr::V6(ip_addr) => (ip_addr.segments()[0] & 0xffc0) != 0xfe80,
+            IpAddr::V4(_) => true,
+        })
+        .collect();
+
+    // Loopback is listed last, it is usable only on the same machine.
+    ip_addrs.sort_by_key(|ip_addr| (ip_addr.is_loopback(), ip_addr.is_ipv6()));
+    ip_addrs.dedup();
+
+    if ip_addrs.is_empty() {
+        ip_addrs.push(bind_ip_addr);
+    }
+
+    ip_addrs
 }
 
-pub fn print_game_welcome_message() {
-    println!(
-        "Welcome to {}. Write {} if unsure what to do and {}.\n",
-        "tic tac toe game".magenta().bold(),
-        HELP_COMMAND.magenta().bold(),
-        "enjoy the game".magenta().bold(),
-    );
+/// Binds tcp listener, IPv6 listener accepts also IPv4 connections where the system
+/// allows it. Must be called within tokio runtime.
+pub fn bind_tcp_listener(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+
+    if addr.is_ipv6() {
+        let _ = socket.set_only_v6(false);
+    }
+
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(128)?;
+
+    TcpListener::from_std(socket.into())
 }
 
-pub fn print_game_help() {
-    println!(
-        "{}\n{}\n{}\n{}\n{}\n{}",
-        "Valid commands to use:\n".magenta().bold(),
-        format!(
-            "{}            Writes message for other player.",
-            PRIVATE_MESSAGE_COMMAND
-        )
-        .green()
-        .bold(),
-        format!(
-            "{}(2, 1)   Places player symbol to playboard. In this example on row 2 and col 1.",
-            PLACE_ON_PLAYBOARD_COMMAND
-        )
-        .green()
-        .bold(),
-        format!("{}          Clears window chat history.", CLEAR_COMMAND)
-            .green()
-            .bold(),
-        format!("{}           Quits application.", QUIT_COMMAND)
-            .green()
-            .bold(),
-        format!(
-            "{}      Sends request for game restart. Oponent must agree with {}",
-            PLAY_AGAIN_COMMAND, AGREE_COMMAND
-        )
-        .green()
-        .bold(),
-    );
+/// Accepts connection, IPv4 client of dual-stack listener gets its IPv4 address.
+pub async fn accept_connection(listener: &TcpListener) -> io::Result<(TcpStream, SocketAddr)> {
+    let (stream, addr) = listener.accept().await?;
+
+    Ok((
+        stream,
+        SocketAddr::new(addr.ip().to_canonical(), addr.port()),
+    ))
 }
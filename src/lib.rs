@@ -0,0 +1,44 @@
+//! Tic tac toe game engine for two players connected over the network.
+//!
+//! Playboards of all game variants implement [`playboard_variant::PlayboardVariant`].
+//! Rules of the network game are kept by [`game_session::GameSession`], which is driven
+//! over the connection by [`host_type_objects::NetworkGame`]. Neither prints anything
+//! itself; everything the player should see is passed as [`game_event::GameEvent`] to
+//! the handler given by front-end. Many games at once are served by [`game_hub`], which
+//! pairs players by [`matchmaking`] and runs [`tournament`]s, games on local network
+//! are found by [`game_discovery`]. Browser players join through [`web_transport`],
+//! dashboards and bots use `rest_api` of `rest-api` feature.
+//! External programs play through the engine protocol of [`engine_player`], players of
+//! [`ai_player`] such as [`mcts_player`], [`threat_player`] and the self-play trained one
+//! of [`q_learning`] are compared in headless games of [`arena`]. Players of
+//! [`personality`] play imperfectly on purpose and chat. Positions built by hand are
+//! solved as "win in N" puzzles of [`puzzle`].
+
+pub mod ai_player;
+pub mod arena;
+pub mod authentication;
+pub mod engine_player;
+pub mod game_discovery;
+pub mod game_event;
+pub mod game_hub;
+pub mod game_options;
+pub mod game_session;
+pub mod host_type_objects;
+pub mod host_type_objects_utility;
+pub mod matchmaking;
+pub mod mcts_player;
+pub mod numerical_playboard;
+pub mod opening_book;
+pub mod personality;
+pub mod playboard;
+pub mod playboard_variant;
+pub mod puzzle;
+pub mod q_learning;
+pub mod quantum_playboard;
+#[cfg(feature = "rest-api")]
+pub mod rest_api;
+pub mod secure_connection;
+pub mod settings;
+pub mod threat_player;
+pub mod tournament;
+pub mod web_transport;
@@ -0,0 +1,140 @@
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use crate::game_messages::print_game_welcome_message;
+use crate::game_output::{print_output, redirect_output_to_line_editor, restore_output};
+use crate::host_type_communication_handler::process_command;
+use tic_tac_toe_game::game_session::SharedGameSession;
+use tic_tac_toe_game::host_type_objects::HostTypeObject;
+use tic_tac_toe_game::host_type_objects_utility::get_home_file_path;
+use tic_tac_toe_game::settings::commands::{
+    ALL_COMMANDS, COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND,
+};
+use tic_tac_toe_game::settings::line_editor_options::{HISTORY_FILE_NAME, LINE_EDITOR_PROMPT};
+use tic_tac_toe_game::settings::playboard_options::PLAYBOARD_ROW_COL_SIZE;
+
+struct LineEditorHelper {
+    arc_session: SharedGameSession,
+}
+
+impl LineEditorHelper {
+    fn get_free_cells(&self) -> Vec<String> {
+        let guard_session = self.arc_session.lock().unwrap();
+        let playboard = guard_session.get_playboard();
+
+        let mut free_cells = Vec::new();
+
+        for row in 0..PLAYBOARD_ROW_COL_SIZE {
+            for col in 0..PLAYBOARD_ROW_COL_SIZE {
+                if playboard.is_cell_free(row, col) {
+                    free_cells.push(format!("({}, {})", row + 1, col + 1));
+                }
+            }
+        }
+
+        free_cells
+    }
+}
+
+impl Completer for LineEditorHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+
+        // Cells are completed after place and collapse commands, spaces are not significant.
+        if line.starts_with(PLACE_ON_PLAYBOARD_COMMAND) || line.starts_with(COLLAPSE_COMMAND) {
+            let start = match line.rfind('(') {
+                Some(start) if !line[start..].contains(')') => start,
+                _ => pos,
+            };
+
+            let typed = line[start..].replace(' ', "");
+
+            let candidates = self
+                .get_free_cells()
+                .into_iter()
+                .filter(|cell| cell.replace(' ', "").starts_with(&typed))
+                .collect();
+
+            return Ok((start, candidates));
+        }
+
+        if line.starts_with('/') && !line.contains(' ') {
+            let candidates = ALL_COMMANDS
+                .iter()
+                .filter(|command| command.starts_with(line))
+                .map(|command| command.to_string())
+                .collect();
+
+            return Ok((0, candidates));
+        }
+
+        Ok((pos, Vec::new()))
+    }
+}
+
+impl Hinter for LineEditorHelper {
+    type Hint = String;
+}
+
+impl Highlighter for LineEditorHelper {}
+
+impl Validator for LineEditorHelper {}
+
+impl Helper for LineEditorHelper {}
+
+// Reads commands with editing, persistent history and tab completion. Messages from
+// oponent are printed above the prompt so typed command is not garbled.
+pub fn run_line_editor(host_type_object: &mut Box<dyn HostTypeObject>) -> rustyline::Result<()> {
+    let mut editor: Editor<LineEditorHelper, DefaultHistory> = Editor::new()?;
+
+    editor.set_helper(Some(LineEditorHelper {
+        arc_session: host_type_object.get_session(),
+    }));
+
+    let history_file_path = get_home_file_path(HISTORY_FILE_NAME);
+
+    // History file does not exist on the first run.
+    let _ = editor.load_history(&history_file_path);
+
+    redirect_output_to_line_editor(editor.create_external_printer()?);
+
+    print_game_welcome_message();
+
+    let result = loop {
+        match editor.readline(LINE_EDITOR_PROMPT) {
+            Ok(line) => {
+                let line = line.trim().to_string();
+
+                if !line.is_empty() {
+                    editor.add_history_entry(line.as_str())?;
+                }
+
+                if !process_command(line, host_type_object) {
+                    break Ok(());
+                }
+            }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break Ok(()),
+            Err(err) => break Err(err),
+        }
+    };
+
+    restore_output();
+
+    if editor.save_history(&history_file_path).is_err() {
+        print_output("Failed to save command history.");
+    }
+
+    result
+}
@@ -1,21 +1,30 @@
 #[macro_use]
 extern crate clap;
-extern crate matrix_display;
 
-mod host_type_objects;
-mod host_type_objects_handlers;
-mod host_type_objects_utility;
-mod playboard;
-mod settings;
+mod game_messages;
+mod game_output;
+mod line_editor;
+mod text_user_interface;
 
 mod cli_args_processing;
-use cli_args_processing::{process_cli_arguments, Arguments};
+use cli_args_processing::{process_cli_arguments, CliCommand};
+
+mod arena_runner;
+use arena_runner::{run_arena_command, run_book_command, run_engine_command, run_train_command};
+
+mod puzzle_runner;
+use puzzle_runner::run_puzzle_command;
 
 mod host_type_communication_handler;
 use host_type_communication_handler::handle_host_type_communication;
 
 fn main() {
-    let arguments: Arguments = process_cli_arguments();
-
-    handle_host_type_communication(arguments);
+    match process_cli_arguments() {
+        CliCommand::Play(arguments) => handle_host_type_communication(arguments),
+        CliCommand::Arena(arguments) => run_arena_command(arguments),
+        CliCommand::Engine(player) => run_engine_command(&player),
+        CliCommand::Book(arguments) => run_book_command(arguments),
+        CliCommand::Train(arguments) => run_train_command(arguments),
+        CliCommand::Puzzle(arguments) => run_puzzle_command(arguments),
+    }
 }
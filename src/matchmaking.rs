@@ -0,0 +1,171 @@
+//! Queue of players waiting on the hub for an oponent.
+//!
+//! Players queue for a game variant, optionally only for oponents of given rating
+//! range. Ratings are Elo ratings kept by the hub for nicknames of players.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game_options::GameVariant;
+
+/// Rating of players who did not play yet.
+pub const INITIAL_RATING: f64 = 1500.0;
+
+// How much one game changes the rating at most.
+const RATING_K_FACTOR: f64 = 32.0;
+
+/// Ratings of oponents the player accepts, both inclusive.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RatingRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl RatingRange {
+    pub fn contains(&self, rating: f64) -> bool {
+        self.min as f64 <= rating && rating <= self.max as f64
+    }
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for RatingRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.min, self.max)
+    }
+}
+
+impl FromStr for RatingRange {
+    type Err = ();
+
+    fn from_str(range: &str) -> Result<RatingRange, Self::Err> {
+        let (min, max) = range.split_once('-').ok_or(())?;
+        let min = min.trim().parse::<u32>().map_err(|_| ())?;
+        let max = max.trim().parse::<u32>().map_err(|_| ())?;
+
+        if min > max {
+            return Err(());
+        }
+
+        Ok(RatingRange { min, max })
+    }
+}
+
+/// Arguments of the queue command, e.g. `quantum 1400-1600`. Both are optional.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct QueueRequest {
+    pub variant: Option<GameVariant>,
+    pub rating_range: Option<RatingRange>,
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for QueueRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let arguments: Vec<String> = [
+            self.variant.map(|variant| variant.to_string()),
+            self.rating_range.map(|range| range.to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        write!(f, "{}", arguments.join(" "))
+    }
+}
+
+impl FromStr for QueueRequest {
+    type Err = String;
+
+    fn from_str(arguments: &str) -> Result<QueueRequest, Self::Err> {
+        let mut request = QueueRequest::default();
+
+        for argument in arguments.split_whitespace() {
+            if let Ok(variant) = GameVariant::from_str(argument) {
+                request.variant = Some(variant);
+            } else if let Ok(range) = RatingRange::from_str(argument) {
+                request.rating_range = Some(range);
+            } else {
+                return Err(format!(
+                    "{} is neither game variant nor rating range like 1400-1600.",
+                    argument
+                ));
+            }
+        }
+
+        Ok(request)
+    }
+}
+
+/// Player waiting in the queue.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct QueueEntry {
+    /// Identifier of the player given by the hub.
+    pub id: u64,
+    pub rating: f64,
+    pub variant: GameVariant,
+    pub rating_range: Option<RatingRange>,
+}
+
+impl QueueEntry {
+    fn accepts(&self, oponent: &QueueEntry) -> bool {
+        self.variant == oponent.variant
+            && self
+                .rating_range
+                .is_none_or(|range| range.contains(oponent.rating))
+    }
+}
+
+/// Players waiting in the order they queued.
+#[derive(Clone, Debug, Default)]
+pub struct MatchQueue {
+    entries: Vec<QueueEntry>,
+}
+
+impl MatchQueue {
+    /// Pairs the player with the longest waiting one both accept each other, who is
+    /// removed from the queue and returned. Queues the player when there is none.
+    pub fn join(&mut self, entry: QueueEntry) -> Option<QueueEntry> {
+        self.leave(entry.id);
+
+        match self
+            .entries
+            .iter()
+            .position(|waiting| waiting.accepts(&entry) && entry.accepts(waiting))
+        {
+            Some(index) => Some(self.entries.remove(index)),
+            None => {
+                self.entries.push(entry);
+                None
+            }
+        }
+    }
+
+    /// Returns false when the player was not in the queue.
+    pub fn leave(&mut self, id: u64) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+
+        self.entries.len() != len
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Elo ratings of both players after the game, `winner` is index of the winner and
+/// `None` on draw.
+pub fn update_ratings(ratings: [f64; 2], winner: Option<usize>) -> [f64; 2] {
+    let expected = 1.0 / (1.0 + 10f64.powf((ratings[1] - ratings[0]) / 400.0));
+    let score = match winner {
+        Some(0) => 1.0,
+        Some(_) => 0.0,
+        None => 0.5,
+    };
+    let change = RATING_K_FACTOR * (score - expected);
+
+    [ratings[0] + change, ratings[1] - change]
+}
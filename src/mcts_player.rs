@@ -0,0 +1,579 @@
+//! Player searching moves by Monte Carlo tree search.
+//!
+//! Every playout descends the search tree by UCT, adds one new position and finishes the
+//! game by random moves. Threads search their own trees from the same position and
+//! their root statistics are summed. Search limited by playouts is deterministic when
+//! seeded with the same number of threads, search limited by time is not.
+//!
+//! Besides playboards of the game variants the player searches k-in-a-row boards given
+//! by [`BoardOptions`], e.g. gomoku, where full search is out of reach.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::ai_player::{
+    get_candidate_moves, get_playboard_moves, play_move, replay_moves, AiPlayer,
+};
+use crate::game_options::{GameVariant, StartOrder};
+use crate::opening_book::get_book_move;
+use crate::playboard::GameState;
+use crate::playboard_variant::PlayboardVariant;
+use crate::settings::ai_options::{
+    MCTS_DEFAULT_PLAYOUTS, MCTS_EXPLORATION, MCTS_PLAYER_NAME, MCTS_SEEDED_THREADS,
+};
+use crate::settings::commands::PLACE_ON_PLAYBOARD_COMMAND;
+use crate::threat_player::{decode_place_command, format_place_command, BoardOptions, ThreatBoard};
+
+/// Limits and settings of the search, e.g. `playouts=5000 threads=4 seed=7` or
+/// `size=15 win=5` for a larger board.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MctsOptions {
+    /// Playouts of all threads together.
+    pub playouts: Option<u32>,
+    /// Search stops when the time is up, even when playouts are left.
+    pub time_budget: Option<Duration>,
+    pub threads: usize,
+    /// Random moves are repeatable when set. Every thread searches by its own seed, so
+    /// the result is repeatable with the same number of threads only.
+    pub seed: Option<u64>,
+    /// Playboard of the game variant when None.
+    pub board: Option<BoardOptions>,
+}
+
+impl Default for MctsOptions {
+    fn default() -> Self {
+        Self {
+            playouts: Some(MCTS_DEFAULT_PLAYOUTS),
+            time_budget: None,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            seed: None,
+            board: None,
+        }
+    }
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for MctsOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut options = Vec::new();
+
+        if let Some(playouts) = self.playouts {
+            options.push(format!("playouts={}", playouts));
+        }
+        if let Some(time_budget) = self.time_budget {
+            options.push(format!("time={}", time_budget.as_millis()));
+        }
+        options.push(format!("threads={}", self.threads));
+        if let Some(seed) = self.seed {
+            options.push(format!("seed={}", seed));
+        }
+        if let Some(board) = self.board {
+            options.push(board.to_string());
+        }
+
+        write!(f, "{}", options.join(" "))
+    }
+}
+
+// Time is given in milliseconds. Only time limits the search when playouts are not
+// given with it. Seeded search runs on fixed number of threads unless they are given,
+// so it is repeatable on any machine.
+impl FromStr for MctsOptions {
+    type Err = String;
+
+    fn from_str(options: &str) -> Result<MctsOptions, Self::Err> {
+        let mut parsed = MctsOptions {
+            playouts: None,
+            ..MctsOptions::default()
+        };
+        let mut threads_given = false;
+        let mut board_options = Vec::new();
+
+        for option in options.split_whitespace() {
+            let invalid_option = || {
+                format!(
+                    "Invalid search option {}, use e.g. playouts=2000 time=500 threads=4 seed=7.",
+                    option
+                )
+            };
+            let (name, value) = option.split_once('=').ok_or_else(invalid_option)?;
+
+            if name == "size" || name == "win" {
+                board_options.push(option);
+                continue;
+            }
+
+            let value = value.parse::<u64>().map_err(|_| invalid_option())?;
+
+            match name {
+                "playouts" if value > 0 => {
+                    parsed.playouts = Some(u32::try_from(value).map_err(|_| invalid_option())?)
+                }
+                "time" if value > 0 => parsed.time_budget = Some(Duration::from_millis(value)),
+                "threads" if value > 0 => {
+                    parsed.threads = value as usize;
+                    threads_given = true;
+                }
+                "seed" => parsed.seed = Some(value),
+                _ => return Err(invalid_option()),
+            }
+        }
+
+        if parsed.playouts.is_none() && parsed.time_budget.is_none() {
+            parsed.playouts = Some(MCTS_DEFAULT_PLAYOUTS);
+        }
+        if parsed.seed.is_some() && !threads_given {
+            parsed.threads = MCTS_SEEDED_THREADS;
+        }
+        if !board_options.is_empty() {
+            parsed.board = Some(BoardOptions::from_str(&board_options.join(" "))?);
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Statistics of one move of the searched position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveAnalysis {
+    pub game_move: String,
+    pub visits: u32,
+    /// Average result of playouts for the player on turn, win 1 and draw 1/2.
+    pub score: f64,
+}
+
+/// Moves of the searched position, the most visited first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MctsAnalysis {
+    pub moves: Vec<MoveAnalysis>,
+    pub playouts: u32,
+}
+
+impl MctsAnalysis {
+    pub fn get_best_move(&self) -> Option<&str> {
+        self.moves
+            .first()
+            .map(|analysis| analysis.game_move.as_str())
+    }
+}
+
+// Playboard of the game variant or larger k-in-a-row board.
+enum Board {
+    Playboard(GameVariant, Box<dyn PlayboardVariant>),
+    Large(ThreatBoard),
+}
+
+// Board with the player on turn, searched without the game session.
+struct Position {
+    board: Board,
+    on_turn: StartOrder,
+    // Winner of the finished game, None on draw.
+    result: Option<Option<StartOrder>>,
+}
+
+impl Position {
+    fn new(variant: GameVariant, board: Option<BoardOptions>, moves: &[String]) -> Self {
+        let (board, on_turn) = match board {
+            Some(board) => {
+                let (board, on_turn) = ThreatBoard::replay(board, moves);
+                (Board::Large(board), on_turn)
+            }
+            None => {
+                let (playboard, on_turn) = replay_moves(variant, moves);
+                (Board::Playboard(variant, playboard), on_turn)
+            }
+        };
+
+        Self {
+            board,
+            on_turn,
+            result: None,
+        }
+    }
+
+    fn clone_position(&self) -> Self {
+        Self {
+            board: match &self.board {
+                Board::Playboard(variant, playboard) => {
+                    Board::Playboard(*variant, playboard.clone_box())
+                }
+                Board::Large(board) => Board::Large(board.clone()),
+            },
+            on_turn: self.on_turn,
+            result: self.result,
+        }
+    }
+
+    // Returns false when the move is not valid.
+    fn play(&mut self, game_move: &str) -> bool {
+        let playboard = match &mut self.board {
+            Board::Playboard(_, playboard) => playboard,
+            Board::Large(_) => {
+                return match decode_place_command(game_move) {
+                    Some((row, col)) => self.place_on_large_board(row, col),
+                    None => false,
+                };
+            }
+        };
+
+        let game_state = match play_move(playboard.as_mut(), game_move, self.on_turn) {
+            Ok((_, game_state)) => game_state,
+            Err(_) => return false,
+        };
+
+        // Collapse can complete line of any player, as in the game session.
+        match game_state {
+            GameState::GameOver => {
+                self.result = Some(Some(playboard.winner().unwrap_or(self.on_turn)))
+            }
+            GameState::Draw => self.result = Some(None),
+            _ => {}
+        }
+
+        if game_move.starts_with(PLACE_ON_PLAYBOARD_COMMAND) {
+            self.on_turn = self.on_turn.oponent();
+        }
+
+        true
+    }
+
+    // Only lines through the placed symbol are checked, large board has many.
+    fn place_on_large_board(&mut self, row: usize, col: usize) -> bool {
+        let board = match &mut self.board {
+            Board::Large(board) => board,
+            Board::Playboard(..) => return false,
+        };
+
+        if !board.place(row, col, self.on_turn) {
+            return false;
+        }
+
+        if board.completes_line(row, col) {
+            self.result = Some(Some(self.on_turn));
+        } else if board.get_free_cells().is_empty() {
+            self.result = Some(None);
+        }
+        self.on_turn = self.on_turn.oponent();
+
+        true
+    }
+
+    fn get_moves(&self) -> Vec<String> {
+        if self.result.is_some() {
+            return Vec::new();
+        }
+
+        match &self.board {
+            Board::Playboard(variant, playboard) => {
+                get_playboard_moves(*variant, playboard.as_ref(), self.on_turn)
+            }
+            Board::Large(board) => board
+                .get_candidate_moves()
+                .into_iter()
+                .map(|(row, col)| format_place_command(row, col))
+                .collect(),
+        }
+    }
+
+    // Plays random moves until the game is finished, game without valid move is drawn.
+    fn play_out(&mut self, rng: &mut StdRng) -> Option<StartOrder> {
+        if let Board::Large(board) = &self.board {
+            // Random order of free cells is a random game, it ends by the first line.
+            let mut cells = board.get_free_cells();
+            cells.shuffle(rng);
+
+            for (row, col) in cells {
+                if self.result.is_some() {
+                    break;
+                }
+                self.place_on_large_board(row, col);
+            }
+
+            return self.result.flatten();
+        }
+
+        while self.result.is_none() {
+            let mut candidates = match &self.board {
+                Board::Playboard(variant, playboard) => {
+                    get_candidate_moves(*variant, playboard.as_ref())
+                }
+                Board::Large(_) => unreachable!("Large board is played out above."),
+            };
+            candidates.shuffle(rng);
+
+            if !candidates.iter().any(|candidate| self.play(candidate)) {
+                return None;
+            }
+        }
+
+        self.result.flatten()
+    }
+}
+
+struct Node {
+    game_move: String,
+    // Player who played the move leading to the node.
+    player: StartOrder,
+    children: Vec<usize>,
+    // Moves not expanded yet, computed on the first visit.
+    untried: Option<Vec<String>>,
+    visits: u32,
+    // Results of playouts for the player of the node, win 1 and draw 1/2.
+    score: f64,
+}
+
+impl Node {
+    fn new(game_move: String, player: StartOrder) -> Self {
+        Self {
+            game_move,
+            player,
+            children: Vec::new(),
+            untried: None,
+            visits: 0,
+            score: 0.0,
+        }
+    }
+
+    fn get_uct(&self, parent_visits: u32) -> f64 {
+        self.score / self.visits as f64
+            + MCTS_EXPLORATION * ((parent_visits as f64).ln() / self.visits as f64).sqrt()
+    }
+}
+
+// Tree of one thread, nodes are indexed and the root is the first one.
+struct SearchTree {
+    nodes: Vec<Node>,
+}
+
+impl SearchTree {
+    fn new(root: &Position) -> Self {
+        Self {
+            nodes: vec![Node::new(String::new(), root.on_turn.oponent())],
+        }
+    }
+
+    fn run_playout(&mut self, root: &Position, rng: &mut StdRng) {
+        let mut position = root.clone_position();
+        let mut path = vec![0];
+        let mut node = 0;
+
+        // Selection of the best child of fully expanded nodes.
+        loop {
+            if self.nodes[node].untried.is_none() {
+                self.nodes[node].untried = Some(position.get_moves());
+            }
+
+            let untried = self.nodes[node].untried.as_ref().map_or(0, Vec::len);
+            if untried > 0 || self.nodes[node].children.is_empty() {
+                break;
+            }
+
+            let parent_visits = self.nodes[node].visits;
+            node = *self.nodes[node]
+                .children
+                .iter()
+                .max_by(|first, second| {
+                    self.nodes[**first]
+                        .get_uct(parent_visits)
+                        .total_cmp(&self.nodes[**second].get_uct(parent_visits))
+                })
+                .unwrap();
+
+            position.play(&self.nodes[node].game_move);
+            path.push(node);
+        }
+
+        // Expansion by one random untried move.
+        if let Some(untried) = self.nodes[node].untried.as_mut() {
+            if !untried.is_empty() {
+                let game_move = untried.swap_remove(rng.gen_range(0..untried.len()));
+                let player = position.on_turn;
+
+                position.play(&game_move);
+                self.nodes.push(Node::new(game_move, player));
+
+                let child = self.nodes.len() - 1;
+                self.nodes[node].children.push(child);
+                path.push(child);
+            }
+        }
+
+        let winner = match position.result {
+            Some(result) => result,
+            None => position.play_out(rng),
+        };
+
+        for node in path {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            node.score += match winner {
+                Some(winner) if winner == node.player => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+        }
+    }
+
+    fn get_root_moves(&self) -> impl Iterator<Item = &Node> {
+        self.nodes[0]
+            .children
+            .iter()
+            .map(|child| &self.nodes[*child])
+    }
+}
+
+fn search_tree(
+    root: &Position,
+    playouts: Option<u32>,
+    deadline: Option<Instant>,
+    seed: Option<u64>,
+) -> SearchTree {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut tree = SearchTree::new(root);
+    let mut played = 0;
+
+    while playouts.is_none_or(|playouts| played < playouts)
+        && deadline.is_none_or(|deadline| Instant::now() < deadline)
+    {
+        tree.run_playout(root, &mut rng);
+        played += 1;
+    }
+
+    tree
+}
+
+/// Searches the position after given moves for the player on turn.
+pub fn search_position(
+    variant: GameVariant,
+    moves: &[String],
+    options: &MctsOptions,
+) -> MctsAnalysis {
+    let root = Position::new(variant, options.board, moves);
+    let deadline = options
+        .time_budget
+        .map(|time_budget| Instant::now() + time_budget);
+    let threads = options.threads.max(1);
+
+    let trees: Vec<SearchTree> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                let root = root.clone_position();
+                // Playouts are split among threads as evenly as possible.
+                let playouts = options.playouts.map(|playouts| {
+                    playouts / threads as u32
+                        + u32::from((thread as u32) < playouts % threads as u32)
+                });
+                let seed = options.seed.map(|seed| seed.wrapping_add(thread as u64));
+
+                scope.spawn(move || search_tree(&root, playouts, deadline, seed))
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Search thread panicked."))
+            .collect()
+    });
+
+    let mut analysis = MctsAnalysis::default();
+
+    for tree in &trees {
+        analysis.playouts += tree.nodes[0].visits;
+
+        for node in tree.get_root_moves() {
+            match analysis
+                .moves
+                .iter_mut()
+                .find(|analysis| analysis.game_move == node.game_move)
+            {
+                Some(analysis) => {
+                    analysis.score += node.score;
+                    analysis.visits += node.visits;
+                }
+                None => analysis.moves.push(MoveAnalysis {
+                    game_move: node.game_move.clone(),
+                    visits: node.visits,
+                    score: node.score,
+                }),
+            }
+        }
+    }
+
+    for analysis in analysis.moves.iter_mut() {
+        analysis.score /= analysis.visits.max(1) as f64;
+    }
+
+    // Stable sort keeps ties in the order of the first tree.
+    analysis
+        .moves
+        .sort_by_key(|analysis| std::cmp::Reverse(analysis.visits));
+
+    analysis
+}
+
+/// Plays the most visited move of the search.
+pub struct MctsPlayer {
+    variant: GameVariant,
+    options: MctsOptions,
+}
+
+impl MctsPlayer {
+    pub fn new(options: MctsOptions) -> Self {
+        Self {
+            variant: GameVariant::Classic,
+            options,
+        }
+    }
+}
+
+impl AiPlayer for MctsPlayer {
+    fn get_name(&self) -> String {
+        format!("{} {}", MCTS_PLAYER_NAME, self.options)
+    }
+
+    fn new_game(&mut self, variant: GameVariant, _start_order: StartOrder) -> io::Result<()> {
+        if self.options.board.is_some() && variant != GameVariant::Classic {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} player plays larger board only in classic variant",
+                    MCTS_PLAYER_NAME
+                ),
+            ));
+        }
+
+        self.variant = variant;
+        Ok(())
+    }
+
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String> {
+        if self.options.board.is_none() {
+            if let Some(book_move) = get_book_move(self.variant, moves) {
+                return Ok(book_move.get_command());
+            }
+        }
+
+        // Seed differs for every move, so the same position is always searched the same.
+        let options = MctsOptions {
+            seed: self
+                .options
+                .seed
+                .map(|seed| seed.wrapping_mul(1000).wrapping_add(moves.len() as u64)),
+            ..self.options
+        };
+
+        search_position(self.variant, moves, &options)
+            .get_best_move()
+            .map(str::to_string)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no valid move left"))
+    }
+}
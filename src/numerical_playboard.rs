@@ -0,0 +1,171 @@
+//! Numerical tic tac toe, first player places odd and second player even numbers,
+//! any full line summing to 15 wins.
+
+use colored::*;
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::game_options::StartOrder;
+use crate::playboard::{GameState, PlayBoardGridOptions, Playboard};
+use crate::playboard_variant::{decode_cells, invalid_option_message, PlayboardVariant};
+use crate::settings::commands::PLACE_ON_PLAYBOARD_COMMAND;
+use crate::settings::playboard_options::{NUMERICAL_MAX_NUMBER, NUMERICAL_WINNING_SUM};
+
+static NUMBER_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\)\s*(?P<number>\d+)\s*$").unwrap());
+
+#[derive(Clone)]
+pub struct NumericalPlayboard {
+    playboard: Playboard,
+}
+
+impl NumericalPlayboard {
+    pub fn new() -> Self {
+        Self {
+            playboard: Playboard::new(),
+        }
+    }
+
+    // First player plays odd numbers, second one even numbers.
+    fn get_player_numbers(start_order: StartOrder) -> Vec<u8> {
+        let first_number = match start_order {
+            StartOrder::First => 1,
+            StartOrder::Second => 2,
+        };
+
+        (first_number..=NUMERICAL_MAX_NUMBER).step_by(2).collect()
+    }
+
+    fn get_remaining_numbers(&self, start_order: StartOrder) -> Vec<u8> {
+        let grid = self.playboard.get_grid();
+
+        Self::get_player_numbers(start_order)
+            .into_iter()
+            .filter(|number| !grid.contains(&PlayBoardGridOptions::Number(*number)))
+            .collect()
+    }
+
+    fn decode_number(data: &str) -> Option<u8> {
+        NUMBER_PATTERN.captures(data.trim())?["number"]
+            .parse::<u8>()
+            .ok()
+    }
+
+    fn check_for_winning_sum(&self) -> bool {
+        self.playboard.get_line_items().iter().any(|line| {
+            let numbers = line
+                .iter()
+                .map(|grid_option| match grid_option {
+                    PlayBoardGridOptions::Number(number) => Some(*number as u32),
+                    _ => None,
+                })
+                .collect::<Option<Vec<u32>>>();
+
+            numbers.is_some_and(|numbers| numbers.iter().sum::<u32>() == NUMERICAL_WINNING_SUM)
+        })
+    }
+
+    fn format_numbers(numbers: &[u8]) -> String {
+        numbers
+            .iter()
+            .map(|number| number.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl Default for NumericalPlayboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayboardVariant for NumericalPlayboard {
+    fn place(
+        &mut self,
+        data: &str,
+        start_order: StartOrder,
+    ) -> Result<(String, GameState), String> {
+        let (row, col) = match decode_cells(data).first() {
+            Some(&cell) => cell,
+            None => return Err(invalid_option_message(PLACE_ON_PLAYBOARD_COMMAND)),
+        };
+
+        let number = match Self::decode_number(data) {
+            Some(number) => number,
+            None => {
+                return Err(format!(
+                    "Number is missing for {}. Use e.g. {}(2, 1) 5.",
+                    PLACE_ON_PLAYBOARD_COMMAND, PLACE_ON_PLAYBOARD_COMMAND
+                ))
+            }
+        };
+
+        let remaining_numbers = self.get_remaining_numbers(start_order);
+
+        if !remaining_numbers.contains(&number) {
+            return Err(format!(
+                "Number {} is not available. Available numbers are: {}.",
+                number,
+                Self::format_numbers(&remaining_numbers)
+            ));
+        }
+
+        if !self
+            .playboard
+            .place_grid_option(row, col, PlayBoardGridOptions::Number(number))
+        {
+            return Err(format!(
+                "Invalid row: {} and col: {} specified for {}. Try again.",
+                row, col, PLACE_ON_PLAYBOARD_COMMAND
+            ));
+        }
+
+        let game_state = if self.check_for_winning_sum() {
+            GameState::GameOver
+        } else if self.playboard.check_for_full_playboard() {
+            GameState::Draw
+        } else {
+            GameState::Placed
+        };
+
+        Ok((format!("({}, {}) number {}", row, col, number), game_state))
+    }
+
+    fn get_cell_text_lines(&self, row: usize, col: usize) -> Vec<String> {
+        self.playboard.get_cell_text_lines(row, col)
+    }
+
+    fn get_board_notes(&self) -> Vec<String> {
+        vec![
+            format!(
+                "Odd numbers remaining: {}",
+                Self::format_numbers(&self.get_remaining_numbers(StartOrder::First))
+            ),
+            format!(
+                "Even numbers remaining: {}",
+                Self::format_numbers(&self.get_remaining_numbers(StartOrder::Second))
+            ),
+        ]
+    }
+
+    fn is_cell_free(&self, row: usize, col: usize) -> bool {
+        self.playboard.is_cell_free(row, col)
+    }
+
+    fn get_board_printable(&self) -> String {
+        format!(
+            "{}\n{}",
+            self.playboard.get_board_printable(),
+            self.get_board_notes().join("\n").yellow()
+        )
+    }
+
+    fn clear_board(&mut self) {
+        self.playboard.clear_board();
+    }
+
+    fn clone_box(&self) -> Box<dyn PlayboardVariant> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,468 @@
+//! Opening book and endgame tablebase of standard tic-tac-toe.
+//!
+//! The book maps positions of a square board, where a full line wins, to the best move
+//! of the player on turn and the result of perfect play. The best move wins in the
+//! fewest plies and loses in the most. Positions equal up to the 8
+//! symmetries of the board share one entry kept under their canonical form. Books of
+//! 3x3 and 4x4 boards are generated by the exhaustive solver of
+//! [`OpeningBook::generate`], the 3x3 one of the classic playboard ships with the
+//! library and AI players consult it before searching.
+//!
+//! Books are stored as text. The first line is `tic-tac-toe-book <version> <size>`,
+//! each following one `<position> <result> (<row>, <col>)`, where the position is
+//! written row by row with `x`, `o` and `.` for an empty cell.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::ai_player::play_move;
+use crate::game_options::{GameVariant, StartOrder};
+use crate::playboard::{
+    get_canonical_cells, get_notation_symbol, hash_cells, parse_notation_symbol,
+    PlayBoardGridOptions, Playboard, Symmetry,
+};
+use crate::playboard_variant::decode_cells;
+use crate::settings::ai_options::{BOOK_FORMAT_VERSION, BOOK_HEADER, BOOK_MAX_SIZE, BOOK_MIN_SIZE};
+use crate::settings::commands::PLACE_ON_PLAYBOARD_COMMAND;
+use crate::settings::playboard_options::PLAYBOARD_ROW_COL_SIZE;
+
+static CLASSIC_BOOK: LazyLock<OpeningBook> = LazyLock::new(|| {
+    OpeningBook::load(include_str!("settings/classic_3x3.book").as_bytes())
+        .unwrap_or_else(|err| panic!("Book of classic variant is broken: {}", err))
+});
+
+/// Result of perfect play for the player on turn.
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum BookResult {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl BookResult {
+    /// The same result seen by the oponent.
+    pub fn oponent(self) -> BookResult {
+        match self {
+            BookResult::Loss => BookResult::Win,
+            BookResult::Draw => BookResult::Draw,
+            BookResult::Win => BookResult::Loss,
+        }
+    }
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for BookResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BookResult::Loss => write!(f, "loss"),
+            BookResult::Draw => write!(f, "draw"),
+            BookResult::Win => write!(f, "win"),
+        }
+    }
+}
+
+impl FromStr for BookResult {
+    type Err = ();
+
+    fn from_str(result: &str) -> Result<BookResult, Self::Err> {
+        match result {
+            "loss" => Ok(BookResult::Loss),
+            "draw" => Ok(BookResult::Draw),
+            "win" => Ok(BookResult::Win),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Best move of the player on turn, row and col index from 1 as players do.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BookMove {
+    pub row: usize,
+    pub col: usize,
+    pub result: BookResult,
+}
+
+impl BookMove {
+    /// The move as game command.
+    pub fn get_command(&self) -> String {
+        format!("{}({}, {})", PLACE_ON_PLAYBOARD_COMMAND, self.row, self.col)
+    }
+}
+
+// Solved canonical position.
+#[derive(Clone, Debug)]
+struct BookEntry {
+    cells: Vec<PlayBoardGridOptions>,
+    result: BookResult,
+    // Best cell of the canonical position.
+    cell: usize,
+}
+
+/// Best moves and results of positions of one board size.
+#[derive(Clone, Debug)]
+pub struct OpeningBook {
+    size: usize,
+    // Zobrist hash of the canonical position to its entry.
+    entries: HashMap<u64, BookEntry>,
+}
+
+impl OpeningBook {
+    /// Book of the classic playboard shipped with the library.
+    pub fn classic() -> &'static OpeningBook {
+        &CLASSIC_BOOK
+    }
+
+    /// Solves all positions of the board, keeping those with less than `max_plies`
+    /// symbols when given. Sizes from 3 to 4 are supported.
+    pub fn generate(size: usize, max_plies: Option<usize>) -> Result<OpeningBook, String> {
+        if !(BOOK_MIN_SIZE..=BOOK_MAX_SIZE).contains(&size) {
+            return Err(format!(
+                "Books are generated only for boards of size {} to {}.",
+                BOOK_MIN_SIZE, BOOK_MAX_SIZE
+            ));
+        }
+
+        let mut book = OpeningBook::new(size);
+        let mut solver = Solver::new(&mut book);
+        solver.solve(
+            &mut vec![PlayBoardGridOptions::Free; size * size],
+            PlayBoardGridOptions::X,
+            0,
+        );
+
+        if let Some(max_plies) = max_plies {
+            book.entries
+                .retain(|_, entry| count_symbols(&entry.cells) < max_plies);
+        }
+
+        Ok(book)
+    }
+
+    /// Reads the book written by save.
+    pub fn load(input: impl BufRead) -> Result<OpeningBook, String> {
+        let mut lines = input.lines();
+        let header = lines.next().and_then(Result::ok).ok_or("Book is empty.")?;
+        let size = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [BOOK_HEADER, version, size] => {
+                if version.parse::<u32>() != Ok(BOOK_FORMAT_VERSION) {
+                    return Err(format!(
+                        "Book version {} is not supported, generate the book again.",
+                        version
+                    ));
+                }
+                size.parse::<usize>()
+                    .ok()
+                    .filter(|size| (BOOK_MIN_SIZE..=BOOK_MAX_SIZE).contains(size))
+                    .ok_or(format!("Book size {} is not supported.", size))?
+            }
+            _ => return Err("Book header is missing.".to_string()),
+        };
+
+        let mut book = OpeningBook::new(size);
+
+        for line in lines {
+            let line = line.map_err(|err| err.to_string())?;
+            let entry = book
+                .parse_entry(&line)
+                .ok_or(format!("Book entry {} is not valid.", line))?;
+            book.entries.insert(hash_cells(&entry.cells), entry);
+        }
+
+        Ok(book)
+    }
+
+    /// Writes the book in the form read by load, positions with fewer symbols first.
+    pub fn save(&self, mut output: impl Write) -> io::Result<()> {
+        writeln!(
+            output,
+            "{} {} {}",
+            BOOK_HEADER, BOOK_FORMAT_VERSION, self.size
+        )?;
+
+        let mut entries: Vec<(usize, String, &BookEntry)> = self
+            .entries
+            .values()
+            .map(|entry| {
+                (
+                    count_symbols(&entry.cells),
+                    format_position(&entry.cells),
+                    entry,
+                )
+            })
+            .collect();
+        entries.sort_by(|first, second| (first.0, &first.1).cmp(&(second.0, &second.1)));
+
+        for (_, position, entry) in entries {
+            writeln!(
+                output,
+                "{} {} ({}, {})",
+                position,
+                entry.result,
+                entry.cell / self.size + 1,
+                entry.cell % self.size + 1
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Best move in the position given row by row, None when the book does not know
+    /// the position or the game is over. The first player is on turn when both
+    /// players have the same number of symbols.
+    pub fn lookup(&self, cells: &[Option<StartOrder>]) -> Option<BookMove> {
+        if cells.len() != self.size * self.size {
+            return None;
+        }
+
+        let cells: Vec<PlayBoardGridOptions> = cells.iter().map(|&cell| to_symbol(cell)).collect();
+        let (canonical, symmetry) = get_canonical_cells(&cells, self.size);
+
+        self.get_move(&canonical, hash_cells(&canonical), symmetry)
+    }
+
+    /// Best move in the position of the classic playboard, as given by lookup.
+    pub fn lookup_playboard(&self, playboard: &Playboard) -> Option<BookMove> {
+        if self.size != PLAYBOARD_ROW_COL_SIZE {
+            return None;
+        }
+
+        let (canonical, symmetry) = playboard.get_canonical();
+
+        self.get_move(canonical.get_grid(), canonical.get_hash(), symmetry)
+    }
+
+    fn new(size: usize) -> OpeningBook {
+        OpeningBook {
+            size,
+            entries: HashMap::new(),
+        }
+    }
+
+    // Entry of the canonical position, hashes of other positions can collide with it.
+    fn get_entry(&self, canonical: &[PlayBoardGridOptions], hash: u64) -> Option<&BookEntry> {
+        self.entries
+            .get(&hash)
+            .filter(|entry| entry.cells == canonical)
+    }
+
+    // Best move of the canonical position moved back by the symmetry giving it.
+    fn get_move(
+        &self,
+        canonical: &[PlayBoardGridOptions],
+        hash: u64,
+        symmetry: Symmetry,
+    ) -> Option<BookMove> {
+        let entry = self.get_entry(canonical, hash)?;
+        let (row, col) =
+            symmetry
+                .inverse()
+                .apply(entry.cell / self.size, entry.cell % self.size, self.size);
+
+        Some(BookMove {
+            row: row + 1,
+            col: col + 1,
+            result: entry.result,
+        })
+    }
+
+    fn parse_entry(&self, line: &str) -> Option<BookEntry> {
+        let mut parts = line.splitn(3, ' ');
+        let position = parts.next()?;
+        let result = BookResult::from_str(parts.next()?).ok()?;
+        let (row, col) = *decode_cells(parts.next()?).first()?;
+
+        if position.chars().count() != self.size * self.size
+            || !(1..=self.size).contains(&row)
+            || !(1..=self.size).contains(&col)
+        {
+            return None;
+        }
+
+        // Numbers of numerical variant are not in the book.
+        let cells = position
+            .chars()
+            .map(|symbol| match parse_notation_symbol(symbol)? {
+                PlayBoardGridOptions::Number(_) => None,
+                grid_option => Some(grid_option),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(BookEntry {
+            cells,
+            result,
+            cell: (row - 1) * self.size + col - 1,
+        })
+    }
+}
+
+/// Book move of the player on turn after given moves of the classic variant.
+pub fn get_book_move(variant: GameVariant, moves: &[String]) -> Option<BookMove> {
+    if variant != GameVariant::Classic {
+        return None;
+    }
+
+    let mut playboard = Playboard::new();
+    let mut on_turn = StartOrder::First;
+
+    for game_move in moves {
+        if play_move(&mut playboard, game_move, on_turn).is_ok() {
+            on_turn = on_turn.oponent();
+        }
+    }
+
+    OpeningBook::classic().lookup_playboard(&playboard)
+}
+
+// Exhaustive search of all positions, each canonical one solved once.
+struct Solver<'a> {
+    book: &'a mut OpeningBook,
+    // Full rows, cols and both diagonals.
+    lines: Vec<Vec<usize>>,
+    // Canonical position key to plies of perfect play until the game is over.
+    plies_to_end: HashMap<u64, usize>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(book: &'a mut OpeningBook) -> Self {
+        let size = book.size;
+        let mut lines: Vec<Vec<usize>> = (0..size)
+            .flat_map(|index| {
+                [
+                    (0..size).map(|col| index * size + col).collect(),
+                    (0..size).map(|row| row * size + index).collect(),
+                ]
+            })
+            .collect();
+        lines.push((0..size).map(|index| index * size + index).collect());
+        lines.push(
+            (0..size)
+                .map(|index| index * size + size - 1 - index)
+                .collect(),
+        );
+
+        Self {
+            book,
+            lines,
+            plies_to_end: HashMap::new(),
+        }
+    }
+
+    // Result of the player on turn and plies until the game is over.
+    fn solve(
+        &mut self,
+        cells: &mut [PlayBoardGridOptions],
+        on_turn: PlayBoardGridOptions,
+        plies: usize,
+    ) -> (BookResult, usize) {
+        let size = self.book.size;
+        let (canonical, symmetry) = get_canonical_cells(cells, size);
+        let key = hash_cells(&canonical);
+        if let Some(entry) = self.book.get_entry(&canonical, key) {
+            return (entry.result, self.plies_to_end[&key]);
+        }
+
+        let mut best: Option<(BookResult, usize, usize)> = None;
+
+        for cell in 0..cells.len() {
+            if cells[cell] != PlayBoardGridOptions::Free {
+                continue;
+            }
+
+            cells[cell] = on_turn;
+            let (result, plies_to_end) = if self.is_win(cells, cell) {
+                (BookResult::Win, 1)
+            } else if plies + 1 == cells.len() {
+                (BookResult::Draw, 1)
+            } else {
+                let (result, plies_to_end) = self.solve(cells, get_oponent(on_turn), plies + 1);
+                (result.oponent(), plies_to_end + 1)
+            };
+            cells[cell] = PlayBoardGridOptions::Free;
+
+            if best.is_none_or(|(best_result, best_plies, _)| {
+                is_better(result, plies_to_end, best_result, best_plies)
+            }) {
+                best = Some((result, plies_to_end, cell));
+            }
+        }
+
+        // Solve is not called on full board.
+        let (result, plies_to_end, cell) = best.unwrap();
+        let (row, col) = symmetry.apply(cell / size, cell % size, size);
+        self.book.entries.insert(
+            key,
+            BookEntry {
+                cells: canonical,
+                result,
+                cell: row * size + col,
+            },
+        );
+        self.plies_to_end.insert(key, plies_to_end);
+
+        (result, plies_to_end)
+    }
+
+    fn is_win(&self, cells: &[PlayBoardGridOptions], cell: usize) -> bool {
+        self.lines.iter().any(|line| {
+            line.contains(&cell) && line.iter().all(|&other| cells[other] == cells[cell])
+        })
+    }
+}
+
+// Better result first, then faster win and slower loss.
+fn is_better(result: BookResult, plies: usize, best_result: BookResult, best_plies: usize) -> bool {
+    match result.cmp(&best_result) {
+        Ordering::Equal => match result {
+            BookResult::Win => plies < best_plies,
+            BookResult::Loss => plies > best_plies,
+            BookResult::Draw => false,
+        },
+        ordering => ordering == Ordering::Greater,
+    }
+}
+
+fn to_symbol(cell: Option<StartOrder>) -> PlayBoardGridOptions {
+    match cell {
+        None => PlayBoardGridOptions::Free,
+        Some(StartOrder::First) => PlayBoardGridOptions::X,
+        Some(StartOrder::Second) => PlayBoardGridOptions::O,
+    }
+}
+
+fn get_oponent(symbol: PlayBoardGridOptions) -> PlayBoardGridOptions {
+    match symbol {
+        PlayBoardGridOptions::X => PlayBoardGridOptions::O,
+        _ => PlayBoardGridOptions::X,
+    }
+}
+
+fn count_symbols(cells: &[PlayBoardGridOptions]) -> usize {
+    cells
+        .iter()
+        .filter(|&&cell| cell != PlayBoardGridOptions::Free)
+        .count()
+}
+
+// Written row by row without separators.
+fn format_position(cells: &[PlayBoardGridOptions]) -> String {
+    cells
+        .iter()
+        .map(|&cell| get_notation_symbol(cell))
+        .collect()
+}
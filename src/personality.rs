@@ -0,0 +1,502 @@
+//! AI players with personality, which makes them fun to play against.
+//!
+//! Personality is a profile of the style of play: probability of a blunder, how many
+//! plies ahead the player searches, whether it prefers its own lines or blocking the
+//! oponent's ones, how long it pretends to think and what it says in the chat.
+//! Profiles are read from a config file of sections named by the personality:
+//!
+//! ```text
+//! # Comments start with hash.
+//! [grumpy]
+//! blunder = 0.05
+//! depth = 4
+//! style = aggressive
+//! delay = 300
+//! chat_probability = 0.5
+//! chat_start = Let's get this over with.
+//! chat_move = Is that really your best?
+//! chat_win = Too easy.
+//! ```
+//!
+//! Chat keys `chat_start`, `chat_move`, `chat_win`, `chat_loss` and `chat_draw` may be
+//! repeated, one of the lines is chosen at random. Some profiles are built in, see
+//! [`get_builtin_personalities`].
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use crate::ai_player::{get_playboard_moves, play_move, replay_moves, AiPlayer};
+use crate::game_options::{GameVariant, StartOrder};
+use crate::playboard::GameState;
+use crate::playboard_variant::{decode_cells, PlayboardVariant};
+use crate::settings::ai_options::{
+    PERSONALITY_DEFAULT_CHAT_PROBABILITY, PERSONALITY_DEFAULT_DEPTH, PERSONALITY_PLAYER_NAME,
+};
+use crate::settings::commands::PLACE_ON_PLAYBOARD_COMMAND;
+use crate::settings::playboard_options::{PLAYBOARD_ROW_COL_SIZE, PLAYBOARD_SIZE};
+
+// Score of a won game, faster wins score more.
+const WIN_SCORE: i32 = 1000;
+
+/// Which lines the player cares about more.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum PlayStyle {
+    /// Builds its own lines.
+    Aggressive,
+    #[default]
+    Balanced,
+    /// Blocks lines of the oponent.
+    Defensive,
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for PlayStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayStyle::Aggressive => write!(f, "aggressive"),
+            PlayStyle::Balanced => write!(f, "balanced"),
+            PlayStyle::Defensive => write!(f, "defensive"),
+        }
+    }
+}
+
+impl FromStr for PlayStyle {
+    type Err = ();
+
+    fn from_str(style: &str) -> Result<PlayStyle, Self::Err> {
+        match style.to_lowercase().as_str() {
+            "aggressive" => Ok(PlayStyle::Aggressive),
+            "balanced" => Ok(PlayStyle::Balanced),
+            "defensive" => Ok(PlayStyle::Defensive),
+            _ => Err(()),
+        }
+    }
+}
+
+impl PlayStyle {
+    // Weights of own lines and lines of the oponent.
+    fn get_weights(self) -> (i32, i32) {
+        match self {
+            PlayStyle::Aggressive => (3, 1),
+            PlayStyle::Balanced => (1, 1),
+            PlayStyle::Defensive => (1, 3),
+        }
+    }
+}
+
+/// Style profile of an AI player.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Personality {
+    pub name: String,
+    /// Probability of a random move instead of the best one.
+    pub blunder_probability: f64,
+    /// Plies searched ahead.
+    pub depth: usize,
+    pub style: PlayStyle,
+    /// Average time the player pretends to think.
+    pub delay: Duration,
+    /// Probability of saying a chat_move line after the move.
+    pub chat_probability: f64,
+    pub chat_start: Vec<String>,
+    pub chat_move: Vec<String>,
+    pub chat_win: Vec<String>,
+    pub chat_loss: Vec<String>,
+    pub chat_draw: Vec<String>,
+}
+
+impl Personality {
+    /// Perfect player of given name without chat until configured otherwise.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            blunder_probability: 0.0,
+            depth: PERSONALITY_DEFAULT_DEPTH,
+            style: PlayStyle::default(),
+            delay: Duration::ZERO,
+            chat_probability: PERSONALITY_DEFAULT_CHAT_PROBABILITY,
+            chat_start: Vec::new(),
+            chat_move: Vec::new(),
+            chat_win: Vec::new(),
+            chat_loss: Vec::new(),
+            chat_draw: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("{} is not valid value of {}.", value, key);
+        let probability = || {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|probability| (0.0..=1.0).contains(probability))
+                .ok_or_else(invalid)
+        };
+
+        match key {
+            "blunder" => self.blunder_probability = probability()?,
+            "depth" => {
+                self.depth = value
+                    .parse()
+                    .ok()
+                    .filter(|&depth| depth > 0)
+                    .ok_or_else(invalid)?
+            }
+            "style" => self.style = PlayStyle::from_str(value).map_err(|_| invalid())?,
+            "delay" => self.delay = Duration::from_millis(value.parse().map_err(|_| invalid())?),
+            "chat_probability" => self.chat_probability = probability()?,
+            "chat_start" => self.chat_start.push(value.to_string()),
+            "chat_move" => self.chat_move.push(value.to_string()),
+            "chat_win" => self.chat_win.push(value.to_string()),
+            "chat_loss" => self.chat_loss.push(value.to_string()),
+            "chat_draw" => self.chat_draw.push(value.to_string()),
+            _ => return Err(format!("{} is not a personality setting.", key)),
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads profiles of the config file described in the module documentation.
+pub fn load_personalities(input: impl BufRead) -> Result<Vec<Personality>, String> {
+    let mut personalities: Vec<Personality> = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(|err| err.to_string())?;
+        let line = line.trim();
+        let at_line = |msg: String| format!("Line {}: {}", index + 1, msg);
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            personalities.push(Personality::new(name.trim()));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| at_line(format!("{} is not key = value.", line)))?;
+        let personality = personalities
+            .last_mut()
+            .ok_or_else(|| at_line("Setting is not in any [personality].".to_string()))?;
+
+        personality.set(key.trim(), value.trim()).map_err(at_line)?;
+    }
+
+    Ok(personalities)
+}
+
+/// Profiles shipped with the library.
+pub fn get_builtin_personalities() -> Vec<Personality> {
+    load_personalities(include_str!("settings/personalities.conf").as_bytes())
+        .unwrap_or_else(|err| panic!("Built-in personalities are broken: {}", err))
+}
+
+// Playboard searched by the player, with symbols of classic variant for evaluation.
+struct SearchPosition {
+    variant: GameVariant,
+    playboard: Box<dyn PlayboardVariant>,
+    on_turn: StartOrder,
+    cells: [Option<StartOrder>; PLAYBOARD_SIZE],
+    // Winner of the finished game, None on draw.
+    result: Option<Option<StartOrder>>,
+}
+
+impl SearchPosition {
+    fn play(&self, game_move: &str) -> Option<SearchPosition> {
+        let mut playboard = self.playboard.clone_box();
+        let (_, game_state) = play_move(playboard.as_mut(), game_move, self.on_turn).ok()?;
+        let mut cells = self.cells;
+
+        let result = match game_state {
+            GameState::GameOver => Some(Some(playboard.winner().unwrap_or(self.on_turn))),
+            GameState::Draw => Some(None),
+            _ => None,
+        };
+
+        let on_turn = match game_move.strip_prefix(PLACE_ON_PLAYBOARD_COMMAND) {
+            Some(data) => {
+                if let (GameVariant::Classic, Some((row, col))) =
+                    (self.variant, decode_cells(data).first())
+                {
+                    cells[(row - 1) * PLAYBOARD_ROW_COL_SIZE + col - 1] = Some(self.on_turn);
+                }
+                self.on_turn.oponent()
+            }
+            None => self.on_turn,
+        };
+
+        Some(SearchPosition {
+            variant: self.variant,
+            playboard,
+            on_turn,
+            cells,
+            result,
+        })
+    }
+
+    // Lines free of the other player's symbols, weighted by square of own symbols.
+    fn get_line_value(&self, player: StartOrder) -> i32 {
+        let size = PLAYBOARD_ROW_COL_SIZE;
+        let mut lines: Vec<Vec<usize>> = (0..size)
+            .flat_map(|index| {
+                [
+                    (0..size).map(|col| index * size + col).collect(),
+                    (0..size).map(|row| row * size + index).collect(),
+                ]
+            })
+            .collect();
+        lines.push((0..size).map(|index| index * size + index).collect());
+        lines.push(
+            (0..size)
+                .map(|index| index * size + size - 1 - index)
+                .collect(),
+        );
+
+        lines
+            .iter()
+            .filter(|line| {
+                line.iter()
+                    .all(|&cell| self.cells[cell] != Some(player.oponent()))
+            })
+            .map(|line| {
+                let symbols = line
+                    .iter()
+                    .filter(|&&cell| self.cells[cell] == Some(player))
+                    .count() as i32;
+                symbols * symbols
+            })
+            .sum()
+    }
+}
+
+/// Plays by search limited by its personality.
+pub struct PersonalityPlayer {
+    personality: Personality,
+    variant: GameVariant,
+    start_order: StartOrder,
+    rng: StdRng,
+    chat: Option<String>,
+    // Loss is admitted only once per game.
+    loss_admitted: bool,
+}
+
+impl PersonalityPlayer {
+    pub fn new(personality: Personality) -> Self {
+        Self {
+            personality,
+            variant: GameVariant::Classic,
+            start_order: StartOrder::First,
+            rng: StdRng::from_entropy(),
+            chat: None,
+            loss_admitted: false,
+        }
+    }
+
+    /// Player of the built-in personality, or of the one in the config file when given.
+    pub fn load(name: &str, path: Option<&str>) -> io::Result<Self> {
+        let personalities = match path {
+            Some(path) => {
+                let file = io::BufReader::new(std::fs::File::open(path)?);
+                load_personalities(file)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
+            }
+            None => get_builtin_personalities(),
+        };
+
+        personalities
+            .into_iter()
+            .find(|personality| personality.name == name)
+            .map(Self::new)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("personality {} is not known", name),
+                )
+            })
+    }
+
+    fn say(&mut self, lines: &[String]) {
+        if let Some(line) = lines.choose(&mut self.rng) {
+            self.chat = Some(line.clone());
+        }
+    }
+
+    // Score for the player on turn of the position.
+    fn search(&self, position: &SearchPosition, depth: usize, alpha: i32, beta: i32) -> i32 {
+        if let Some(result) = position.result {
+            return match result {
+                Some(winner) if winner == position.on_turn => WIN_SCORE + depth as i32,
+                Some(_) => -WIN_SCORE - depth as i32,
+                None => 0,
+            };
+        }
+
+        if depth == 0 {
+            return self.evaluate(position);
+        }
+
+        let mut alpha = alpha;
+        let mut best = None;
+
+        for game_move in
+            get_playboard_moves(self.variant, position.playboard.as_ref(), position.on_turn)
+        {
+            let Some(child) = position.play(&game_move) else {
+                continue;
+            };
+
+            // Collapse is followed by place of the same player.
+            let score = if child.on_turn == position.on_turn {
+                self.search(&child, depth - 1, alpha, beta)
+            } else {
+                -self.search(&child, depth - 1, -beta, -alpha)
+            };
+
+            best = Some(best.map_or(score, |best: i32| best.max(score)));
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // Game without valid move is drawn.
+        best.unwrap_or(0)
+    }
+
+    // Style weighted lines of the personality, for the player on turn.
+    fn evaluate(&self, position: &SearchPosition) -> i32 {
+        let (attack, defense) = self.personality.style.get_weights();
+        let value = attack * position.get_line_value(self.start_order)
+            - defense * position.get_line_value(self.start_order.oponent());
+
+        if position.on_turn == self.start_order {
+            value
+        } else {
+            -value
+        }
+    }
+
+    fn think(&mut self) {
+        let delay = self.personality.delay.as_millis() as u64;
+
+        if delay > 0 {
+            let delay = self.rng.gen_range(delay / 2..=delay + delay / 2);
+            thread::sleep(Duration::from_millis(delay));
+        }
+    }
+}
+
+impl AiPlayer for PersonalityPlayer {
+    fn get_name(&self) -> String {
+        format!("{} {}", PERSONALITY_PLAYER_NAME, self.personality.name)
+    }
+
+    fn new_game(&mut self, variant: GameVariant, start_order: StartOrder) -> io::Result<()> {
+        self.variant = variant;
+        self.start_order = start_order;
+        self.loss_admitted = false;
+
+        let lines = self.personality.chat_start.clone();
+        self.say(&lines);
+
+        Ok(())
+    }
+
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String> {
+        let (playboard, on_turn) = replay_moves(self.variant, moves);
+        let mut position = SearchPosition {
+            variant: self.variant,
+            playboard,
+            on_turn,
+            cells: [None; PLAYBOARD_SIZE],
+            result: None,
+        };
+
+        // Symbols of classic variant are not kept by the playboard trait.
+        if self.variant == GameVariant::Classic {
+            let mut on_turn = StartOrder::First;
+            for game_move in moves {
+                if let Some(cell) = decode_cells(game_move).first() {
+                    position.cells[(cell.0 - 1) * PLAYBOARD_ROW_COL_SIZE + cell.1 - 1] =
+                        Some(on_turn);
+                }
+                on_turn = on_turn.oponent();
+            }
+        }
+
+        let mut candidates =
+            get_playboard_moves(self.variant, position.playboard.as_ref(), on_turn);
+        // Equally good moves are chosen at random.
+        candidates.shuffle(&mut self.rng);
+
+        let mut scored: Vec<(i32, String, Option<Option<StartOrder>>)> = candidates
+            .into_iter()
+            .filter_map(|game_move| {
+                let child = position.play(&game_move)?;
+                let depth = self.personality.depth - 1;
+                let score = if child.on_turn == on_turn {
+                    self.search(&child, depth, -i32::MAX, i32::MAX)
+                } else {
+                    -self.search(&child, depth, -i32::MAX, i32::MAX)
+                };
+                Some((score, game_move, child.result))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+
+        if scored.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no valid move left",
+            ));
+        }
+
+        let index = if self.rng.gen::<f64>() < self.personality.blunder_probability {
+            self.rng.gen_range(0..scored.len())
+        } else {
+            0
+        };
+        let (score, game_move, result) = scored.swap_remove(index);
+
+        self.chat = None;
+        match result {
+            Some(Some(winner)) if winner == on_turn => {
+                let lines = self.personality.chat_win.clone();
+                self.say(&lines);
+            }
+            Some(None) => {
+                let lines = self.personality.chat_draw.clone();
+                self.say(&lines);
+            }
+            _ if score <= -WIN_SCORE && !self.loss_admitted => {
+                self.loss_admitted = true;
+                let lines = self.personality.chat_loss.clone();
+                self.say(&lines);
+            }
+            _ if self.rng.gen::<f64>() < self.personality.chat_probability => {
+                let lines = self.personality.chat_move.clone();
+                self.say(&lines);
+            }
+            _ => {}
+        }
+
+        self.think();
+
+        Ok(game_move)
+    }
+
+    fn take_chat(&mut self) -> Option<String> {
+        self.chat.take()
+    }
+}
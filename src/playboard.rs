@@ -1,28 +1,202 @@
-use crate::matrix_display;
 use matrix_display::*;
+use std::str::FromStr;
 
-use crate::cli_args_processing::StartOrder;
+use crate::game_options::StartOrder;
+use crate::playboard_variant::{decode_cells, invalid_option_message, PlayboardVariant};
+use crate::settings::commands::PLACE_ON_PLAYBOARD_COMMAND;
 use crate::settings::playboard_options::{
-    PLAYBOARD_COLOR_TEXT, PLAYBOARD_GRID_COLOR1, PLAYBOARD_GRID_COLOR2, PLAYBOARD_GRID_HEIGHT,
-    PLAYBOARD_GRID_WIDTH, PLAYBOARD_ROW_COL_SIZE, PLAYBOARD_SIZE,
+    NOTATION_FREE, NOTATION_ROW_SEPARATOR, NUMERICAL_MAX_NUMBER, PLAYBOARD_COLOR_TEXT,
+    PLAYBOARD_GRID_COLOR1, PLAYBOARD_GRID_COLOR2, PLAYBOARD_GRID_HEIGHT, PLAYBOARD_GRID_WIDTH,
+    PLAYBOARD_ROW_COL_SIZE, PLAYBOARD_SIZE, ZOBRIST_MAX_CELLS, ZOBRIST_SEED,
 };
 
+// X, O and every number of the numerical variant.
+const ZOBRIST_OPTIONS: usize = 2 + NUMERICAL_MAX_NUMBER as usize;
+static ZOBRIST_KEYS: [[u64; ZOBRIST_OPTIONS]; ZOBRIST_MAX_CELLS] = generate_zobrist_keys();
+
+// SplitMix64 sequence, it does not depend on version of any random crate.
+const fn generate_zobrist_keys() -> [[u64; ZOBRIST_OPTIONS]; ZOBRIST_MAX_CELLS] {
+    let mut keys = [[0; ZOBRIST_OPTIONS]; ZOBRIST_MAX_CELLS];
+    let mut state = ZOBRIST_SEED;
+    let mut index = 0;
+
+    while index < ZOBRIST_MAX_CELLS * ZOBRIST_OPTIONS {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut key = state;
+        key = (key ^ (key >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[index / ZOBRIST_OPTIONS][index % ZOBRIST_OPTIONS] = key ^ (key >> 31);
+        index += 1;
+    }
+
+    keys
+}
+
+fn get_zobrist_key(index: usize, grid_option: PlayBoardGridOptions) -> u64 {
+    match grid_option {
+        PlayBoardGridOptions::X => ZOBRIST_KEYS[index][0],
+        PlayBoardGridOptions::O => ZOBRIST_KEYS[index][1],
+        PlayBoardGridOptions::Number(number) => ZOBRIST_KEYS[index][1 + number as usize],
+        PlayBoardGridOptions::Free => 0,
+    }
+}
+
+/// Zobrist hash of cells of a square board given row by row, equal to the hash of the
+/// playboard with the same cells. Boards of up to 4x4 cells are hashed.
+pub fn hash_cells(cells: &[PlayBoardGridOptions]) -> u64 {
+    cells
+        .iter()
+        .enumerate()
+        .fold(0, |hash, (index, &grid_option)| {
+            hash ^ get_zobrist_key(index, grid_option)
+        })
+}
+
+/// Cells of a square board of given size moved by the symmetry.
+pub fn transform_cells(
+    cells: &[PlayBoardGridOptions],
+    size: usize,
+    symmetry: Symmetry,
+) -> Vec<PlayBoardGridOptions> {
+    let mut transformed = vec![PlayBoardGridOptions::Free; cells.len()];
+
+    for (index, &grid_option) in cells.iter().enumerate() {
+        let (row, col) = symmetry.apply(index / size, index % size, size);
+        transformed[row * size + col] = grid_option;
+    }
+
+    transformed
+}
+
+/// The same representative of all symmetric positions of a square board, the one of
+/// the smallest notation, and the symmetry turning the cells into it.
+pub fn get_canonical_cells(
+    cells: &[PlayBoardGridOptions],
+    size: usize,
+) -> (Vec<PlayBoardGridOptions>, Symmetry) {
+    Symmetry::ALL
+        .iter()
+        .map(|&symmetry| (transform_cells(cells, size, symmetry), symmetry))
+        .min_by(|(first, _), (second, _)| {
+            let first = first
+                .iter()
+                .map(|&grid_option| get_notation_symbol(grid_option));
+            let second = second
+                .iter()
+                .map(|&grid_option| get_notation_symbol(grid_option));
+            first.cmp(second)
+        })
+        .unwrap()
+}
+
+/// Symbol of the cell in position notation.
+pub fn get_notation_symbol(grid_option: PlayBoardGridOptions) -> char {
+    match grid_option {
+        PlayBoardGridOptions::X => 'x',
+        PlayBoardGridOptions::O => 'o',
+        PlayBoardGridOptions::Number(number) => char::from_digit(number as u32, 10).unwrap_or('?'),
+        PlayBoardGridOptions::Free => NOTATION_FREE,
+    }
+}
+
+/// Cell of the symbol in position notation, symbols are case insensitive.
+pub fn parse_notation_symbol(symbol: char) -> Option<PlayBoardGridOptions> {
+    match symbol.to_ascii_lowercase() {
+        NOTATION_FREE => Some(PlayBoardGridOptions::Free),
+        'x' => Some(PlayBoardGridOptions::X),
+        'o' => Some(PlayBoardGridOptions::O),
+        digit => digit
+            .to_digit(10)
+            .filter(|number| (1..=NUMERICAL_MAX_NUMBER as u32).contains(number))
+            .map(|number| PlayBoardGridOptions::Number(number as u8)),
+    }
+}
+
+/// One of the 8 symmetries of the square playboard.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Symmetry {
+    Identity,
+    RotateRight,
+    RotateHalf,
+    RotateLeft,
+    // Mirrors cols.
+    MirrorHorizontal,
+    // Mirrors rows.
+    MirrorVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::RotateRight,
+        Symmetry::RotateHalf,
+        Symmetry::RotateLeft,
+        Symmetry::MirrorHorizontal,
+        Symmetry::MirrorVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /// Cell the cell moves to on square of given size, row and col are indexed from 0.
+    pub fn apply(self, row: usize, col: usize, size: usize) -> (usize, usize) {
+        let last = size - 1;
+
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::RotateRight => (col, last - row),
+            Symmetry::RotateHalf => (last - row, last - col),
+            Symmetry::RotateLeft => (last - col, row),
+            Symmetry::MirrorHorizontal => (row, last - col),
+            Symmetry::MirrorVertical => (last - row, col),
+            Symmetry::Transpose => (col, row),
+            Symmetry::AntiTranspose => (last - col, last - row),
+        }
+    }
+
+    /// Symmetry moving cells back.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::RotateRight => Symmetry::RotateLeft,
+            Symmetry::RotateLeft => Symmetry::RotateRight,
+            symmetry => symmetry,
+        }
+    }
+}
+
+/// Content of one playboard cell.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum PlayBoardGridOptions {
     X,
     O,
+    // Numerical variant value.
+    Number(u8),
     Free,
 }
 
+/// Result of a move.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "rest-api",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum GameState {
     InvalidPlace,
     Placed,
+    // Quantum variant only, move closed entanglement cycle.
+    CollapsePending,
     Draw,
     GameOver,
 }
 
+/// Classic playboard, rows and cols are indexed from 1 in all public methods taking them.
+#[derive(Clone)]
 pub struct Playboard {
     grid: [PlayBoardGridOptions; PLAYBOARD_SIZE],
+    // Zobrist hash of the grid, updated with every placed option.
+    hash: u64,
 }
 
 impl Playboard {
@@ -39,7 +213,25 @@ impl Playboard {
         let grid: [PlayBoardGridOptions; PLAYBOARD_SIZE] =
             [PlayBoardGridOptions::Free; PLAYBOARD_SIZE];
 
-        Self { grid }
+        Self { grid, hash: 0 }
+    }
+
+    // Playboard of given cells, row by row.
+    fn from_cells(cells: &[PlayBoardGridOptions]) -> Self {
+        let mut playboard = Playboard::new();
+
+        for (index, &grid_option) in cells.iter().enumerate() {
+            if grid_option != PlayBoardGridOptions::Free {
+                // Players index from 1.
+                playboard.place_grid_option(
+                    index / PLAYBOARD_ROW_COL_SIZE + 1,
+                    index % PLAYBOARD_ROW_COL_SIZE + 1,
+                    grid_option,
+                );
+            }
+        }
+
+        playboard
     }
 
     fn i2d_into_1d(row: usize, col: usize) -> usize {
@@ -54,7 +246,8 @@ impl Playboard {
             && self.grid[Self::i2d_into_1d(row - 1, col - 1)] == PlayBoardGridOptions::Free
     }
 
-    fn check_for_game_win(&self) -> bool {
+    /// True when a row, col or diagonal is full of the same symbol.
+    pub fn check_for_game_win(&self) -> bool {
         let row_items = self.get_row_items();
         let col_items = self.get_col_items();
         let diagonal_items = self.get_diagonal_items();
@@ -96,7 +289,7 @@ impl Playboard {
     }
 
     // k zamysleni: drzet pocet plnych poli
-    fn check_for_full_playboard(&self) -> bool {
+    pub fn check_for_full_playboard(&self) -> bool {
         for index_grid in 0..self.grid.len() {
             if self.grid[index_grid] == PlayBoardGridOptions::Free {
                 return false;
@@ -106,21 +299,49 @@ impl Playboard {
         true
     }
 
-    pub fn place_on_grid(&mut self, row: usize, col: usize, start_order: StartOrder) -> GameState {
+    // Returns false when indexes are out of playboard or place is taken.
+    pub fn place_grid_option(
+        &mut self,
+        row: usize,
+        col: usize,
+        grid_option: PlayBoardGridOptions,
+    ) -> bool {
         if !self.check_validity_of_indexes(row, col) {
-            return GameState::InvalidPlace;
+            return false;
         }
 
         // Players index from 1.
-        let row = row - 1;
-        let col = col - 1;
+        let index = Self::i2d_into_1d(row - 1, col - 1);
+        self.grid[index] = grid_option;
+        self.hash ^= get_zobrist_key(index, grid_option);
 
+        true
+    }
+
+    // Returns false when indexes are out of playboard, free place stays free.
+    pub fn clear_grid_option(&mut self, row: usize, col: usize) -> bool {
+        if row == 0 || row > PLAYBOARD_ROW_COL_SIZE || col == 0 || col > PLAYBOARD_ROW_COL_SIZE {
+            return false;
+        }
+
+        // Players index from 1.
+        let index = Self::i2d_into_1d(row - 1, col - 1);
+        self.hash ^= get_zobrist_key(index, self.grid[index]);
+        self.grid[index] = PlayBoardGridOptions::Free;
+
+        true
+    }
+
+    /// Places symbol of the player with given start order and evaluates the game.
+    pub fn place_on_grid(&mut self, row: usize, col: usize, start_order: StartOrder) -> GameState {
         let player_playboard_grid_option = match start_order {
             StartOrder::First => PlayBoardGridOptions::X,
             StartOrder::Second => PlayBoardGridOptions::O,
         };
 
-        self.grid[Self::i2d_into_1d(row, col)] = player_playboard_grid_option;
+        if !self.place_grid_option(row, col, player_playboard_grid_option) {
+            return GameState::InvalidPlace;
+        }
 
         if self.check_for_game_win() {
             GameState::GameOver
@@ -134,20 +355,22 @@ impl Playboard {
     fn tranfer_playboard_grid_options_to_printable(&self) -> [char; PLAYBOARD_SIZE] {
         let mut grid_printable: [char; PLAYBOARD_SIZE] = [' '; PLAYBOARD_SIZE];
 
-        for index_grid in 0..self.grid.len() {
-            let printable_symbol = match self.grid[index_grid] {
+        for (printable_symbol, grid_option) in grid_printable.iter_mut().zip(self.grid.iter()) {
+            *printable_symbol = match grid_option {
                 PlayBoardGridOptions::X => 'X',
                 PlayBoardGridOptions::O => 'O',
+                PlayBoardGridOptions::Number(number) => {
+                    char::from_digit(*number as u32, 10).unwrap_or('?')
+                }
                 PlayBoardGridOptions::Free => ' ',
             };
-
-            grid_printable[index_grid] = printable_symbol;
         }
 
         grid_printable
     }
 
-    pub fn display_board(&self) {
+    /// Colored rendering of the playboard, one line per terminal row.
+    pub fn get_board_printable(&self) -> String {
         let format = Format::new(PLAYBOARD_GRID_WIDTH, PLAYBOARD_GRID_HEIGHT);
 
         let grid_printable = self.tranfer_playboard_grid_options_to_printable();
@@ -166,11 +389,65 @@ impl Playboard {
 
         let mut data = matrix::Matrix::new(PLAYBOARD_ROW_COL_SIZE, board);
         let display = MatrixDisplay::new(&format, &mut data);
-        display.print(&mut std::io::stdout(), &style::BordersStyle::None);
+
+        let mut printable = Vec::new();
+        display.print(&mut printable, &style::BordersStyle::None);
+
+        String::from_utf8_lossy(&printable).into_owned()
     }
 
     pub fn clear_board(&mut self) {
         self.grid = [PlayBoardGridOptions::Free; PLAYBOARD_SIZE];
+        self.hash = 0;
+    }
+
+    /// Zobrist hash of the grid, equal positions have equal hashes in every build.
+    pub fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Copy of the playboard with cells moved by the symmetry.
+    pub fn transform(&self, symmetry: Symmetry) -> Playboard {
+        Self::from_cells(&transform_cells(
+            &self.grid,
+            PLAYBOARD_ROW_COL_SIZE,
+            symmetry,
+        ))
+    }
+
+    /// The same representative of all symmetric positions, the one of the smallest
+    /// notation, and the symmetry turning this playboard into it.
+    pub fn get_canonical(&self) -> (Playboard, Symmetry) {
+        let (cells, symmetry) = get_canonical_cells(&self.grid, PLAYBOARD_ROW_COL_SIZE);
+
+        (Self::from_cells(&cells), symmetry)
+    }
+
+    /// Rows of the grid separated by slash, e.g. `x.o/.x./..o`, numbers of numerical
+    /// variant are written as digits. It is parsed back by from_str.
+    pub fn get_notation(&self) -> String {
+        self.grid
+            .chunks(PLAYBOARD_ROW_COL_SIZE)
+            .map(|row| {
+                row.iter()
+                    .map(|&grid_option| get_notation_symbol(grid_option))
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join(&NOTATION_ROW_SEPARATOR.to_string())
+    }
+
+    pub fn get_grid(&self) -> &[PlayBoardGridOptions; PLAYBOARD_SIZE] {
+        &self.grid
+    }
+
+    // Rows, cols and both diagonals.
+    pub fn get_line_items(&self) -> Vec<[PlayBoardGridOptions; PLAYBOARD_ROW_COL_SIZE]> {
+        let mut lines = self.get_row_items();
+        lines.extend(self.get_col_items());
+        lines.extend(self.get_diagonal_items());
+
+        lines
     }
 
     // TODO: Predelat
@@ -181,8 +458,8 @@ impl Playboard {
         for index_row in 0..PLAYBOARD_ROW_COL_SIZE {
             let mut row_values = [PlayBoardGridOptions::Free; PLAYBOARD_ROW_COL_SIZE];
 
-            for index_col in 0..PLAYBOARD_ROW_COL_SIZE {
-                row_values[index_col] = self.grid[Self::i2d_into_1d(index_row, index_col)];
+            for (index_col, row_value) in row_values.iter_mut().enumerate() {
+                *row_value = self.grid[Self::i2d_into_1d(index_row, index_col)];
             }
 
             grid_rows.push(row_values);
@@ -197,8 +474,8 @@ impl Playboard {
         for index_col in 0..PLAYBOARD_ROW_COL_SIZE {
             let mut col_values = [PlayBoardGridOptions::Free; PLAYBOARD_ROW_COL_SIZE];
 
-            for index_row in 0..PLAYBOARD_ROW_COL_SIZE {
-                col_values[index_row] = self.grid[Self::i2d_into_1d(index_row, index_col)];
+            for (index_row, col_value) in col_values.iter_mut().enumerate() {
+                *col_value = self.grid[Self::i2d_into_1d(index_row, index_col)];
             }
 
             grid_cols.push(col_values);
@@ -241,3 +518,87 @@ impl Playboard {
         grid_diagonal
     }
 }
+
+impl FromStr for Playboard {
+    type Err = String;
+
+    fn from_str(notation: &str) -> Result<Playboard, Self::Err> {
+        let rows: Vec<&str> = notation.trim().split(NOTATION_ROW_SEPARATOR).collect();
+        let mut playboard = Playboard::new();
+
+        if rows.len() != PLAYBOARD_ROW_COL_SIZE
+            || rows
+                .iter()
+                .any(|row| row.chars().count() != PLAYBOARD_ROW_COL_SIZE)
+        {
+            return Err(format!(
+                "Position {} does not have {} rows of {} cells.",
+                notation, PLAYBOARD_ROW_COL_SIZE, PLAYBOARD_ROW_COL_SIZE
+            ));
+        }
+
+        for (row, symbols) in rows.iter().enumerate() {
+            for (col, symbol) in symbols.chars().enumerate() {
+                let grid_option = match parse_notation_symbol(symbol) {
+                    Some(PlayBoardGridOptions::Free) => continue,
+                    Some(grid_option) => grid_option,
+                    None => return Err(format!("{} is not a symbol of position.", symbol)),
+                };
+
+                // Players index from 1.
+                playboard.place_grid_option(row + 1, col + 1, grid_option);
+            }
+        }
+
+        Ok(playboard)
+    }
+}
+
+impl Default for Playboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayboardVariant for Playboard {
+    fn place(
+        &mut self,
+        data: &str,
+        start_order: StartOrder,
+    ) -> Result<(String, GameState), String> {
+        let (row, col) = match decode_cells(data).first() {
+            Some(&cell) => cell,
+            None => return Err(invalid_option_message(PLACE_ON_PLAYBOARD_COMMAND)),
+        };
+
+        match self.place_on_grid(row, col, start_order) {
+            GameState::InvalidPlace => Err(format!(
+                "Invalid row: {} and col: {} specified for {}. Try again.",
+                row, col, PLACE_ON_PLAYBOARD_COMMAND
+            )),
+            game_state => Ok((format!("({}, {})", row, col), game_state)),
+        }
+    }
+
+    fn get_cell_text_lines(&self, row: usize, col: usize) -> Vec<String> {
+        let grid_printable = self.tranfer_playboard_grid_options_to_printable();
+
+        vec![grid_printable[Self::i2d_into_1d(row, col)].to_string()]
+    }
+
+    fn is_cell_free(&self, row: usize, col: usize) -> bool {
+        self.grid[Self::i2d_into_1d(row, col)] == PlayBoardGridOptions::Free
+    }
+
+    fn get_board_printable(&self) -> String {
+        Playboard::get_board_printable(self)
+    }
+
+    fn clear_board(&mut self) {
+        Playboard::clear_board(self);
+    }
+
+    fn clone_box(&self) -> Box<dyn PlayboardVariant> {
+        Box::new(self.clone())
+    }
+}
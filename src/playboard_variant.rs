@@ -0,0 +1,86 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::game_options::{GameVariant, StartOrder};
+use crate::numerical_playboard::NumericalPlayboard;
+use crate::playboard::{GameState, Playboard};
+use crate::quantum_playboard::QuantumPlayboard;
+use crate::settings::commands::{COLLAPSE_COMMAND, HELP_COMMAND};
+
+/// Rules and rendering of one game variant.
+pub trait PlayboardVariant: Send {
+    // Places move described by data (command stripped). Returns description of the
+    // move and resulting state or error message for the player.
+    fn place(&mut self, data: &str, start_order: StartOrder)
+        -> Result<(String, GameState), String>;
+
+    // Resolves pending entanglement cycle. Only quantum variant supports it.
+    fn collapse(
+        &mut self,
+        _data: &str,
+        _start_order: StartOrder,
+    ) -> Result<(String, GameState), String> {
+        Err(format!(
+            "{} is not available in this game variant.",
+            COLLAPSE_COMMAND.trim()
+        ))
+    }
+
+    fn is_collapse_pending(&self) -> bool {
+        false
+    }
+
+    // Winner of finished game when it is not necessarily the player who moved last.
+    fn winner(&self) -> Option<StartOrder> {
+        None
+    }
+
+    // Cell text for text user interface, row and col are indexed from 0.
+    fn get_cell_text_lines(&self, row: usize, col: usize) -> Vec<String>;
+
+    // Row and col are indexed from 0.
+    fn is_cell_free(&self, row: usize, col: usize) -> bool;
+
+    // Additional information shown below playboard.
+    fn get_board_notes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    // Playboard rendered with colors for line based output.
+    fn get_board_printable(&self) -> String;
+
+    fn clear_board(&mut self);
+
+    // Copy of the playboard, AI players try moves on copies.
+    fn clone_box(&self) -> Box<dyn PlayboardVariant>;
+}
+
+pub struct PlayboardVariantFactory;
+impl PlayboardVariantFactory {
+    pub fn create_playboard(variant: GameVariant) -> Box<dyn PlayboardVariant> {
+        match variant {
+            GameVariant::Classic => Box::new(Playboard::new()),
+            GameVariant::Quantum => Box::new(QuantumPlayboard::new()),
+            GameVariant::Numerical => Box::new(NumericalPlayboard::new()),
+        }
+    }
+}
+
+// Compiled once, AI players decode many moves.
+static CELL_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(\s*(?P<row>\d+)\s*,\s*(?P<col>\d+)\s*\)").unwrap());
+
+// Decodes all "(row, col)" pairs from data in order of appearance.
+pub fn decode_cells(data: &str) -> Vec<(usize, usize)> {
+    CELL_PATTERN
+        .captures_iter(data)
+        .filter_map(|res| Some((res["row"].parse().ok()?, res["col"].parse().ok()?)))
+        .collect()
+}
+
+pub fn invalid_option_message(command: &str) -> String {
+    format!(
+        "Invalid option for {}. You can use {} if in doubts.",
+        command, HELP_COMMAND
+    )
+}
@@ -0,0 +1,396 @@
+//! "Win in N" puzzles of the classic variant and editor of their positions.
+//!
+//! Puzzle is a position, the player on turn and the number of moves the player wins
+//! in against any defence. Moves of the player are checked by exhaustive search of
+//! the position and the defender replies with the move delaying the loss the most.
+//!
+//! Puzzle files have one puzzle per line written by [`Puzzle`]'s `Display`, the
+//! position in notation of [`Playboard::get_notation`] followed by the player on turn
+//! and the number of moves, e.g. `xx./oo./... first 1`. Empty lines and lines
+//! starting with `#` are skipped. Progress files list the solved puzzles the same way.
+
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::game_options::StartOrder;
+use crate::playboard::{GameState, PlayBoardGridOptions, Playboard};
+use crate::playboard_variant::decode_cells;
+use crate::settings::ai_options::PUZZLE_COMMENT;
+use crate::settings::commands::{SETUP_CLEAR, SETUP_TURN};
+use crate::settings::playboard_options::PLAYBOARD_ROW_COL_SIZE;
+
+/// Position the player on turn wins in given number of moves.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Puzzle {
+    notation: String,
+    on_turn: StartOrder,
+    moves: usize,
+}
+
+impl Puzzle {
+    /// Checks the player on turn wins in exactly given number of moves.
+    pub fn new(playboard: &Playboard, on_turn: StartOrder, moves: usize) -> Result<Puzzle, String> {
+        check_position(playboard)?;
+
+        match find_win_length(playboard, on_turn) {
+            Some(length) if length == moves => Ok(Puzzle {
+                notation: playboard.get_notation(),
+                on_turn,
+                moves,
+            }),
+            Some(length) => Err(format!(
+                "Position {} is a win in {}, not in {}.",
+                playboard.get_notation(),
+                length,
+                moves
+            )),
+            None => Err(format!(
+                "The {} player has no forced win in position {}.",
+                on_turn,
+                playboard.get_notation()
+            )),
+        }
+    }
+
+    pub fn get_playboard(&self) -> Playboard {
+        // Notation was written by the playboard.
+        Playboard::from_str(&self.notation).unwrap()
+    }
+
+    pub fn get_notation(&self) -> &str {
+        &self.notation
+    }
+
+    pub fn get_on_turn(&self) -> StartOrder {
+        self.on_turn
+    }
+
+    pub fn get_moves(&self) -> usize {
+        self.moves
+    }
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.notation, self.on_turn, self.moves)
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Puzzle, Self::Err> {
+        let (notation, on_turn, moves) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [notation, on_turn, moves] => (notation, on_turn, moves),
+            _ => {
+                return Err(format!(
+                    "Puzzle {} is not position, player on turn and number of moves.",
+                    line.trim()
+                ))
+            }
+        };
+
+        let playboard = Playboard::from_str(notation)?;
+        let on_turn = StartOrder::from_str(on_turn)
+            .map_err(|_| format!("{} is not first or second player.", on_turn))?;
+        let moves = match moves.parse::<usize>() {
+            Ok(moves) if moves > 0 => moves,
+            _ => return Err(format!("{} is not number of moves.", moves)),
+        };
+
+        Puzzle::new(&playboard, on_turn, moves)
+    }
+}
+
+/// Reads puzzles or solved puzzles of progress file, errors tell the line.
+pub fn load_puzzles(input: impl BufRead) -> Result<Vec<Puzzle>, String> {
+    let mut puzzles = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(|err| err.to_string())?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(PUZZLE_COMMENT) {
+            continue;
+        }
+
+        let puzzle =
+            Puzzle::from_str(line).map_err(|msg| format!("Line {}: {}", index + 1, msg))?;
+        puzzles.push(puzzle);
+    }
+
+    Ok(puzzles)
+}
+
+/// Result of a move played in the puzzle.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PuzzleProgress {
+    /// Move won the game, puzzle is solved.
+    Solved,
+    /// Move keeps the win, defender replied on (row, col).
+    Replied(usize, usize),
+    /// Move lets the defender escape. Reply on (row, col) is shown when the game
+    /// did not end by the move.
+    Failed(Option<(usize, usize)>),
+}
+
+/// Puzzle being solved, rows and cols are indexed from 1.
+pub struct PuzzleGame {
+    puzzle: Puzzle,
+    playboard: Playboard,
+    moves_left: usize,
+    finished: bool,
+}
+
+impl PuzzleGame {
+    pub fn new(puzzle: Puzzle) -> Self {
+        Self {
+            playboard: puzzle.get_playboard(),
+            moves_left: puzzle.get_moves(),
+            puzzle,
+            finished: false,
+        }
+    }
+
+    pub fn get_puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    pub fn get_playboard(&self) -> &Playboard {
+        &self.playboard
+    }
+
+    pub fn get_moves_left(&self) -> usize {
+        self.moves_left
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Starts the puzzle from its position again.
+    pub fn restart(&mut self) {
+        *self = PuzzleGame::new(self.puzzle.clone());
+    }
+
+    /// Plays the move of the player on turn, error when the place is not free or the
+    /// puzzle is finished.
+    pub fn play(&mut self, row: usize, col: usize) -> Result<PuzzleProgress, String> {
+        if self.finished {
+            return Err("Puzzle is finished, retry it or go to the next one.".to_string());
+        }
+
+        let attacker = self.puzzle.get_on_turn();
+
+        let progress = match self.playboard.place_on_grid(row, col, attacker) {
+            GameState::InvalidPlace => {
+                return Err(format!("Place ({}, {}) is not free.", row, col));
+            }
+            GameState::GameOver => PuzzleProgress::Solved,
+            GameState::Draw => PuzzleProgress::Failed(None),
+            _ => {
+                self.moves_left -= 1;
+                self.reply(attacker)
+            }
+        };
+
+        self.finished = !matches!(progress, PuzzleProgress::Replied(..));
+
+        Ok(progress)
+    }
+
+    fn reply(&mut self, attacker: StartOrder) -> PuzzleProgress {
+        // Playboard is not full, the move did not finish the game.
+        let (row, col) = get_defence(&self.playboard, attacker.oponent()).unwrap();
+
+        match self.playboard.place_on_grid(row, col, attacker.oponent()) {
+            GameState::Placed => match find_win_length(&self.playboard, attacker) {
+                Some(length) if length <= self.moves_left => PuzzleProgress::Replied(row, col),
+                _ => PuzzleProgress::Failed(Some((row, col))),
+            },
+            _ => PuzzleProgress::Failed(Some((row, col))),
+        }
+    }
+}
+
+/// Builds position of a puzzle by placing and clearing symbols freely.
+pub struct PositionEditor {
+    playboard: Playboard,
+    on_turn: StartOrder,
+}
+
+impl PositionEditor {
+    /// Empty playboard with the first player on turn.
+    pub fn new() -> Self {
+        Self {
+            playboard: Playboard::new(),
+            on_turn: StartOrder::First,
+        }
+    }
+
+    pub fn get_playboard(&self) -> &Playboard {
+        &self.playboard
+    }
+
+    pub fn get_on_turn(&self) -> StartOrder {
+        self.on_turn
+    }
+
+    /// Edits the position by one of `x (1, 2)`, `o (1, 2)`, `clear (1, 2)`, `clear`
+    /// for empty playboard, `turn second` or whole position like `x.o/.x./...`.
+    pub fn edit(&mut self, arguments: &str) -> Result<(), String> {
+        let arguments = arguments.trim();
+        let (keyword, data) = arguments.split_once(' ').unwrap_or((arguments, ""));
+
+        match keyword.to_lowercase().as_str() {
+            "x" | "o" | SETUP_CLEAR if !data.trim().is_empty() => {
+                let (row, col) = match decode_cells(data)[..] {
+                    [(row, col)] => (row, col),
+                    _ => return Err(format!("{} is not one cell like (1, 2).", data.trim())),
+                };
+
+                if !self.playboard.clear_grid_option(row, col) {
+                    return Err(format!("Cell ({}, {}) is out of playboard.", row, col));
+                }
+
+                let grid_option = match keyword.to_lowercase().as_str() {
+                    "x" => PlayBoardGridOptions::X,
+                    "o" => PlayBoardGridOptions::O,
+                    _ => return Ok(()),
+                };
+                self.playboard.place_grid_option(row, col, grid_option);
+            }
+            SETUP_CLEAR => self.playboard.clear_board(),
+            SETUP_TURN => {
+                self.on_turn = StartOrder::from_str(data.trim())
+                    .map_err(|_| format!("{} is not first or second player.", data.trim()))?;
+            }
+            _ if data.is_empty() => {
+                let playboard = Playboard::from_str(keyword)?;
+                check_symbols(&playboard)?;
+                self.playboard = playboard;
+            }
+            _ => return Err(format!("{} is not a setup command.", arguments)),
+        }
+
+        Ok(())
+    }
+
+    /// Puzzle of the position, the number of moves is the fastest win.
+    pub fn create_puzzle(&self) -> Result<Puzzle, String> {
+        check_position(&self.playboard)?;
+
+        match find_win_length(&self.playboard, self.on_turn) {
+            Some(moves) => Puzzle::new(&self.playboard, self.on_turn, moves),
+            None => Err(format!(
+                "The {} player has no forced win in the position.",
+                self.on_turn
+            )),
+        }
+    }
+}
+
+impl Default for PositionEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fewest moves the player on turn wins in against any defence, None when the
+/// player cannot force the win.
+pub fn find_win_length(playboard: &Playboard, on_turn: StartOrder) -> Option<usize> {
+    let free_cells = get_free_cells(playboard).len();
+
+    // Player on turn places every second symbol.
+    (1..=free_cells.div_ceil(2)).find(|&moves| can_win_within(playboard, on_turn, moves))
+}
+
+/// Reply of the defender avoiding the loss or delaying it the most, None on full
+/// playboard.
+pub fn get_defence(playboard: &Playboard, defender: StartOrder) -> Option<(usize, usize)> {
+    // Wins first, then positions without forced win, then the slowest losses.
+    get_free_cells(playboard)
+        .into_iter()
+        .map(|(row, col)| {
+            let mut playboard = playboard.clone();
+            let rank = match playboard.place_on_grid(row, col, defender) {
+                GameState::GameOver => usize::MAX,
+                _ => match find_win_length(&playboard, defender.oponent()) {
+                    None => usize::MAX - 1,
+                    Some(length) => length,
+                },
+            };
+
+            ((row, col), rank)
+        })
+        .fold(None, |best, (cell, rank)| match best {
+            Some((_, best_rank)) if best_rank >= rank => best,
+            _ => Some((cell, rank)),
+        })
+        .map(|(cell, _)| cell)
+}
+
+// True when the player wins with at most given number of own moves.
+fn can_win_within(playboard: &Playboard, on_turn: StartOrder, moves: usize) -> bool {
+    if moves == 0 {
+        return false;
+    }
+
+    get_free_cells(playboard).into_iter().any(|(row, col)| {
+        let mut playboard = playboard.clone();
+
+        match playboard.place_on_grid(row, col, on_turn) {
+            GameState::GameOver => true,
+            GameState::Placed if moves > 1 => {
+                get_free_cells(&playboard)
+                    .into_iter()
+                    .all(|(reply_row, reply_col)| {
+                        let mut playboard = playboard.clone();
+
+                        playboard.place_on_grid(reply_row, reply_col, on_turn.oponent())
+                            == GameState::Placed
+                            && can_win_within(&playboard, on_turn, moves - 1)
+                    })
+            }
+            _ => false,
+        }
+    })
+}
+
+fn get_free_cells(playboard: &Playboard) -> Vec<(usize, usize)> {
+    // Players index from 1.
+    (1..=PLAYBOARD_ROW_COL_SIZE)
+        .flat_map(|row| (1..=PLAYBOARD_ROW_COL_SIZE).map(move |col| (row, col)))
+        .filter(|&(row, col)| {
+            playboard.get_grid()[(row - 1) * PLAYBOARD_ROW_COL_SIZE + col - 1]
+                == PlayBoardGridOptions::Free
+        })
+        .collect()
+}
+
+fn check_symbols(playboard: &Playboard) -> Result<(), String> {
+    if playboard
+        .get_grid()
+        .iter()
+        .any(|grid_option| matches!(grid_option, PlayBoardGridOptions::Number(_)))
+    {
+        return Err("Puzzles have only x and o symbols.".to_string());
+    }
+
+    Ok(())
+}
+
+fn check_position(playboard: &Playboard) -> Result<(), String> {
+    check_symbols(playboard)?;
+
+    if playboard.check_for_game_win() || playboard.check_for_full_playboard() {
+        return Err(format!(
+            "Game in position {} is already finished.",
+            playboard.get_notation()
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Write};
+
+use crate::cli_args_processing::PuzzleArguments;
+use crate::game_messages::{
+    print_all_puzzles_solved, print_puzzle, print_puzzle_created, print_puzzle_error,
+    print_puzzle_help, print_puzzle_progress, print_puzzles_loaded, print_setup_position,
+    print_setup_unfinished, print_unknown_puzzle_command,
+};
+use tic_tac_toe_game::playboard_variant::decode_cells;
+use tic_tac_toe_game::puzzle::{load_puzzles, PositionEditor, Puzzle, PuzzleGame, PuzzleProgress};
+use tic_tac_toe_game::settings::commands::{
+    HELP_COMMAND, PLACE_ON_PLAYBOARD_COMMAND, QUIT_COMMAND, RETRY_COMMAND, SETUP_CANCEL,
+    SETUP_COMMAND, SETUP_DONE, SKIP_COMMAND,
+};
+
+// Missing file is read as empty, puzzles are added to it by setup.
+fn read_puzzles(path: &str) -> Vec<Puzzle> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Vec::new(),
+        Err(err) => panic!("Cannot read file {}: {}.", path, err),
+    };
+
+    load_puzzles(BufReader::new(file)).unwrap_or_else(|err| panic!("{} {}", path, err))
+}
+
+// Written at once, so the puzzle is kept when the mode is interrupted.
+fn append_puzzle(path: &str, puzzle: &Puzzle) {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", puzzle))
+        .unwrap_or_else(|err| panic!("Failed to write file {}: {}.", path, err));
+}
+
+struct PuzzleMode {
+    arguments: PuzzleArguments,
+    puzzles: Vec<Puzzle>,
+    solved: HashSet<Puzzle>,
+    // Index of the played puzzle in the puzzles.
+    index: usize,
+    game: Option<PuzzleGame>,
+    editor: Option<PositionEditor>,
+}
+
+impl PuzzleMode {
+    fn new(arguments: PuzzleArguments) -> Self {
+        let puzzles = read_puzzles(&arguments.path);
+        let solved: HashSet<Puzzle> = read_puzzles(&arguments.progress_path).into_iter().collect();
+
+        let solved_count = puzzles
+            .iter()
+            .filter(|puzzle| solved.contains(puzzle))
+            .count();
+        print_puzzles_loaded(puzzles.len(), solved_count, &arguments.path);
+
+        let mut puzzle_mode = Self {
+            arguments,
+            puzzles,
+            solved,
+            index: 0,
+            game: None,
+            editor: None,
+        };
+        puzzle_mode.start_unsolved(0);
+
+        puzzle_mode
+    }
+
+    // Starts the first unsolved puzzle from the index on, wrapping around.
+    fn start_unsolved(&mut self, from: usize) {
+        let count = self.puzzles.len();
+        let index = (0..count)
+            .map(|offset| (from + offset) % count)
+            .find(|&index| !self.solved.contains(&self.puzzles[index]));
+
+        match index {
+            Some(index) => self.start_puzzle(index),
+            None => {
+                self.game = None;
+                print_all_puzzles_solved();
+            }
+        }
+    }
+
+    fn start_puzzle(&mut self, index: usize) {
+        self.index = index;
+        self.game = Some(PuzzleGame::new(self.puzzles[index].clone()));
+        self.print_puzzle();
+    }
+
+    fn print_puzzle(&self) {
+        if let Some(game) = &self.game {
+            print_puzzle(game, self.index + 1);
+        }
+    }
+
+    fn play(&mut self, data: &str) {
+        if self.editor.is_some() {
+            print_setup_unfinished();
+            return;
+        }
+
+        let game = match self.game.as_mut() {
+            Some(game) => game,
+            None => {
+                print_all_puzzles_solved();
+                return;
+            }
+        };
+
+        let (row, col) = match decode_cells(data)[..] {
+            [cell] => cell,
+            _ => {
+                print_puzzle_error(format!("{} is not one cell like (1, 2).", data.trim()));
+                return;
+            }
+        };
+
+        let progress = match game.play(row, col) {
+            Ok(progress) => progress,
+            Err(msg) => {
+                print_puzzle_error(msg);
+                return;
+            }
+        };
+
+        print_puzzle_progress(progress, game, self.index + 1);
+
+        if progress == PuzzleProgress::Solved {
+            let puzzle = game.get_puzzle().clone();
+
+            if self.solved.insert(puzzle.clone()) {
+                append_puzzle(&self.arguments.progress_path, &puzzle);
+            }
+
+            self.start_unsolved(self.index + 1);
+        }
+    }
+
+    fn setup(&mut self, arguments: &str) {
+        let editor = self.editor.get_or_insert_with(PositionEditor::new);
+
+        match arguments {
+            "" => {}
+            SETUP_CANCEL => {
+                self.editor = None;
+                self.print_puzzle();
+                return;
+            }
+            SETUP_DONE => match editor.create_puzzle() {
+                Ok(puzzle) => {
+                    append_puzzle(&self.arguments.path, &puzzle);
+                    self.puzzles.push(puzzle);
+                    self.editor = None;
+
+                    print_puzzle_created(self.puzzles.len(), &self.arguments.path);
+                    self.start_puzzle(self.puzzles.len() - 1);
+                    return;
+                }
+                Err(msg) => {
+                    print_puzzle_error(msg);
+                    return;
+                }
+            },
+            arguments => {
+                if let Err(msg) = editor.edit(arguments) {
+                    print_puzzle_error(msg);
+                    return;
+                }
+            }
+        }
+
+        print_setup_position(editor);
+    }
+
+    // Returns false when the mode should quit.
+    fn process_command(&mut self, line: &str) -> bool {
+        let (command, arguments) = line.split_once(' ').unwrap_or((line, ""));
+
+        if let Some(data) = line.strip_prefix(PLACE_ON_PLAYBOARD_COMMAND) {
+            self.play(data);
+        } else if command == SETUP_COMMAND {
+            self.setup(arguments.trim());
+        } else if line == RETRY_COMMAND {
+            if let Some(game) = self.game.as_mut() {
+                game.restart();
+            }
+            self.print_puzzle();
+        } else if line == SKIP_COMMAND {
+            if self.game.is_some() {
+                self.start_unsolved(self.index + 1);
+            }
+        } else if line == HELP_COMMAND {
+            print_puzzle_help();
+        } else if line == QUIT_COMMAND {
+            return false;
+        } else if !line.is_empty() {
+            print_unknown_puzzle_command(line);
+        }
+
+        true
+    }
+}
+
+pub fn run_puzzle_command(arguments: PuzzleArguments) {
+    let mut puzzle_mode = PuzzleMode::new(arguments);
+    let mut line = String::new();
+
+    while let Ok(bytes_read) = io::stdin().read_line(&mut line) {
+        if bytes_read == 0 || !puzzle_mode.process_command(line.trim()) {
+            break;
+        }
+
+        line = String::new();
+    }
+}
@@ -0,0 +1,315 @@
+//! Player learning the classic variant by tabular Q-learning in self-play.
+//!
+//! Both sides share one table of values of moves for the player on turn, so the
+//! value of a move is the negated value of the best reply of the oponent. Training
+//! explores random moves with given probability and runs on CPU only, a seeded
+//! training always learns the same policy.
+//!
+//! Policies are stored as text. The first line is `tic-tac-toe-policy <version>`,
+//! each following one the position written row by row with `x`, `o` and `.` for an
+//! empty cell and the values of placing on all cells.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use crate::ai_player::{play_move, AiPlayer};
+use crate::game_options::{GameVariant, StartOrder};
+use crate::playboard::{GameState, PlayBoardGridOptions, Playboard};
+use crate::settings::ai_options::{
+    POLICY_FORMAT_VERSION, POLICY_HEADER, POLICY_PLAYER_NAME, TRAINING_DEFAULT_DISCOUNT,
+    TRAINING_DEFAULT_EPISODES, TRAINING_DEFAULT_EXPLORATION, TRAINING_DEFAULT_LEARNING_RATE,
+};
+use crate::settings::commands::PLACE_ON_PLAYBOARD_COMMAND;
+use crate::settings::playboard_options::{PLAYBOARD_ROW_COL_SIZE, PLAYBOARD_SIZE};
+
+/// Parameters of the self-play training.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TrainingOptions {
+    pub episodes: u32,
+    /// How far a value moves towards the new estimate.
+    pub learning_rate: f64,
+    /// How much the value of the following position counts.
+    pub discount: f64,
+    /// Probability of a random move instead of the best one.
+    pub exploration: f64,
+    /// Random training when not given.
+    pub seed: Option<u64>,
+}
+
+impl Default for TrainingOptions {
+    fn default() -> Self {
+        Self {
+            episodes: TRAINING_DEFAULT_EPISODES,
+            learning_rate: TRAINING_DEFAULT_LEARNING_RATE,
+            discount: TRAINING_DEFAULT_DISCOUNT,
+            exploration: TRAINING_DEFAULT_EXPLORATION,
+            seed: None,
+        }
+    }
+}
+
+/// Results of the self-play games of the training.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TrainingStats {
+    pub episodes: u32,
+    pub first_wins: u32,
+    pub second_wins: u32,
+    pub draws: u32,
+    /// Positions the policy knows.
+    pub positions: usize,
+}
+
+/// Values of placing on every cell for the player on turn in learned positions.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Policy {
+    values: HashMap<String, [f64; PLAYBOARD_SIZE]>,
+}
+
+impl Policy {
+    /// Learns the policy by self-play games on the playboard.
+    pub fn train(options: &TrainingOptions) -> (Policy, TrainingStats) {
+        let mut rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let mut policy = Policy::default();
+        let mut stats = TrainingStats {
+            episodes: options.episodes,
+            ..TrainingStats::default()
+        };
+
+        for _ in 0..options.episodes {
+            let mut playboard = Playboard::new();
+            let mut on_turn = StartOrder::First;
+
+            loop {
+                let position = get_position(&playboard);
+                let free_cells = get_free_cells(&playboard);
+                let cell = if rng.gen::<f64>() < options.exploration {
+                    *free_cells.choose(&mut rng).unwrap()
+                } else {
+                    policy.get_best_cell(&position, &free_cells).unwrap()
+                };
+
+                // Players index from 1.
+                let game_state = playboard.place_on_grid(
+                    cell / PLAYBOARD_ROW_COL_SIZE + 1,
+                    cell % PLAYBOARD_ROW_COL_SIZE + 1,
+                    on_turn,
+                );
+                let target = match game_state {
+                    GameState::GameOver => 1.0,
+                    GameState::Draw => 0.0,
+                    _ => {
+                        let reply = get_position(&playboard);
+                        -options.discount
+                            * policy.get_best_value(&reply, &get_free_cells(&playboard))
+                    }
+                };
+
+                let value = &mut policy
+                    .values
+                    .entry(position)
+                    .or_insert([0.0; PLAYBOARD_SIZE])[cell];
+                *value += options.learning_rate * (target - *value);
+
+                match (game_state, on_turn) {
+                    (GameState::GameOver, StartOrder::First) => stats.first_wins += 1,
+                    (GameState::GameOver, StartOrder::Second) => stats.second_wins += 1,
+                    (GameState::Draw, _) => stats.draws += 1,
+                    _ => {
+                        on_turn = on_turn.oponent();
+                        continue;
+                    }
+                }
+
+                break;
+            }
+        }
+
+        stats.positions = policy.len();
+        (policy, stats)
+    }
+
+    /// Reads the policy written by save.
+    pub fn load(input: impl BufRead) -> Result<Policy, String> {
+        let mut lines = input.lines();
+        let header = lines
+            .next()
+            .and_then(Result::ok)
+            .ok_or("Policy is empty.")?;
+
+        match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [POLICY_HEADER, version] if version.parse::<u32>() == Ok(POLICY_FORMAT_VERSION) => {}
+            [POLICY_HEADER, version] => {
+                return Err(format!(
+                    "Policy version {} is not supported, train the policy again.",
+                    version
+                ))
+            }
+            _ => return Err("Policy header is missing.".to_string()),
+        }
+
+        let mut policy = Policy::default();
+
+        for line in lines {
+            let line = line.map_err(|err| err.to_string())?;
+            let (position, values) =
+                parse_entry(&line).ok_or(format!("Policy entry {} is not valid.", line))?;
+            policy.values.insert(position, values);
+        }
+
+        Ok(policy)
+    }
+
+    /// Writes the policy in the form read by load, positions sorted.
+    pub fn save(&self, mut output: impl Write) -> io::Result<()> {
+        writeln!(output, "{} {}", POLICY_HEADER, POLICY_FORMAT_VERSION)?;
+
+        let mut positions: Vec<&String> = self.values.keys().collect();
+        positions.sort();
+
+        for position in positions {
+            let values: Vec<String> = self.values[position]
+                .iter()
+                .map(|value| value.to_string())
+                .collect();
+            writeln!(output, "{} {}", position, values.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Best of the free cells, the first one in unknown position.
+    fn get_best_cell(&self, position: &str, free_cells: &[usize]) -> Option<usize> {
+        let values = self.values.get(position);
+
+        free_cells
+            .iter()
+            .copied()
+            .reduce(|best, cell| match values {
+                Some(values) if values[cell] > values[best] => cell,
+                _ => best,
+            })
+    }
+
+    fn get_best_value(&self, position: &str, free_cells: &[usize]) -> f64 {
+        match (
+            self.values.get(position),
+            self.get_best_cell(position, free_cells),
+        ) {
+            (Some(values), Some(cell)) => values[cell],
+            _ => 0.0,
+        }
+    }
+}
+
+// Position as written in policy files.
+fn get_position(playboard: &Playboard) -> String {
+    playboard
+        .get_grid()
+        .iter()
+        .map(|grid_option| match grid_option {
+            PlayBoardGridOptions::X => 'x',
+            PlayBoardGridOptions::O => 'o',
+            _ => '.',
+        })
+        .collect()
+}
+
+fn get_free_cells(playboard: &Playboard) -> Vec<usize> {
+    (0..PLAYBOARD_SIZE)
+        .filter(|&cell| playboard.get_grid()[cell] == PlayBoardGridOptions::Free)
+        .collect()
+}
+
+fn parse_entry(line: &str) -> Option<(String, [f64; PLAYBOARD_SIZE])> {
+    let mut parts = line.split_whitespace();
+    let position = parts.next()?;
+
+    if position.len() != PLAYBOARD_SIZE || !position.chars().all(|symbol| "xo.".contains(symbol)) {
+        return None;
+    }
+
+    let mut values = [0.0; PLAYBOARD_SIZE];
+    for value in values.iter_mut() {
+        *value = parts.next()?.parse().ok()?;
+    }
+
+    parts
+        .next()
+        .is_none()
+        .then(|| (position.to_string(), values))
+}
+
+/// Plays the best moves of the learned policy.
+pub struct PolicyPlayer {
+    policy: Policy,
+    path: String,
+}
+
+impl PolicyPlayer {
+    /// Player of the policy saved in the file.
+    pub fn load(path: &str) -> io::Result<Self> {
+        let file = io::BufReader::new(std::fs::File::open(path)?);
+        let policy =
+            Policy::load(file).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(Self {
+            policy,
+            path: path.to_string(),
+        })
+    }
+}
+
+impl AiPlayer for PolicyPlayer {
+    fn get_name(&self) -> String {
+        format!("{} {}", POLICY_PLAYER_NAME, self.path)
+    }
+
+    fn new_game(&mut self, variant: GameVariant, _start_order: StartOrder) -> io::Result<()> {
+        if variant != GameVariant::Classic {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} player plays only classic variant", POLICY_PLAYER_NAME),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String> {
+        let mut playboard = Playboard::new();
+        let mut on_turn = StartOrder::First;
+
+        for game_move in moves {
+            if play_move(&mut playboard, game_move, on_turn).is_ok() {
+                on_turn = on_turn.oponent();
+            }
+        }
+
+        // Players index from 1.
+        self.policy
+            .get_best_cell(&get_position(&playboard), &get_free_cells(&playboard))
+            .map(|cell| {
+                format!(
+                    "{}({}, {})",
+                    PLACE_ON_PLAYBOARD_COMMAND,
+                    cell / PLAYBOARD_ROW_COL_SIZE + 1,
+                    cell % PLAYBOARD_ROW_COL_SIZE + 1
+                )
+            })
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no valid move left"))
+    }
+}
@@ -0,0 +1,415 @@
+//! Quantum tic tac toe, every move places spooky marks into two cells which
+//! collapse into classical ones when they form an entanglement cycle.
+
+use colored::*;
+
+use crate::game_options::StartOrder;
+use crate::playboard::GameState;
+use crate::playboard_variant::{decode_cells, invalid_option_message, PlayboardVariant};
+use crate::settings::commands::{COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND};
+use crate::settings::playboard_options::{
+    PLAYBOARD_GRID_HEIGHT, PLAYBOARD_GRID_WIDTH, PLAYBOARD_ROW_COL_SIZE, PLAYBOARD_SIZE,
+};
+
+const SUBSCRIPT_DIGITS: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+const SPOOKY_MARKS_PER_LINE: usize = 3;
+
+// Every move is one edge of entanglement graph between its two cells.
+#[derive(Clone)]
+struct QuantumMove {
+    start_order: StartOrder,
+    cells: [usize; 2],
+    collapsed_to: Option<usize>,
+}
+
+#[derive(Clone)]
+pub struct QuantumPlayboard {
+    moves: Vec<QuantumMove>,
+    // Index of move occupying cell classically.
+    classical: [Option<usize>; PLAYBOARD_SIZE],
+    // Move which closed entanglement cycle and waits for collapse.
+    pending_collapse: Option<usize>,
+    winner: Option<StartOrder>,
+}
+
+impl QuantumPlayboard {
+    pub fn new() -> Self {
+        Self {
+            moves: Vec::new(),
+            classical: [None; PLAYBOARD_SIZE],
+            pending_collapse: None,
+            winner: None,
+        }
+    }
+
+    fn i2d_into_1d(row: usize, col: usize) -> usize {
+        row * PLAYBOARD_ROW_COL_SIZE + col
+    }
+
+    fn decode_grid_index(&self, (row, col): (usize, usize)) -> Option<usize> {
+        if row == 0 || row > PLAYBOARD_ROW_COL_SIZE || col == 0 || col > PLAYBOARD_ROW_COL_SIZE {
+            return None;
+        }
+
+        let index = Self::i2d_into_1d(row - 1, col - 1);
+
+        match self.classical[index] {
+            Some(_) => None,
+            None => Some(index),
+        }
+    }
+
+    fn symbol(start_order: StartOrder) -> char {
+        match start_order {
+            StartOrder::First => 'X',
+            StartOrder::Second => 'O',
+        }
+    }
+
+    fn subscript(number: usize) -> String {
+        number
+            .to_string()
+            .chars()
+            .filter_map(|digit| digit.to_digit(10))
+            .map(|digit| SUBSCRIPT_DIGITS[digit as usize])
+            .collect()
+    }
+
+    fn is_entangled(&self, quantum_move: &QuantumMove) -> bool {
+        quantum_move.collapsed_to.is_none()
+    }
+
+    // Walks uncollapsed moves from cell `from` to find out whether `to` is reachable.
+    fn is_connected(&self, from: usize, to: usize) -> bool {
+        let mut visited = [false; PLAYBOARD_SIZE];
+        let mut stack = vec![from];
+
+        while let Some(cell) = stack.pop() {
+            if cell == to {
+                return true;
+            }
+
+            if visited[cell] {
+                continue;
+            }
+            visited[cell] = true;
+
+            for quantum_move in self.moves.iter().filter(|m| self.is_entangled(m)) {
+                if quantum_move.cells[0] == cell {
+                    stack.push(quantum_move.cells[1]);
+                } else if quantum_move.cells[1] == cell {
+                    stack.push(quantum_move.cells[0]);
+                }
+            }
+        }
+
+        false
+    }
+
+    // Collapses move into cell and forces every other spooky mark in that cell
+    // into its second cell, which resolves whole entangled component.
+    fn collapse_move(&mut self, move_index: usize, cell: usize) {
+        let mut to_collapse = vec![(move_index, cell)];
+
+        while let Some((move_index, cell)) = to_collapse.pop() {
+            if !self.is_entangled(&self.moves[move_index]) {
+                continue;
+            }
+
+            self.moves[move_index].collapsed_to = Some(cell);
+            self.classical[cell] = Some(move_index);
+
+            for (other_index, other_move) in self.moves.iter().enumerate() {
+                if other_index == move_index || !self.is_entangled(other_move) {
+                    continue;
+                }
+
+                if other_move.cells[0] == cell {
+                    to_collapse.push((other_index, other_move.cells[1]));
+                } else if other_move.cells[1] == cell {
+                    to_collapse.push((other_index, other_move.cells[0]));
+                }
+            }
+        }
+    }
+
+    fn get_lines() -> Vec<[usize; PLAYBOARD_ROW_COL_SIZE]> {
+        let mut lines = Vec::new();
+
+        for index in 0..PLAYBOARD_ROW_COL_SIZE {
+            let mut row = [0; PLAYBOARD_ROW_COL_SIZE];
+            let mut col = [0; PLAYBOARD_ROW_COL_SIZE];
+
+            for inner in 0..PLAYBOARD_ROW_COL_SIZE {
+                row[inner] = Self::i2d_into_1d(index, inner);
+                col[inner] = Self::i2d_into_1d(inner, index);
+            }
+
+            lines.push(row);
+            lines.push(col);
+        }
+
+        let mut main_diagonal = [0; PLAYBOARD_ROW_COL_SIZE];
+        let mut anti_diagonal = [0; PLAYBOARD_ROW_COL_SIZE];
+
+        for index in 0..PLAYBOARD_ROW_COL_SIZE {
+            main_diagonal[index] = Self::i2d_into_1d(index, index);
+            anti_diagonal[index] = Self::i2d_into_1d(index, PLAYBOARD_ROW_COL_SIZE - 1 - index);
+        }
+
+        lines.push(main_diagonal);
+        lines.push(anti_diagonal);
+
+        lines
+    }
+
+    // When both players complete line by one collapse, the one whose line was
+    // finished by lower move number wins.
+    fn evaluate_game_state(&mut self) -> GameState {
+        let mut best_line: Option<(usize, StartOrder)> = None;
+
+        for line in Self::get_lines() {
+            let move_indexes = line
+                .iter()
+                .map(|&cell| self.classical[cell])
+                .collect::<Option<Vec<usize>>>();
+
+            let move_indexes = match move_indexes {
+                Some(move_indexes) => move_indexes,
+                None => continue,
+            };
+
+            let start_order = self.moves[move_indexes[0]].start_order;
+
+            if move_indexes
+                .iter()
+                .any(|&index| self.moves[index].start_order != start_order)
+            {
+                continue;
+            }
+
+            let completed_by = *move_indexes.iter().max().unwrap();
+
+            if best_line.is_none_or(|(best, _)| completed_by < best) {
+                best_line = Some((completed_by, start_order));
+            }
+        }
+
+        if let Some((_, start_order)) = best_line {
+            self.winner = Some(start_order);
+            GameState::GameOver
+        } else if self.classical.iter().all(|cell| cell.is_some()) {
+            GameState::Draw
+        } else {
+            GameState::Placed
+        }
+    }
+
+    fn get_grid_cell_text_lines(&self, cell: usize) -> Vec<String> {
+        if let Some(move_index) = self.classical[cell] {
+            return vec![Self::symbol(self.moves[move_index].start_order).to_string()];
+        }
+
+        let spooky_marks = self
+            .moves
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| self.is_entangled(m) && m.cells.contains(&cell))
+            .map(|(index, m)| {
+                format!(
+                    "{}{}",
+                    Self::symbol(m.start_order),
+                    Self::subscript(index + 1)
+                )
+            })
+            .collect::<Vec<String>>();
+
+        spooky_marks
+            .chunks(SPOOKY_MARKS_PER_LINE)
+            .map(|chunk| chunk.join(" "))
+            .collect()
+    }
+}
+
+impl Default for QuantumPlayboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlayboardVariant for QuantumPlayboard {
+    fn place(
+        &mut self,
+        data: &str,
+        start_order: StartOrder,
+    ) -> Result<(String, GameState), String> {
+        if self.pending_collapse.is_some() {
+            return Err(format!(
+                "Entanglement cycle must be collapsed first with {}(row, col).",
+                COLLAPSE_COMMAND
+            ));
+        }
+
+        let cells = decode_cells(data);
+
+        let invalid_place_message = format!(
+            "Invalid cells specified for {}. Use two different free cells, e.g. {}(1, 1) (2, 2). Try again.",
+            PLACE_ON_PLAYBOARD_COMMAND, PLACE_ON_PLAYBOARD_COMMAND
+        );
+
+        let free_cells = self.classical.iter().filter(|cell| cell.is_none()).count();
+
+        match cells.len() {
+            // Last free cell is played classically.
+            1 if free_cells == 1 => {
+                let cell = self
+                    .decode_grid_index(cells[0])
+                    .ok_or(invalid_place_message)?;
+
+                self.moves.push(QuantumMove {
+                    start_order,
+                    cells: [cell, cell],
+                    collapsed_to: Some(cell),
+                });
+                self.classical[cell] = Some(self.moves.len() - 1);
+
+                let (row, col) = cells[0];
+                Ok((format!("({}, {})", row, col), self.evaluate_game_state()))
+            }
+            2 => {
+                let first = self.decode_grid_index(cells[0]);
+                let second = self.decode_grid_index(cells[1]);
+
+                let (first, second) = match (first, second) {
+                    (Some(first), Some(second)) if first != second => (first, second),
+                    _ => return Err(invalid_place_message),
+                };
+
+                let closes_cycle = self.is_connected(first, second);
+
+                self.moves.push(QuantumMove {
+                    start_order,
+                    cells: [first, second],
+                    collapsed_to: None,
+                });
+
+                let description = format!(
+                    "({}, {}) and ({}, {})",
+                    cells[0].0, cells[0].1, cells[1].0, cells[1].1
+                );
+
+                if closes_cycle {
+                    self.pending_collapse = Some(self.moves.len() - 1);
+                    Ok((description, GameState::CollapsePending))
+                } else {
+                    Ok((description, GameState::Placed))
+                }
+            }
+            _ if cells.is_empty() => Err(invalid_option_message(PLACE_ON_PLAYBOARD_COMMAND)),
+            _ => Err(invalid_place_message),
+        }
+    }
+
+    fn collapse(
+        &mut self,
+        data: &str,
+        _start_order: StartOrder,
+    ) -> Result<(String, GameState), String> {
+        let move_index = match self.pending_collapse {
+            Some(move_index) => move_index,
+            None => return Err("There is no entanglement cycle to collapse.".to_string()),
+        };
+
+        let (row, col) = match decode_cells(data).first() {
+            Some(&cell) => cell,
+            None => return Err(invalid_option_message(COLLAPSE_COMMAND)),
+        };
+
+        let cell = match self.decode_grid_index((row, col)) {
+            Some(cell) if self.moves[move_index].cells.contains(&cell) => cell,
+            _ => {
+                return Err(format!(
+                    "Mark can collapse only into one of the cells of move {}. Try again.",
+                    move_index + 1
+                ))
+            }
+        };
+
+        self.collapse_move(move_index, cell);
+        self.pending_collapse = None;
+
+        Ok((format!("({}, {})", row, col), self.evaluate_game_state()))
+    }
+
+    fn is_collapse_pending(&self) -> bool {
+        self.pending_collapse.is_some()
+    }
+
+    fn winner(&self) -> Option<StartOrder> {
+        self.winner
+    }
+
+    fn get_cell_text_lines(&self, row: usize, col: usize) -> Vec<String> {
+        self.get_grid_cell_text_lines(Self::i2d_into_1d(row, col))
+    }
+
+    fn get_board_notes(&self) -> Vec<String> {
+        match self.pending_collapse {
+            Some(move_index) => vec![format!(
+                "Entanglement cycle closed by move {} waits for collapse.",
+                move_index + 1
+            )],
+            None => Vec::new(),
+        }
+    }
+
+    fn is_cell_free(&self, row: usize, col: usize) -> bool {
+        self.classical[Self::i2d_into_1d(row, col)].is_none()
+    }
+
+    fn get_board_printable(&self) -> String {
+        let mut printable_lines = Vec::new();
+
+        for row in 0..PLAYBOARD_ROW_COL_SIZE {
+            let cells_text_lines = (0..PLAYBOARD_ROW_COL_SIZE)
+                .map(|col| self.get_grid_cell_text_lines(Self::i2d_into_1d(row, col)))
+                .collect::<Vec<Vec<String>>>();
+
+            for line in 0..PLAYBOARD_GRID_HEIGHT {
+                let mut printable_line = String::new();
+
+                for (col, cell_text_lines) in cells_text_lines.iter().enumerate() {
+                    // Vertically center text lines inside the cell.
+                    let first_text_line = (PLAYBOARD_GRID_HEIGHT - cell_text_lines.len()) / 2;
+
+                    let text = line
+                        .checked_sub(first_text_line)
+                        .and_then(|index| cell_text_lines.get(index))
+                        .map_or("", |text| text.as_str());
+
+                    let text = format!("{:^width$}", text, width = PLAYBOARD_GRID_WIDTH).blue();
+
+                    let text = if (row * PLAYBOARD_ROW_COL_SIZE + col) % 2 == 1 {
+                        text.on_bright_white()
+                    } else {
+                        text.on_black()
+                    };
+
+                    printable_line.push_str(&text.to_string());
+                }
+
+                printable_lines.push(printable_line);
+            }
+        }
+
+        printable_lines.join("\n")
+    }
+
+    fn clear_board(&mut self) {
+        *self = Self::new();
+    }
+
+    fn clone_box(&self) -> Box<dyn PlayboardVariant> {
+        Box::new(self.clone())
+    }
+}
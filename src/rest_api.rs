@@ -0,0 +1,389 @@
+//! HTTP/JSON API for dashboards and bots, built with `rest-api` feature.
+//!
+//! - `GET /games` lists registered games.
+//! - `GET /games/{id}` returns board, turn, history and clocks of the game.
+//! - `POST /games/{id}/moves` plays move of the local player, body is `{"place": "(1, 1)"}`
+//!   or `{"collapse": "(1, 1)"}`. Move is checked by the game itself, rejected one is
+//!   reported as `invalid_move` event.
+//! - `GET /games/{id}/events` streams game events as server-sent events.
+
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::timeout;
+
+use crate::game_event::{GameEvent, GameEventHandler};
+use crate::game_options::GameVariant;
+use crate::game_session::SharedGameSession;
+use crate::host_type_objects::{CommandSender, HostTypeObject};
+use crate::host_type_objects_utility::{accept_connection, bind_tcp_listener, create_runtime};
+use crate::settings::commands::{COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND};
+use crate::settings::network_options::{
+    API_EVENT_BUFFER_SIZE, API_REQUEST_MAX_BODY_SIZE, WEB_REQUEST_TIMEOUT,
+};
+use crate::settings::playboard_options::PLAYBOARD_ROW_COL_SIZE;
+use crate::web_transport::{invalid_request, read_request, write_response};
+
+struct ApiGame {
+    variant: GameVariant,
+    // Both are set once the oponent connected.
+    session: Option<SharedGameSession>,
+    command_sender: Option<CommandSender>,
+    events: broadcast::Sender<GameEvent>,
+}
+
+type SharedApiGames = Arc<Mutex<BTreeMap<u32, ApiGame>>>;
+
+/// Game registered in the API, it is removed from the API when the handle is dropped.
+pub struct ApiGameHandle {
+    id: u32,
+    games: SharedApiGames,
+    events: broadcast::Sender<GameEvent>,
+}
+
+impl ApiGameHandle {
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    /// Publishes events of the game to the API before passing them to `event_handler`.
+    pub fn wrap_event_handler(&self, event_handler: GameEventHandler) -> GameEventHandler {
+        let events = self.events.clone();
+
+        Arc::new(move |event: GameEvent| {
+            // Fails only when nobody listens.
+            let _ = events.send(event.clone());
+            event_handler(event);
+        })
+    }
+
+    /// Makes running game visible and playable through the API.
+    pub fn attach(&self, host_type_object: &dyn HostTypeObject) {
+        if let Some(game) = self.games.lock().unwrap().get_mut(&self.id) {
+            game.session = Some(host_type_object.get_session());
+            game.command_sender = Some(host_type_object.get_command_sender());
+        }
+    }
+}
+
+impl Drop for ApiGameHandle {
+    fn drop(&mut self) {
+        self.games.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// HTTP server of the API, runs until dropped.
+pub struct GameApi {
+    _runtime: Runtime,
+    addr: SocketAddr,
+    games: SharedApiGames,
+    next_id: AtomicU32,
+}
+
+impl GameApi {
+    /// Serves the API on given address in its own runtime.
+    pub fn start(addr: SocketAddr) -> io::Result<Self> {
+        let runtime = create_runtime();
+        let listener = runtime.block_on(async { bind_tcp_listener(addr) })?;
+        let addr = listener.local_addr()?;
+
+        let games: SharedApiGames = Arc::new(Mutex::new(BTreeMap::new()));
+        runtime.spawn(run_api(listener, games.clone()));
+
+        Ok(Self {
+            _runtime: runtime,
+            addr,
+            games,
+            next_id: AtomicU32::new(1),
+        })
+    }
+
+    pub fn get_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Registers game waiting for the oponent, see [`ApiGameHandle::attach`].
+    pub fn add_game(&self, variant: GameVariant) -> ApiGameHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (events, _) = broadcast::channel(API_EVENT_BUFFER_SIZE);
+
+        self.games.lock().unwrap().insert(
+            id,
+            ApiGame {
+                variant,
+                session: None,
+                command_sender: None,
+                events: events.clone(),
+            },
+        );
+
+        ApiGameHandle {
+            id,
+            games: self.games.clone(),
+            events,
+        }
+    }
+}
+
+async fn run_api(listener: TcpListener, games: SharedApiGames) {
+    while let Ok((stream, _)) = accept_connection(&listener).await {
+        tokio::spawn(handle_api_request(stream, games.clone()));
+    }
+}
+
+type ApiResponse = (&'static str, Value);
+
+fn api_error(status: &'static str, message: &str) -> ApiResponse {
+    (status, json!({ "error": message }))
+}
+
+fn get_game_summary(id: u32, game: &ApiGame) -> Value {
+    let mut summary = json!({
+        "id": id,
+        "variant": game.variant.to_string(),
+    });
+
+    match &game.session {
+        None => summary["state"] = json!("waiting_for_oponent"),
+        Some(session) => {
+            let status = session.lock().unwrap().get_game_status();
+
+            summary["state"] = json!(match (status.connected, status.game_running) {
+                (false, _) => "disconnected",
+                (true, true) => "running",
+                (true, false) => "finished",
+            });
+            summary["turn"] = json!(if status.my_turn { "you" } else { "oponent" });
+            summary["score"] = json!({
+                "wins": status.score.wins,
+                "losses": status.score.losses,
+                "draws": status.score.draws,
+            });
+        }
+    }
+
+    summary
+}
+
+fn get_game_detail(id: u32, game: &ApiGame) -> Value {
+    let mut detail = get_game_summary(id, game);
+
+    let session = match &game.session {
+        Some(session) => session.lock().unwrap(),
+        None => return detail,
+    };
+    let playboard = session.get_playboard();
+
+    // Cells are indexed from 0 here, players index them from 1 in moves.
+    detail["board"] = (0..PLAYBOARD_ROW_COL_SIZE)
+        .map(|row| {
+            (0..PLAYBOARD_ROW_COL_SIZE)
+                .map(|col| {
+                    playboard
+                        .get_cell_text_lines(row, col)
+                        .join(" ")
+                        .trim()
+                        .to_string()
+                })
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<Vec<String>>>()
+        .into();
+    detail["notes"] = playboard.get_board_notes().into();
+
+    detail["history"] = session
+        .get_history()
+        .iter()
+        .map(|game_move| {
+            json!({
+                "player": game_move.player,
+                "command": game_move.command,
+                "place": game_move.place,
+                "duration_ms": game_move.duration.as_millis() as u64,
+            })
+        })
+        .collect::<Vec<Value>>()
+        .into();
+
+    let clocks = session.get_clocks();
+    detail["clocks"] = json!({
+        "you_ms": clocks.you.as_millis() as u64,
+        "oponent_ms": clocks.oponent.as_millis() as u64,
+    });
+
+    detail
+}
+
+fn parse_game_id(id: &str) -> Result<u32, ApiResponse> {
+    id.parse::<u32>()
+        .map_err(|_| api_error("400 Bad Request", "game id must be a number"))
+}
+
+fn get_game(games: &SharedApiGames, id: &str) -> ApiResponse {
+    let id = match parse_game_id(id) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match games.lock().unwrap().get(&id) {
+        Some(game) => ("200 OK", get_game_detail(id, game)),
+        None => api_error("404 Not Found", "game not found"),
+    }
+}
+
+fn post_move(games: &SharedApiGames, id: &str, body: &str) -> ApiResponse {
+    let id = match parse_game_id(id) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let body: Value = match serde_json::from_str(body) {
+        Ok(body) => body,
+        Err(_) => return api_error("400 Bad Request", "body must be JSON object"),
+    };
+
+    let command = match (body["place"].as_str(), body["collapse"].as_str()) {
+        (Some(place), None) => format!("{}{}", PLACE_ON_PLAYBOARD_COMMAND, place),
+        (None, Some(collapse)) => format!("{}{}", COLLAPSE_COMMAND, collapse),
+        _ => {
+            return api_error(
+                "400 Bad Request",
+                "body must contain either place or collapse",
+            )
+        }
+    };
+
+    let games = games.lock().unwrap();
+    let game = match games.get(&id) {
+        Some(game) => game,
+        None => return api_error("404 Not Found", "game not found"),
+    };
+
+    let (session, command_sender) = match (&game.session, &game.command_sender) {
+        (Some(session), Some(command_sender)) => (session, command_sender),
+        _ => return api_error("409 Conflict", "oponent has not connected yet"),
+    };
+
+    let status = session.lock().unwrap().get_game_status();
+    if !status.game_running {
+        return api_error("409 Conflict", "game is not running");
+    }
+    if !status.my_turn {
+        return api_error("409 Conflict", "it is not your turn");
+    }
+
+    command_sender(command.clone());
+
+    ("202 Accepted", json!({ "command": command }))
+}
+
+// Names of the events are the same as in JSON of the event.
+fn get_event_name(event: &Value) -> String {
+    match event {
+        Value::String(name) => name.clone(),
+        Value::Object(fields) => fields.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+// Keeps the connection open until the game is removed or the client disconnects.
+async fn stream_events<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    games: &SharedApiGames,
+    id: &str,
+) -> io::Result<()> {
+    let receiver = parse_game_id(id).ok().and_then(|id| {
+        games
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|game| game.events.subscribe())
+    });
+
+    let mut receiver = match receiver {
+        Some(receiver) => receiver,
+        None => {
+            return write_api_response(stream, api_error("404 Not Found", "game not found")).await
+        }
+    };
+
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n")
+        .await?;
+    stream.flush().await?;
+
+    loop {
+        let event = match receiver.recv().await {
+            Ok(event) => event,
+            // Slow client misses some events rather than holding the game back.
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return Ok(()),
+        };
+
+        let data = serde_json::to_value(&event).map_err(io::Error::other)?;
+        let message = format!("event: {}\ndata: {}\n\n", get_event_name(&data), data);
+
+        stream.write_all(message.as_bytes()).await?;
+        stream.flush().await?;
+    }
+}
+
+async fn write_api_response<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    (status, body): ApiResponse,
+) -> io::Result<()> {
+    write_response(stream, status, "application/json", &body.to_string()).await
+}
+
+async fn handle_api_request(stream: TcpStream, games: SharedApiGames) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+
+    let request = timeout(WEB_REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .map_err(|_| invalid_request("request timed out"))??;
+
+    let path = request.path.trim_matches('/');
+    let segments: Vec<&str> = path.split('/').collect();
+
+    let response = match (request.method.as_str(), &segments[..]) {
+        ("GET", ["games"]) => {
+            let games = games.lock().unwrap();
+            let summaries: Vec<Value> = games
+                .iter()
+                .map(|(id, game)| get_game_summary(*id, game))
+                .collect();
+
+            ("200 OK", Value::from(summaries))
+        }
+        ("GET", ["games", id]) => get_game(&games, id),
+        ("POST", ["games", id, "moves"]) => {
+            let length = request
+                .headers
+                .get("content-length")
+                .and_then(|length| length.parse::<usize>().ok())
+                .unwrap_or(0);
+
+            if length > API_REQUEST_MAX_BODY_SIZE {
+                api_error("413 Payload Too Large", "body is too large")
+            } else {
+                let mut body = vec![0u8; length];
+                timeout(WEB_REQUEST_TIMEOUT, stream.read_exact(&mut body))
+                    .await
+                    .map_err(|_| invalid_request("request timed out"))??;
+
+                post_move(&games, id, &String::from_utf8_lossy(&body))
+            }
+        }
+        ("GET", ["games", id, "events"]) => return stream_events(&mut stream, &games, id).await,
+        _ => api_error("404 Not Found", "unknown path"),
+    };
+
+    write_api_response(&mut stream, response).await
+}
@@ -0,0 +1,258 @@
+//! Optional TLS for game connections.
+//!
+//! Server uses self-signed certificate generated on the first run. Client has no
+//! authority to check it against, so it trusts the certificate seen first for the host
+//! and port and pins its fingerprint in known hosts file, like ssh does.
+
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{ring, verify_tls12_signature, verify_tls13_signature};
+use tokio_rustls::rustls::crypto::{CryptoProvider, WebPkiSupportedAlgorithms};
+use tokio_rustls::rustls::pki_types::{
+    CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime,
+};
+use tokio_rustls::rustls::{
+    ClientConfig, DigitallySignedStruct, Error, ServerConfig, SignatureScheme,
+};
+use tokio_rustls::{client, TlsAcceptor, TlsConnector};
+
+use crate::host_type_objects_utility::get_home_file_path;
+use crate::settings::network_options::{
+    KNOWN_HOSTS_FILE_NAME, TLS_CERTIFICATE_FILE_NAME, TLS_PRIVATE_KEY_FILE_NAME, TLS_SERVER_NAME,
+};
+
+/// SHA-256 of the certificate as colon separated hex pairs.
+pub fn get_certificate_fingerprint(certificate: &[u8]) -> String {
+    Sha256::digest(certificate)
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+fn write_private_file(path: &PathBuf, data: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(data)
+}
+
+/// Certificate of the server with its private key.
+pub struct ServerIdentity {
+    certificate: CertificateDer<'static>,
+    private_key: PrivatePkcs8KeyDer<'static>,
+}
+
+impl ServerIdentity {
+    /// Loads certificate stored in home directory, generates it on the first run.
+    pub fn load_or_create() -> io::Result<Self> {
+        let certificate_path = get_home_file_path(TLS_CERTIFICATE_FILE_NAME);
+        let private_key_path = get_home_file_path(TLS_PRIVATE_KEY_FILE_NAME);
+
+        if let (Ok(certificate), Ok(private_key)) =
+            (fs::read(&certificate_path), fs::read(&private_key_path))
+        {
+            return Ok(Self {
+                certificate: CertificateDer::from(certificate),
+                private_key: PrivatePkcs8KeyDer::from(private_key),
+            });
+        }
+
+        let identity = Self::generate()?;
+
+        write_private_file(&private_key_path, identity.private_key.secret_pkcs8_der())?;
+        fs::write(&certificate_path, &identity.certificate)?;
+
+        Ok(identity)
+    }
+
+    /// New self-signed certificate, not stored anywhere.
+    pub fn generate() -> io::Result<Self> {
+        let certified_key = rcgen::generate_simple_self_signed(vec![TLS_SERVER_NAME.to_string()])
+            .map_err(io::Error::other)?;
+
+        Ok(Self {
+            certificate: certified_key.cert.der().clone(),
+            private_key: PrivatePkcs8KeyDer::from(certified_key.key_pair.serialize_der()),
+        })
+    }
+
+    pub fn get_fingerprint(&self) -> String {
+        get_certificate_fingerprint(&self.certificate)
+    }
+
+    pub fn create_acceptor(&self) -> io::Result<TlsAcceptor> {
+        let config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![self.certificate.clone()],
+                PrivateKeyDer::Pkcs8(self.private_key.clone_key()),
+            )
+            .map_err(io::Error::other)?;
+
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+// Trust on first use, certificate is accepted when it is the one pinned for the host
+// or when the host has none pinned yet. Host is address with port, as servers of several
+// games may run on one machine.
+#[derive(Debug)]
+struct PinningCertificateVerifier {
+    host: String,
+    known_hosts_path: PathBuf,
+    supported_algorithms: WebPkiSupportedAlgorithms,
+    // Set when host has no pinned certificate, pinned only after successful handshake.
+    fingerprint_to_pin: Mutex<Option<String>>,
+}
+
+impl PinningCertificateVerifier {
+    fn new(addr: SocketAddr, known_hosts_path: PathBuf) -> Self {
+        let provider: CryptoProvider = ring::default_provider();
+
+        Self {
+            host: addr.to_string(),
+            known_hosts_path,
+            supported_algorithms: provider.signature_verification_algorithms,
+            fingerprint_to_pin: Mutex::new(None),
+        }
+    }
+
+    // Every line of known hosts file is host and fingerprint separated by space.
+    fn get_pinned_fingerprint(&self) -> Option<String> {
+        let known_hosts = fs::read_to_string(&self.known_hosts_path).ok()?;
+
+        known_hosts.lines().find_map(|line| {
+            let (host, fingerprint) = line.trim().split_once(' ')?;
+            (host == self.host).then(|| fingerprint.trim().to_string())
+        })
+    }
+
+    fn pin_fingerprint(&self, fingerprint: &str) -> io::Result<()> {
+        OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.known_hosts_path)?
+            .write_all(format!("{} {}\n", self.host, fingerprint).as_bytes())
+    }
+}
+
+impl ServerCertVerifier for PinningCertificateVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, Error> {
+        let fingerprint = get_certificate_fingerprint(end_entity);
+
+        match self.get_pinned_fingerprint() {
+            Some(pinned) if pinned == fingerprint => {}
+            Some(pinned) => {
+                return Err(Error::General(format!(
+                    "certificate of {} changed from pinned {} to {}, remove its line from {} if you trust it",
+                    self.host,
+                    pinned,
+                    fingerprint,
+                    self.known_hosts_path.display()
+                )))
+            }
+            None => *self.fingerprint_to_pin.lock().unwrap() = Some(fingerprint),
+        }
+
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        verify_tls12_signature(message, cert, dss, &self.supported_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, Error> {
+        verify_tls13_signature(message, cert, dss, &self.supported_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.supported_algorithms.supported_schemes()
+    }
+}
+
+/// Server certificate accepted by the client.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrustedCertificate {
+    pub fingerprint: String,
+    /// Host was not known before and its certificate was pinned now.
+    pub newly_pinned: bool,
+}
+
+/// Connects to the server at the address, certificates are pinned in known hosts file
+/// in home directory.
+pub async fn connect_tls(
+    stream: TcpStream,
+    addr: SocketAddr,
+) -> io::Result<(client::TlsStream<TcpStream>, TrustedCertificate)> {
+    connect_tls_pinned(stream, addr, get_home_file_path(KNOWN_HOSTS_FILE_NAME)).await
+}
+
+/// Same as [`connect_tls`] with certificates pinned in the given known hosts file.
+pub async fn connect_tls_pinned(
+    stream: TcpStream,
+    addr: SocketAddr,
+    known_hosts_path: PathBuf,
+) -> io::Result<(client::TlsStream<TcpStream>, TrustedCertificate)> {
+    let verifier = Arc::new(PinningCertificateVerifier::new(addr, known_hosts_path));
+
+    let config = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(ServerName::IpAddress(addr.ip().into()), stream)
+        .await?;
+
+    let fingerprint = stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|certificates| certificates.first())
+        .map(|certificate| get_certificate_fingerprint(certificate))
+        .unwrap_or_default();
+
+    let fingerprint_to_pin = verifier.fingerprint_to_pin.lock().unwrap().take();
+    let newly_pinned = fingerprint_to_pin.is_some();
+
+    if let Some(fingerprint_to_pin) = fingerprint_to_pin {
+        verifier.pin_fingerprint(&fingerprint_to_pin)?;
+    }
+
+    Ok((
+        stream,
+        TrustedCertificate {
+            fingerprint,
+            newly_pinned,
+        },
+    ))
+}
@@ -0,0 +1,39 @@
+// Monte Carlo tree search, see mcts_player.
+pub const MCTS_PLAYER_NAME: &str = "mcts";
+pub const MCTS_DEFAULT_PLAYOUTS: u32 = 2000;
+// Seeded search without threads given, the same on every machine.
+pub const MCTS_SEEDED_THREADS: usize = 1;
+// Exploration constant of UCT, square root of 2 balances wins and rarely tried moves.
+pub const MCTS_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+// Moves of the best ones shown by hint.
+pub const HINT_SHOWN_MOVES: usize = 3;
+// Threat-space search, see threat_player.
+pub const THREAT_PLAYER_NAME: &str = "threat";
+// Fours of one forced win looked ahead at most.
+pub const THREAT_SEARCH_DEPTH: usize = 8;
+// Boards larger than the playboard, gomoku by default.
+pub const BOARD_DEFAULT_WIN_LENGTH: usize = 5;
+pub const BOARD_MAX_SIZE: usize = 19;
+// Opening book, see opening_book.
+pub const BOOK_HEADER: &str = "tic-tac-toe-book";
+// Books of other versions have to be generated again.
+pub const BOOK_FORMAT_VERSION: u32 = 2;
+pub const BOOK_MIN_SIZE: usize = 3;
+pub const BOOK_MAX_SIZE: usize = 4;
+// Self-play training, see q_learning.
+pub const POLICY_PLAYER_NAME: &str = "policy";
+pub const POLICY_HEADER: &str = "tic-tac-toe-policy";
+// Policies of other versions have to be trained again.
+pub const POLICY_FORMAT_VERSION: u32 = 1;
+pub const TRAINING_DEFAULT_EPISODES: u32 = 200000;
+pub const TRAINING_DEFAULT_LEARNING_RATE: f64 = 0.3;
+pub const TRAINING_DEFAULT_DISCOUNT: f64 = 0.95;
+pub const TRAINING_DEFAULT_EXPLORATION: f64 = 0.3;
+// AI players with personality, see personality.
+pub const PERSONALITY_PLAYER_NAME: &str = "personality";
+pub const PERSONALITY_DEFAULT_DEPTH: usize = 3;
+pub const PERSONALITY_DEFAULT_CHAT_PROBABILITY: f64 = 0.3;
+// Win in N puzzles, see puzzle.
+pub const PUZZLE_COMMENT: char = '#';
+// Solved puzzles are kept next to the puzzle file when no progress file is given.
+pub const PUZZLE_PROGRESS_EXTENSION: &str = ".solved";
@@ -1,7 +1,44 @@
 pub const PRIVATE_MESSAGE_COMMAND: &str = "/pm ";
 pub const PLACE_ON_PLAYBOARD_COMMAND: &str = "/place ";
+pub const COLLAPSE_COMMAND: &str = "/collapse ";
 pub const PLAY_AGAIN_COMMAND: &str = "/playagain";
 pub const CLEAR_COMMAND: &str = "/clear";
 pub const QUIT_COMMAND: &str = "/quit";
 pub const HELP_COMMAND: &str = "/help";
 pub const AGREE_COMMAND: &str = "/agree";
+pub const REGISTER_COMMAND: &str = "/register ";
+pub const QUEUE_COMMAND: &str = "/queue";
+pub const LEAVE_COMMAND: &str = "/leave";
+pub const HINT_COMMAND: &str = "/hint";
+
+// Puzzle mode, see puzzle of the library. Position is edited by the setup command
+// with given keywords and finished by done or thrown away by cancel.
+pub const SETUP_COMMAND: &str = "/setup";
+pub const SETUP_CLEAR: &str = "clear";
+pub const SETUP_TURN: &str = "turn";
+pub const SETUP_DONE: &str = "done";
+pub const SETUP_CANCEL: &str = "cancel";
+pub const RETRY_COMMAND: &str = "/retry";
+pub const SKIP_COMMAND: &str = "/skip";
+pub const PUZZLE_COMMANDS: [&str; 3] = [SETUP_COMMAND, RETRY_COMMAND, SKIP_COMMAND];
+
+// Sent only by hub, which starts games of tournament matches with given start order.
+pub const NEW_GAME_COMMAND: &str = "/newgame ";
+
+pub const ALL_COMMANDS: [&str; 15] = [
+    PRIVATE_MESSAGE_COMMAND,
+    PLACE_ON_PLAYBOARD_COMMAND,
+    COLLAPSE_COMMAND,
+    PLAY_AGAIN_COMMAND,
+    CLEAR_COMMAND,
+    QUIT_COMMAND,
+    HELP_COMMAND,
+    AGREE_COMMAND,
+    REGISTER_COMMAND,
+    QUEUE_COMMAND,
+    LEAVE_COMMAND,
+    HINT_COMMAND,
+    SETUP_COMMAND,
+    RETRY_COMMAND,
+    SKIP_COMMAND,
+];
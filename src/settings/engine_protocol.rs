@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+// Lines written to the engine, see engine_player.
+pub const ENGINE_NEW_GAME: &str = "newgame ";
+pub const ENGINE_POSITION: &str = "position ";
+pub const ENGINE_GO: &str = "go";
+pub const ENGINE_QUIT: &str = "quit";
+
+// Lines written by the engine.
+pub const ENGINE_BEST_MOVE: &str = "bestmove ";
+pub const ENGINE_CHAT: &str = "chat ";
+
+// Separates moves of the position.
+pub const ENGINE_MOVE_SEPARATOR: &str = "; ";
+
+// Engine asked directly forfeits when it does not answer in time.
+pub const ENGINE_MOVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Engine is killed when it does not quit in time.
+pub const ENGINE_QUIT_TIMEOUT: Duration = Duration::from_secs(2);
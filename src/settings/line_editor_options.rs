@@ -0,0 +1,4 @@
+pub const LINE_EDITOR_PROMPT: &str = "> ";
+
+// stored in home directory, current directory when home is unknown
+pub const HISTORY_FILE_NAME: &str = ".tic_tac_toe_game_history";
@@ -1,2 +1,6 @@
+pub mod ai_options;
 pub mod commands;
+pub mod engine_protocol;
+pub mod line_editor_options;
+pub mod network_options;
 pub mod playboard_options;
@@ -0,0 +1,51 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+// Client gives up when server does not accept connection in time.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Player leaves the queue of the hub when nobody joined them in time.
+pub const HUB_WAITING_TIMEOUT: Duration = Duration::from_secs(600);
+pub const LOBBY_REQUEST_BUFFER_SIZE: usize = 64;
+
+// Player on turn in game refereed by hub loses when they do not move in time.
+pub const HUB_MOVE_TIMEOUT: Duration = Duration::from_secs(120);
+// Knockout match drawn after all its games continues by this many deciding games.
+pub const TOURNAMENT_TIEBREAK_GAMES: u32 = 2;
+
+// Files in home directory.
+pub const TLS_CERTIFICATE_FILE_NAME: &str = ".tic_tac_toe_game_certificate.der";
+pub const TLS_PRIVATE_KEY_FILE_NAME: &str = ".tic_tac_toe_game_private_key.der";
+pub const KNOWN_HOSTS_FILE_NAME: &str = ".tic_tac_toe_game_known_hosts";
+
+// Server rejects client which did not finish TLS handshake in time.
+pub const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Certificates are pinned by fingerprint, name is not checked.
+pub const TLS_SERVER_NAME: &str = "tic-tac-toe-game";
+
+// Password authentication messages, exchanged before the game starts.
+pub const AUTHENTICATION_CHALLENGE: &str = "/challenge ";
+pub const AUTHENTICATION_RESPONSE: &str = "/response ";
+pub const AUTHENTICATION_ACCEPTED: &str = "/authenticated ";
+pub const AUTHENTICATION_REJECTED: &str = "/rejected";
+pub const AUTHENTICATION_CHALLENGE_SIZE: usize = 32;
+pub const AUTHENTICATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Servers announce their games on local network, see game_discovery.
+pub const DISCOVERY_PORT: u16 = 45999;
+pub const DISCOVERY_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(239, 255, 42, 99);
+pub const DISCOVERY_ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+pub const DISCOVERY_LISTEN_DURATION: Duration = Duration::from_millis(2500);
+pub const DISCOVERY_MESSAGE_PREFIX: &str = "tic_tac_toe_game;";
+
+// Browser players load the client page and play over WebSocket, see web_transport.
+pub const WEB_CLIENT_PATH: &str = "/";
+pub const WEB_CLIENT_SCRIPT_PATH: &str = "/game_client.js";
+pub const WEB_SOCKET_PATH: &str = "/ws";
+pub const WEB_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+pub const WEB_REQUEST_MAX_SIZE: usize = 8192;
+
+// Game API of rest-api feature.
+pub const API_EVENT_BUFFER_SIZE: usize = 64;
+pub const API_REQUEST_MAX_BODY_SIZE: usize = 4096;
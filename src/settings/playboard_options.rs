@@ -1,6 +1,20 @@
 pub const PLAYBOARD_SIZE: usize = 9;
 pub const PLAYBOARD_ROW_COL_SIZE: usize = 3;
 
+// numerical variant, odd numbers for first player and even for second
+pub const NUMERICAL_MAX_NUMBER: u8 = 9;
+pub const NUMERICAL_WINNING_SUM: u32 = 15;
+
+// Zobrist keys are generated from this seed, the same in every build, so hashes of
+// positions can be compared between players.
+pub const ZOBRIST_SEED: u64 = 0x7469_635f_7461_635f;
+// Cells of the largest square board hashed, 4x4 board of the opening book.
+pub const ZOBRIST_MAX_CELLS: usize = 16;
+
+// Position notation, e.g. "x.o/.x./..o".
+pub const NOTATION_ROW_SEPARATOR: char = '/';
+pub const NOTATION_FREE: char = '.';
+
 // simulate square
 pub const PLAYBOARD_GRID_WIDTH: usize = 13;
 pub const PLAYBOARD_GRID_HEIGHT: usize = 6;
@@ -9,3 +23,7 @@ pub const PLAYBOARD_GRID_HEIGHT: usize = 6;
 pub const PLAYBOARD_COLOR_TEXT: u8 = 27; // blue
 pub const PLAYBOARD_GRID_COLOR1: u8 = 0; // black
 pub const PLAYBOARD_GRID_COLOR2: u8 = 15; // whiteish
+
+// text user interface
+pub const PLAYBOARD_GRID_CURSOR_COLOR: u8 = 220; // yellow
+pub const PLAYBOARD_GRID_SELECTED_COLOR: u8 = 34; // green
@@ -0,0 +1,484 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton, MouseEventKind,
+};
+use crossterm::style::{
+    Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{stdout, Stdout, Write};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use crate::game_messages::{print_game_help, print_game_welcome_message};
+use crate::game_output::{print_output, redirect_output_to_text_user_interface, restore_output};
+use crate::host_type_communication_handler::process_command;
+use tic_tac_toe_game::game_options::GameVariant;
+use tic_tac_toe_game::game_session::GameStatus;
+use tic_tac_toe_game::host_type_objects::HostTypeObject;
+use tic_tac_toe_game::settings::commands::{
+    CLEAR_COMMAND, COLLAPSE_COMMAND, PLACE_ON_PLAYBOARD_COMMAND,
+};
+use tic_tac_toe_game::settings::playboard_options::{
+    PLAYBOARD_COLOR_TEXT, PLAYBOARD_GRID_COLOR1, PLAYBOARD_GRID_COLOR2,
+    PLAYBOARD_GRID_CURSOR_COLOR, PLAYBOARD_GRID_HEIGHT, PLAYBOARD_GRID_SELECTED_COLOR,
+    PLAYBOARD_GRID_WIDTH, PLAYBOARD_ROW_COL_SIZE,
+};
+
+const EVENT_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+const CHAT_HISTORY_LIMIT: usize = 1000;
+
+const STATUS_BAR_ROW: u16 = 0;
+const PLAYBOARD_TOP_ROW: u16 = 2;
+const CHAT_PANE_GAP: u16 = 3;
+
+const KEYS_HINT: &str =
+    "Arrows/Alt+hjkl move, Enter or mouse click places symbol, Enter sends typed command, Esc cancels, Ctrl+C quits.";
+
+// Switches terminal to raw mode and alternate screen, restores it when dropped (also on panic).
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+
+        if let Err(err) = execute!(stdout(), EnterAlternateScreen, EnableMouseCapture, Hide) {
+            let _ = terminal::disable_raw_mode();
+            return Err(err);
+        }
+
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), Show, DisableMouseCapture, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct TextUserInterface {
+    variant: GameVariant,
+    // Playboard cursor, indexed from 0.
+    cursor: (usize, usize),
+    // First cell of quantum move waiting for the second one.
+    selected_cell: Option<(usize, usize)>,
+    input: String,
+    chat: Vec<String>,
+    chat_receiver: Receiver<String>,
+    redraw: bool,
+    last_game_status: Option<GameStatus>,
+}
+
+impl TextUserInterface {
+    fn new(variant: GameVariant, chat_receiver: Receiver<String>) -> Self {
+        Self {
+            variant,
+            cursor: (0, 0),
+            selected_cell: None,
+            input: String::new(),
+            chat: Vec::new(),
+            chat_receiver,
+            redraw: true,
+            last_game_status: None,
+        }
+    }
+
+    fn run(&mut self, host_type_object: &mut Box<dyn HostTypeObject>) {
+        let mut stdout = stdout();
+
+        loop {
+            while let Ok(line) = self.chat_receiver.try_recv() {
+                self.chat.push(line);
+                self.redraw = true;
+            }
+
+            if self.chat.len() > CHAT_HISTORY_LIMIT {
+                self.chat.drain(..self.chat.len() - CHAT_HISTORY_LIMIT);
+            }
+
+            let game_status = host_type_object.get_game_status();
+            if self.last_game_status != Some(game_status) {
+                self.last_game_status = Some(game_status);
+                self.redraw = true;
+            }
+
+            if self.redraw {
+                if self
+                    .draw(&mut stdout, host_type_object.as_ref(), game_status)
+                    .is_err()
+                {
+                    break;
+                }
+                self.redraw = false;
+            }
+
+            match event::poll(EVENT_POLL_TIMEOUT) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(_) => break,
+            }
+
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    self.redraw = true;
+
+                    if !self.handle_key(key, host_type_object) {
+                        break;
+                    }
+                }
+                Ok(Event::Mouse(mouse))
+                    if mouse.kind == MouseEventKind::Down(MouseButton::Left) =>
+                {
+                    self.redraw = true;
+                    self.handle_click(mouse.column, mouse.row, host_type_object);
+                }
+                Ok(Event::Resize(_, _)) => self.redraw = true,
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+    }
+
+    fn move_cursor(&mut self, row_offset: isize, col_offset: isize) {
+        let max_index = PLAYBOARD_ROW_COL_SIZE as isize - 1;
+
+        self.cursor = (
+            (self.cursor.0 as isize + row_offset).clamp(0, max_index) as usize,
+            (self.cursor.1 as isize + col_offset).clamp(0, max_index) as usize,
+        );
+    }
+
+    // Returns false when application should quit.
+    fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        host_type_object: &mut Box<dyn HostTypeObject>,
+    ) -> bool {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Up => self.move_cursor(-1, 0),
+            KeyCode::Down => self.move_cursor(1, 0),
+            KeyCode::Left => self.move_cursor(0, -1),
+            KeyCode::Right => self.move_cursor(0, 1),
+            // Plain letters are always typed, words and messages may start with them.
+            KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.move_cursor(-1, 0)
+            }
+            KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.move_cursor(1, 0)
+            }
+            KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.move_cursor(0, -1)
+            }
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.move_cursor(0, 1)
+            }
+            KeyCode::Char(character) => self.input.push(character),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.selected_cell = None;
+            }
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input).trim().to_string();
+
+                if line.is_empty() || line.chars().all(|c| c.is_ascii_digit()) {
+                    let cell = (self.cursor.0 + 1, self.cursor.1 + 1);
+                    self.place_on_cell(cell, &line, host_type_object);
+                } else if line == CLEAR_COMMAND {
+                    self.chat.clear();
+                    print_game_help();
+                } else {
+                    return process_command(line, host_type_object);
+                }
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    // Translates cell into command of current game variant. Row and col are indexed
+    // from 1 as in commands, cells outside playboard are passed on to get the same
+    // errors as typed commands.
+    fn place_on_cell(
+        &mut self,
+        (row, col): (usize, usize),
+        number: &str,
+        host_type_object: &mut Box<dyn HostTypeObject>,
+    ) {
+        let on_playboard = (1..=PLAYBOARD_ROW_COL_SIZE).contains(&row)
+            && (1..=PLAYBOARD_ROW_COL_SIZE).contains(&col);
+
+        let (collapse_pending, cell_free) = {
+            let arc_session = host_type_object.get_session();
+            let guard_session = arc_session.lock().unwrap();
+            let playboard = guard_session.get_playboard();
+
+            (
+                playboard.is_collapse_pending(),
+                on_playboard && playboard.is_cell_free(row - 1, col - 1),
+            )
+        };
+
+        // Taken cell is not selected, the place command is rejected as when typed.
+        let game_status = host_type_object.get_game_status();
+        let can_select = cell_free && game_status.my_turn && game_status.game_running;
+
+        let command = match self.variant {
+            _ if collapse_pending => format!("{}({}, {})", COLLAPSE_COMMAND, row, col),
+            GameVariant::Classic => format!("{}({}, {})", PLACE_ON_PLAYBOARD_COMMAND, row, col),
+            GameVariant::Numerical if number.is_empty() && on_playboard => {
+                print_output("Type number to place first.");
+                return;
+            }
+            GameVariant::Numerical => {
+                format!(
+                    "{}({}, {}) {}",
+                    PLACE_ON_PLAYBOARD_COMMAND, row, col, number
+                )
+            }
+            GameVariant::Quantum => match self.selected_cell.take() {
+                None if can_select => {
+                    self.selected_cell = Some((row - 1, col - 1));
+                    print_output("First cell selected, choose the second one.");
+                    return;
+                }
+                None => format!("{}({}, {})", PLACE_ON_PLAYBOARD_COMMAND, row, col),
+                Some((first_row, first_col)) => format!(
+                    "{}({}, {}) ({}, {})",
+                    PLACE_ON_PLAYBOARD_COMMAND,
+                    first_row + 1,
+                    first_col + 1,
+                    row,
+                    col
+                ),
+            },
+        };
+
+        host_type_object.send_message(command);
+    }
+
+    fn handle_click(
+        &mut self,
+        column: u16,
+        row: u16,
+        host_type_object: &mut Box<dyn HostTypeObject>,
+    ) {
+        let cell_row = match row.checked_sub(PLAYBOARD_TOP_ROW) {
+            Some(row) => row as usize / PLAYBOARD_GRID_HEIGHT + 1,
+            None => 0,
+        };
+        let cell_col = column as usize / PLAYBOARD_GRID_WIDTH + 1;
+
+        if (1..=PLAYBOARD_ROW_COL_SIZE).contains(&cell_row)
+            && (1..=PLAYBOARD_ROW_COL_SIZE).contains(&cell_col)
+        {
+            self.cursor = (cell_row - 1, cell_col - 1);
+        }
+
+        // Number for numerical variant is typed before clicking.
+        let number = if self.input.trim().chars().all(|c| c.is_ascii_digit()) {
+            std::mem::take(&mut self.input).trim().to_string()
+        } else {
+            String::new()
+        };
+
+        self.place_on_cell((cell_row, cell_col), &number, host_type_object);
+    }
+
+    fn get_status_text(&self, game_status: GameStatus, collapse_pending: bool) -> String {
+        let turn = if !game_status.connected {
+            "Oponent disconnected"
+        } else if !game_status.game_running {
+            "Game finished"
+        } else if game_status.my_turn && collapse_pending {
+            "Your turn, collapse"
+        } else if game_status.my_turn {
+            "Your turn"
+        } else {
+            "Oponent's turn"
+        };
+
+        format!(
+            " Tic tac toe ({:?}) | {} | Score {}:{}:{} (wins:losses:draws) | {} ",
+            self.variant,
+            turn,
+            game_status.score.wins,
+            game_status.score.losses,
+            game_status.score.draws,
+            if game_status.connected {
+                "Connected"
+            } else {
+                "Disconnected"
+            }
+        )
+    }
+
+    fn get_cell_color(&self, row: usize, col: usize) -> u8 {
+        if self.cursor == (row, col) {
+            PLAYBOARD_GRID_CURSOR_COLOR
+        } else if self.selected_cell == Some((row, col)) {
+            PLAYBOARD_GRID_SELECTED_COLOR
+        } else if (row * PLAYBOARD_ROW_COL_SIZE + col) % 2 == 1 {
+            PLAYBOARD_GRID_COLOR2
+        } else {
+            PLAYBOARD_GRID_COLOR1
+        }
+    }
+
+    fn draw(
+        &self,
+        stdout: &mut Stdout,
+        host_type_object: &dyn HostTypeObject,
+        game_status: GameStatus,
+    ) -> std::io::Result<()> {
+        let (width, height) = terminal::size()?;
+
+        let arc_session = host_type_object.get_session();
+        let guard_session = arc_session.lock().unwrap();
+        let playboard = guard_session.get_playboard();
+
+        queue!(stdout, Clear(ClearType::All))?;
+
+        // Status bar.
+        let status_text = self.get_status_text(game_status, playboard.is_collapse_pending());
+        queue!(
+            stdout,
+            MoveTo(0, STATUS_BAR_ROW),
+            SetAttribute(Attribute::Reverse),
+            Print(format!("{:<width$}", status_text, width = width as usize)),
+            SetAttribute(Attribute::Reset)
+        )?;
+
+        // Playboard.
+        for row in 0..PLAYBOARD_ROW_COL_SIZE {
+            for col in 0..PLAYBOARD_ROW_COL_SIZE {
+                let text_lines = playboard.get_cell_text_lines(row, col);
+                // Vertically center text lines inside the cell.
+                let first_text_line = PLAYBOARD_GRID_HEIGHT.saturating_sub(text_lines.len()) / 2;
+
+                queue!(
+                    stdout,
+                    SetForegroundColor(Color::AnsiValue(PLAYBOARD_COLOR_TEXT)),
+                    SetBackgroundColor(Color::AnsiValue(self.get_cell_color(row, col)))
+                )?;
+
+                for line in 0..PLAYBOARD_GRID_HEIGHT {
+                    let text = line
+                        .checked_sub(first_text_line)
+                        .and_then(|index| text_lines.get(index))
+                        .map_or("", |text| text.as_str());
+
+                    queue!(
+                        stdout,
+                        MoveTo(
+                            (col * PLAYBOARD_GRID_WIDTH) as u16,
+                            PLAYBOARD_TOP_ROW + (row * PLAYBOARD_GRID_HEIGHT + line) as u16
+                        ),
+                        Print(format!("{:^width$}", text, width = PLAYBOARD_GRID_WIDTH))
+                    )?;
+                }
+            }
+        }
+
+        queue!(stdout, ResetColor)?;
+
+        let playboard_bottom_row =
+            PLAYBOARD_TOP_ROW + (PLAYBOARD_ROW_COL_SIZE * PLAYBOARD_GRID_HEIGHT) as u16;
+
+        for (index, note) in playboard.get_board_notes().iter().enumerate() {
+            queue!(
+                stdout,
+                MoveTo(0, playboard_bottom_row + 1 + index as u16),
+                Print(note)
+            )?;
+        }
+
+        drop(guard_session);
+
+        // Chat pane, newest messages at the bottom.
+        let chat_left_col = (PLAYBOARD_ROW_COL_SIZE * PLAYBOARD_GRID_WIDTH) as u16 + CHAT_PANE_GAP;
+        let chat_width = width.saturating_sub(chat_left_col) as usize;
+        let chat_height = height.saturating_sub(PLAYBOARD_TOP_ROW + 2) as usize;
+
+        if chat_width > 0 {
+            let chat_lines = self
+                .chat
+                .iter()
+                .flat_map(|line| wrap_line(line, chat_width))
+                .collect::<Vec<String>>();
+
+            let visible_chat_lines = &chat_lines[chat_lines.len().saturating_sub(chat_height)..];
+
+            for (index, line) in visible_chat_lines.iter().enumerate() {
+                queue!(
+                    stdout,
+                    MoveTo(chat_left_col, PLAYBOARD_TOP_ROW + index as u16),
+                    Print(line)
+                )?;
+            }
+        }
+
+        // Hints and input line.
+        queue!(
+            stdout,
+            MoveTo(0, height.saturating_sub(2)),
+            SetAttribute(Attribute::Dim),
+            Print(wrap_line(KEYS_HINT, width as usize).remove(0)),
+            SetAttribute(Attribute::Reset),
+            MoveTo(0, height.saturating_sub(1)),
+            Print(format!("> {}", self.input))
+        )?;
+
+        stdout.flush()
+    }
+}
+
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let characters = line.chars().collect::<Vec<char>>();
+
+    if characters.is_empty() || width == 0 {
+        return vec![String::new()];
+    }
+
+    characters
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+pub fn run_text_user_interface(
+    host_type_object: &mut Box<dyn HostTypeObject>,
+    variant: GameVariant,
+) {
+    let (sender, receiver) = channel();
+
+    redirect_output_to_text_user_interface(sender);
+    // Colors are given by interface itself, escape sequences would break layout.
+    colored::control::set_override(false);
+
+    match TerminalGuard::new() {
+        Ok(_terminal_guard) => {
+            print_game_welcome_message();
+
+            TextUserInterface::new(variant, receiver).run(host_type_object);
+        }
+        Err(err) => {
+            restore_output();
+            print_output(format!(
+                "Failed to start text user interface: {}. Use -l for line based interface.",
+                err
+            ));
+        }
+    }
+
+    restore_output();
+    colored::control::unset_override();
+}
@@ -0,0 +1,634 @@
+//! Player of the classic variant searching forced wins by threats.
+//!
+//! Threats are recognised on lines of [`ThreatBoard`], a k-in-a-row board of any size,
+//! so the same search serves the 3x3 playboard and gomoku sized boards given by
+//! [`BoardOptions`]. A four is a
+//! segment of k cells of one line missing a single symbol of the player, a three is
+//! missing two. Gaps inside the segment make them broken, empty cells on both ends
+//! make them open. Two fours missing different cells are a double threat, the
+//! oponent blocks only one of them.
+//!
+//! Threat-space search plays continuous fours, each answered by the only reply of the
+//! oponent, until a double threat wins. When no forced win is found the move is chosen
+//! by heuristic evaluation of segments of both players, avoiding moves after which the
+//! oponent has a forced win.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use crate::ai_player::{play_move, AiPlayer};
+use crate::game_options::{GameVariant, StartOrder};
+use crate::opening_book::get_book_move;
+use crate::playboard::{PlayBoardGridOptions, Playboard};
+use crate::playboard_variant::decode_cells;
+use crate::settings::ai_options::{
+    BOARD_DEFAULT_WIN_LENGTH, BOARD_MAX_SIZE, THREAT_PLAYER_NAME, THREAT_SEARCH_DEPTH,
+};
+use crate::settings::commands::PLACE_ON_PLAYBOARD_COMMAND;
+use crate::settings::playboard_options::PLAYBOARD_ROW_COL_SIZE;
+
+// Candidate moves are only empty cells this close to some symbol.
+const CANDIDATE_DISTANCE: usize = 2;
+// Value of a segment with one more symbol grows this many times.
+const SEGMENT_WEIGHT_BASE: i64 = 10;
+
+// Directions of lines as (row, col) steps: row, col, diagonal and anti-diagonal.
+const LINE_DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Board larger than the playboard, e.g. `size=15 win=5` for gomoku.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BoardOptions {
+    pub size: usize,
+    /// Symbols of one player in a line which win.
+    pub win_length: usize,
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for BoardOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "size={} win={}", self.size, self.win_length)
+    }
+}
+
+// Win length is the gomoku one when not given, at most the size.
+impl FromStr for BoardOptions {
+    type Err = String;
+
+    fn from_str(options: &str) -> Result<BoardOptions, Self::Err> {
+        let mut size = None;
+        let mut win_length = None;
+
+        for option in options.split_whitespace() {
+            let invalid_option =
+                || format!("Invalid board option {}, use e.g. size=15 win=5.", option);
+            let (name, value) = option.split_once('=').ok_or_else(invalid_option)?;
+            let value = value.parse::<usize>().map_err(|_| invalid_option())?;
+
+            match name {
+                "size" => size = Some(value),
+                "win" => win_length = Some(value),
+                _ => return Err(invalid_option()),
+            }
+        }
+
+        let size = size.ok_or("Board size is missing, use e.g. size=15 win=5.")?;
+        let win_length = win_length.unwrap_or(BOARD_DEFAULT_WIN_LENGTH.min(size));
+
+        if !(PLAYBOARD_ROW_COL_SIZE..=BOARD_MAX_SIZE).contains(&size)
+            || !(PLAYBOARD_ROW_COL_SIZE..=size).contains(&win_length)
+        {
+            return Err(format!(
+                "Board size must be from {} to {} and win length from {} to the size.",
+                PLAYBOARD_ROW_COL_SIZE, BOARD_MAX_SIZE, PLAYBOARD_ROW_COL_SIZE
+            ));
+        }
+
+        Ok(BoardOptions { size, win_length })
+    }
+}
+
+/// Move placing the symbol on the cell given as (row, col) from 0, players index from 1.
+pub fn format_place_command(row: usize, col: usize) -> String {
+    format!("{}({}, {})", PLACE_ON_PLAYBOARD_COMMAND, row + 1, col + 1)
+}
+
+/// Cell of the place move as (row, col) from 0, None for other moves.
+pub fn decode_place_command(game_move: &str) -> Option<(usize, usize)> {
+    let data = game_move.strip_prefix(PLACE_ON_PLAYBOARD_COMMAND)?;
+
+    match decode_cells(data).first() {
+        Some(&(row, col)) if row > 0 && col > 0 => Some((row - 1, col - 1)),
+        _ => None,
+    }
+}
+
+/// Kinds of threat patterns.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ThreatKind {
+    /// k-1 symbols in a segment of k cells, wins by the missing one.
+    Four,
+    /// k-1 symbols in a row with both ends empty, wins by either end.
+    OpenFour,
+    /// k-2 symbols in a row with both ends empty, makes an open four.
+    OpenThree,
+    /// Open three with a gap inside.
+    BrokenThree,
+}
+
+/// Threat of one player.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Threat {
+    pub kind: ThreatKind,
+    /// Empty cells of the pattern as (row, col) from 0, the oponent defends on them.
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Square board on which k symbols of one player in a line win.
+#[derive(Clone, Debug)]
+pub struct ThreatBoard {
+    size: usize,
+    win_length: usize,
+    cells: Vec<Option<StartOrder>>,
+    // Cell indexes of all segments of win_length cells.
+    segments: Vec<Vec<usize>>,
+    // Cell indexes of all segments one cell longer.
+    long_segments: Vec<Vec<usize>>,
+}
+
+impl ThreatBoard {
+    pub fn new(size: usize, win_length: usize) -> Self {
+        Self {
+            size,
+            win_length,
+            cells: vec![None; size * size],
+            segments: Self::get_segments(size, win_length),
+            long_segments: Self::get_segments(size, win_length + 1),
+        }
+    }
+
+    /// Symbols of the classic playboard, where a full line wins.
+    pub fn from_playboard(playboard: &Playboard) -> Self {
+        let mut board = Self::new(PLAYBOARD_ROW_COL_SIZE, PLAYBOARD_ROW_COL_SIZE);
+
+        for (cell, grid_option) in board.cells.iter_mut().zip(playboard.get_grid().iter()) {
+            *cell = match grid_option {
+                PlayBoardGridOptions::X => Some(StartOrder::First),
+                PlayBoardGridOptions::O => Some(StartOrder::Second),
+                _ => None,
+            };
+        }
+
+        board
+    }
+
+    /// Board after the moves, places alternate starting with the first player and
+    /// invalid ones are skipped. Returns the player on turn too.
+    pub fn replay(options: BoardOptions, moves: &[String]) -> (Self, StartOrder) {
+        let mut board = Self::new(options.size, options.win_length);
+        let mut on_turn = StartOrder::First;
+
+        for game_move in moves {
+            if let Some((row, col)) = decode_place_command(game_move) {
+                if board.place(row, col, on_turn) {
+                    on_turn = on_turn.oponent();
+                }
+            }
+        }
+
+        (board, on_turn)
+    }
+
+    /// Board of given rows, `x` for the first player, `o` for the second one and any
+    /// other character for an empty cell. None when rows do not form a square.
+    pub fn from_rows(rows: &[&str], win_length: usize) -> Option<Self> {
+        let mut board = Self::new(rows.len(), win_length);
+
+        for (row, symbols) in rows.iter().enumerate() {
+            if symbols.chars().count() != rows.len() {
+                return None;
+            }
+
+            for (col, symbol) in symbols.chars().enumerate() {
+                board.cells[row * rows.len() + col] = match symbol.to_ascii_lowercase() {
+                    'x' => Some(StartOrder::First),
+                    'o' => Some(StartOrder::Second),
+                    _ => None,
+                };
+            }
+        }
+
+        Some(board)
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get_win_length(&self) -> usize {
+        self.win_length
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<StartOrder> {
+        self.cells[row * self.size + col]
+    }
+
+    /// Returns false when the cell is out of the board or taken.
+    pub fn place(&mut self, row: usize, col: usize, player: StartOrder) -> bool {
+        if row >= self.size || col >= self.size || self.get(row, col).is_some() {
+            return false;
+        }
+
+        self.cells[row * self.size + col] = Some(player);
+
+        true
+    }
+
+    /// Whether the symbol on the cell is part of a winning line, cheaper than
+    /// [`Self::is_win`] when only the last move may have won.
+    pub fn completes_line(&self, row: usize, col: usize) -> bool {
+        let player = match self.get(row, col) {
+            Some(player) => player,
+            None => return false,
+        };
+
+        LINE_DIRECTIONS.iter().any(|&(row_step, col_step)| {
+            let count_symbols = |sign: isize| {
+                (1..self.win_length as isize)
+                    .map(|index| {
+                        (
+                            row as isize + sign * row_step * index,
+                            col as isize + sign * col_step * index,
+                        )
+                    })
+                    .take_while(|&(row, col)| {
+                        (0..self.size as isize).contains(&row)
+                            && (0..self.size as isize).contains(&col)
+                            && self.get(row as usize, col as usize) == Some(player)
+                    })
+                    .count()
+            };
+
+            1 + count_symbols(1) + count_symbols(-1) >= self.win_length
+        })
+    }
+
+    /// Empty cells as (row, col) from 0.
+    pub fn get_free_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.cells.len())
+            .filter(|&cell| self.cells[cell].is_none())
+            .map(|cell| self.to_row_col(cell))
+            .collect()
+    }
+
+    /// Empty cells near symbols as (row, col) from 0, the center on empty board. Moves
+    /// far from all symbols are rarely worth searching.
+    pub fn get_candidate_moves(&self) -> Vec<(usize, usize)> {
+        self.get_candidate_cells()
+            .into_iter()
+            .map(|cell| self.to_row_col(cell))
+            .collect()
+    }
+
+    pub fn is_win(&self, player: StartOrder) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| segment.iter().all(|&cell| self.cells[cell] == Some(player)))
+    }
+
+    /// Empty cells which win for the player, more than one is a double threat.
+    pub fn get_winning_cells(&self, player: StartOrder) -> Vec<(usize, usize)> {
+        self.winning_cells(player)
+            .into_iter()
+            .map(|cell| self.to_row_col(cell))
+            .collect()
+    }
+
+    pub fn has_double_threat(&self, player: StartOrder) -> bool {
+        self.winning_cells(player).len() > 1
+    }
+
+    /// All threats of the player, each pattern once.
+    pub fn get_threats(&self, player: StartOrder) -> Vec<Threat> {
+        let mut seen = HashSet::new();
+        let mut threats = Vec::new();
+
+        let fours = self.segments.iter().filter_map(|segment| {
+            let empty = self.get_empty_cells(segment, player)?;
+            (empty.len() == 1).then_some((ThreatKind::Four, segment, empty))
+        });
+        let open = self.long_segments.iter().filter_map(|segment| {
+            let (first, last) = (segment[0], segment[segment.len() - 1]);
+            if self.cells[first].is_some() || self.cells[last].is_some() {
+                return None;
+            }
+
+            let inner = &segment[1..segment.len() - 1];
+            let gaps = self.get_empty_cells(inner, player)?;
+            let kind = match gaps[..] {
+                [] => ThreatKind::OpenFour,
+                [gap] if gap == inner[0] || gap == inner[inner.len() - 1] => ThreatKind::OpenThree,
+                [_] => ThreatKind::BrokenThree,
+                _ => return None,
+            };
+
+            Some((kind, segment, [vec![first], gaps, vec![last]].concat()))
+        });
+
+        for (kind, segment, empty) in fours.chain(open) {
+            // Longer segments find the same pattern more times.
+            let symbols: Vec<usize> = segment
+                .iter()
+                .copied()
+                .filter(|&cell| self.cells[cell].is_some())
+                .collect();
+
+            if seen.insert((kind, symbols)) {
+                threats.push(Threat {
+                    kind,
+                    cells: empty.iter().map(|&cell| self.to_row_col(cell)).collect(),
+                });
+            }
+        }
+
+        threats
+    }
+
+    /// Moves of a forced win of the player on turn, alternating with the only replies
+    /// of the oponent, as (row, col) from 0. The last move wins or makes a double
+    /// threat. Looks at most `max_depth` fours ahead.
+    pub fn find_forced_win(
+        &mut self,
+        player: StartOrder,
+        max_depth: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        self.search_fours(player, max_depth)
+            .map(|line| line.into_iter().map(|cell| self.to_row_col(cell)).collect())
+    }
+
+    /// Heuristic value of the position for the player, segments free of the oponent's
+    /// symbols count more with every symbol in them.
+    pub fn evaluate(&self, player: StartOrder) -> i64 {
+        self.segments
+            .iter()
+            .map(|segment| {
+                let mut owners = segment.iter().filter_map(|&cell| self.cells[cell]);
+                match owners.next() {
+                    None => 0,
+                    Some(owner) if owners.all(|other| other == owner) => {
+                        let symbols = segment.iter().filter(|&&cell| self.cells[cell].is_some());
+                        // Long segments of large win length saturate.
+                        let value = SEGMENT_WEIGHT_BASE.saturating_pow(symbols.count() as u32);
+                        if owner == player {
+                            value
+                        } else {
+                            -value
+                        }
+                    }
+                    Some(_) => 0,
+                }
+            })
+            .fold(0, i64::saturating_add)
+            // Symmetric for both players.
+            .max(-i64::MAX)
+    }
+
+    /// Best move of the player on turn as (row, col) from 0, None on full board.
+    pub fn choose_move(&mut self, player: StartOrder, max_depth: usize) -> Option<(usize, usize)> {
+        let cell = match self.search_fours(player, max_depth) {
+            Some(line) => line[0],
+            None => match self.winning_cells(player.oponent()).first() {
+                Some(&block) => block,
+                None => self.choose_quiet_move(player, max_depth)?,
+            },
+        };
+
+        Some(self.to_row_col(cell))
+    }
+
+    // Prefers moves after which the oponent has no forced win, then higher evaluation.
+    fn choose_quiet_move(&mut self, player: StartOrder, max_depth: usize) -> Option<usize> {
+        let mut best: Option<(bool, i64, usize)> = None;
+
+        for cell in self.get_candidate_cells() {
+            self.cells[cell] = Some(player);
+            let safe = self.search_fours(player.oponent(), max_depth).is_none();
+            let value = self.evaluate(player);
+            self.cells[cell] = None;
+
+            if best.is_none_or(|(best_safe, best_value, _)| (safe, value) > (best_safe, best_value))
+            {
+                best = Some((safe, value, cell));
+            }
+        }
+
+        best.map(|(_, _, cell)| cell)
+    }
+
+    fn search_fours(&mut self, attacker: StartOrder, depth: usize) -> Option<Vec<usize>> {
+        if let Some(&cell) = self.winning_cells(attacker).first() {
+            return Some(vec![cell]);
+        }
+
+        let defender = attacker.oponent();
+        let blocks = self.winning_cells(defender);
+        // Fours of the oponent have to be blocked first, two of them can not be.
+        if depth == 0 || blocks.len() > 1 {
+            return None;
+        }
+
+        for cell in self.get_four_cells(attacker) {
+            if blocks.first().is_some_and(|&block| block != cell) {
+                continue;
+            }
+
+            self.cells[cell] = Some(attacker);
+            let line = match self.winning_cells(attacker)[..] {
+                [] => None,
+                [gain] => {
+                    self.cells[gain] = Some(defender);
+                    // Block may win for the defender or make their four, which the next
+                    // four of the attacker has to block then.
+                    let line = if self.is_win(defender) {
+                        None
+                    } else {
+                        self.search_fours(attacker, depth - 1)
+                            .map(|rest| [vec![cell, gain], rest].concat())
+                    };
+                    self.cells[gain] = None;
+                    line
+                }
+                _ => Some(vec![cell]),
+            };
+            self.cells[cell] = None;
+
+            if line.is_some() {
+                return line;
+            }
+        }
+
+        None
+    }
+
+    fn winning_cells(&self, player: StartOrder) -> Vec<usize> {
+        let mut cells: Vec<usize> = self
+            .segments
+            .iter()
+            .filter_map(|segment| match self.get_empty_cells(segment, player)?[..] {
+                [cell] => Some(cell),
+                _ => None,
+            })
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+
+        cells
+    }
+
+    // Empty cells which make a four of the player.
+    fn get_four_cells(&self, player: StartOrder) -> Vec<usize> {
+        let mut cells: Vec<usize> = self
+            .segments
+            .iter()
+            .filter_map(|segment| {
+                let empty = self.get_empty_cells(segment, player)?;
+                (empty.len() == 2).then_some(empty)
+            })
+            .flatten()
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+
+        cells
+    }
+
+    // None when the oponent has a symbol in the cells.
+    fn get_empty_cells(&self, cells: &[usize], player: StartOrder) -> Option<Vec<usize>> {
+        let mut empty = Vec::new();
+
+        for &cell in cells {
+            match self.cells[cell] {
+                None => empty.push(cell),
+                Some(owner) if owner != player => return None,
+                Some(_) => {}
+            }
+        }
+
+        Some(empty)
+    }
+
+    // Empty cells near symbols, the center on empty board.
+    fn get_candidate_cells(&self) -> Vec<usize> {
+        let taken: Vec<(usize, usize)> = (0..self.cells.len())
+            .filter(|&cell| self.cells[cell].is_some())
+            .map(|cell| self.to_row_col(cell))
+            .collect();
+
+        if taken.is_empty() {
+            return vec![self.size / 2 * self.size + self.size / 2];
+        }
+
+        (0..self.cells.len())
+            .filter(|&cell| self.cells[cell].is_none())
+            .filter(|&cell| {
+                let (row, col) = self.to_row_col(cell);
+                taken.iter().any(|&(taken_row, taken_col)| {
+                    row.abs_diff(taken_row) <= CANDIDATE_DISTANCE
+                        && col.abs_diff(taken_col) <= CANDIDATE_DISTANCE
+                })
+            })
+            .collect()
+    }
+
+    fn to_row_col(&self, cell: usize) -> (usize, usize) {
+        (cell / self.size, cell % self.size)
+    }
+
+    fn get_segments(size: usize, length: usize) -> Vec<Vec<usize>> {
+        let mut segments = Vec::new();
+
+        for row in 0..size as isize {
+            for col in 0..size as isize {
+                for (row_step, col_step) in LINE_DIRECTIONS {
+                    let segment: Vec<usize> = (0..length as isize)
+                        .map(|index| (row + row_step * index, col + col_step * index))
+                        .take_while(|&(row, col)| {
+                            (0..size as isize).contains(&row) && (0..size as isize).contains(&col)
+                        })
+                        .map(|(row, col)| row as usize * size + col as usize)
+                        .collect();
+
+                    if segment.len() == length {
+                        segments.push(segment);
+                    }
+                }
+            }
+        }
+
+        segments
+    }
+}
+
+/// Plays the classic variant by threat-space search, on the playboard or on a larger
+/// board given by [`BoardOptions`].
+pub struct ThreatPlayer {
+    max_depth: usize,
+    // Playboard of the game when None.
+    board: Option<BoardOptions>,
+}
+
+impl ThreatPlayer {
+    pub fn new() -> Self {
+        Self {
+            max_depth: THREAT_SEARCH_DEPTH,
+            board: None,
+        }
+    }
+
+    /// Player of the larger board, moves are `/place (row, col)` indexed from 1 as on the
+    /// playboard.
+    pub fn for_board(board: BoardOptions) -> Self {
+        Self {
+            max_depth: THREAT_SEARCH_DEPTH,
+            board: Some(board),
+        }
+    }
+
+    fn replay_playboard(moves: &[String]) -> (ThreatBoard, StartOrder) {
+        let mut playboard = Playboard::new();
+        let mut on_turn = StartOrder::First;
+
+        for game_move in moves {
+            if play_move(&mut playboard, game_move, on_turn).is_ok() {
+                on_turn = on_turn.oponent();
+            }
+        }
+
+        (ThreatBoard::from_playboard(&playboard), on_turn)
+    }
+}
+
+impl Default for ThreatPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AiPlayer for ThreatPlayer {
+    fn get_name(&self) -> String {
+        match self.board {
+            Some(board) => format!("{} {}", THREAT_PLAYER_NAME, board),
+            None => THREAT_PLAYER_NAME.to_string(),
+        }
+    }
+
+    fn new_game(&mut self, variant: GameVariant, _start_order: StartOrder) -> io::Result<()> {
+        if variant != GameVariant::Classic {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} player plays only classic variant", THREAT_PLAYER_NAME),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String> {
+        let (mut board, on_turn) = match self.board {
+            Some(board) => ThreatBoard::replay(board, moves),
+            None => {
+                if let Some(book_move) = get_book_move(GameVariant::Classic, moves) {
+                    return Ok(book_move.get_command());
+                }
+
+                Self::replay_playboard(moves)
+            }
+        };
+
+        board
+            .choose_move(on_turn, self.max_depth)
+            .map(|(row, col)| format_place_command(row, col))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no valid move left"))
+    }
+}
@@ -0,0 +1,459 @@
+//! Pairings and standings of tournaments played on the hub.
+//!
+//! Round-robin pairs every two players once (circle method), players are ranked by
+//! match points, then by game points and by Sonneborn-Berger score. Knockout seeds
+//! players by their registration order, top seeds get byes when the number of players
+//! is not a power of two and winners advance until one is left.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game_options::GameVariant;
+
+/// How players are paired.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TournamentFormat {
+    /// Everybody plays everybody once.
+    RoundRobin,
+    /// Loser of a match leaves the tournament.
+    Knockout,
+}
+
+// Written in the form accepted by from_str.
+impl fmt::Display for TournamentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TournamentFormat::RoundRobin => write!(f, "round-robin"),
+            TournamentFormat::Knockout => write!(f, "knockout"),
+        }
+    }
+}
+
+// Strum macros not used due to case insensitiveness.
+impl FromStr for TournamentFormat {
+    type Err = ();
+
+    fn from_str(format: &str) -> Result<TournamentFormat, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "round-robin" | "roundrobin" => Ok(TournamentFormat::RoundRobin),
+            "knockout" | "single-elimination" => Ok(TournamentFormat::Knockout),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Tournament settings of the hub.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TournamentOptions {
+    pub format: TournamentFormat,
+    /// Tournament starts when this many players registered.
+    pub players: usize,
+    /// Games of one match, the player who wins most of them wins the match.
+    pub best_of: u32,
+    pub variant: GameVariant,
+}
+
+/// Players meeting in one round, players are indexes of registration order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Pairing {
+    Match([usize; 2]),
+    /// Player has nobody to play with in this round.
+    Bye(usize),
+}
+
+/// Result of a match, player indexes as in the pairing.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MatchResult {
+    pub players: [usize; 2],
+    pub wins: [u32; 2],
+    pub draws: u32,
+    /// Player who lost the match without finishing it, e.g. by disconnecting.
+    pub forfeit: Option<usize>,
+    /// Knockout match drawn by games is decided for the better seed.
+    pub decided_by_seed: bool,
+    /// Neither player finished the match, e.g. when it failed on the hub, both lost it.
+    pub double_forfeit: bool,
+}
+
+impl MatchResult {
+    pub fn new(players: [usize; 2]) -> Self {
+        Self {
+            players,
+            ..Self::default()
+        }
+    }
+
+    pub fn get_winner(&self) -> Option<usize> {
+        if self.double_forfeit {
+            return None;
+        }
+
+        if let Some(forfeit) = self.forfeit {
+            return self.players.into_iter().find(|&player| player != forfeit);
+        }
+
+        if self.wins[0] > self.wins[1] {
+            Some(self.players[0])
+        } else if self.wins[1] > self.wins[0] {
+            Some(self.players[1])
+        } else if self.decided_by_seed {
+            self.players.into_iter().min()
+        } else {
+            None
+        }
+    }
+
+    // Match points of the player, win 1 and draw 1/2.
+    fn get_points(&self, player: usize) -> f64 {
+        if self.double_forfeit {
+            return 0.0;
+        }
+
+        match self.get_winner() {
+            Some(winner) if winner == player => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        }
+    }
+
+    // Game points of the player, win 1 and draw 1/2.
+    fn get_game_points(&self, player: usize) -> f64 {
+        let index = if self.players[0] == player { 0 } else { 1 };
+
+        self.wins[index] as f64 + self.draws as f64 / 2.0
+    }
+
+    fn get_oponent(&self, player: usize) -> usize {
+        if self.players[0] == player {
+            self.players[1]
+        } else {
+            self.players[0]
+        }
+    }
+}
+
+/// One round, results are kept in order of pairings.
+#[derive(Clone, Debug)]
+pub struct TournamentRound {
+    /// Number of the round from 1.
+    pub number: u32,
+    pub pairings: Vec<Pairing>,
+    pub results: Vec<Option<MatchResult>>,
+}
+
+impl TournamentRound {
+    fn new(number: u32, pairings: Vec<Pairing>) -> Self {
+        Self {
+            number,
+            results: vec![None; pairings.len()],
+            pairings,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.pairings
+            .iter()
+            .zip(&self.results)
+            .all(|(pairing, result)| matches!(pairing, Pairing::Bye(_)) || result.is_some())
+    }
+
+    // Players advancing to the next knockout round in bracket order, slot of the match
+    // lost by both players stays empty.
+    fn get_advancing(&self) -> Vec<Option<usize>> {
+        self.pairings
+            .iter()
+            .zip(&self.results)
+            .map(|(pairing, result)| match pairing {
+                Pairing::Bye(player) => Some(*player),
+                Pairing::Match(_) => result.as_ref()?.get_winner(),
+            })
+            .collect()
+    }
+}
+
+/// Ranking of a player in round-robin tournament.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub player: usize,
+    pub matches: u32,
+    pub points: f64,
+    pub game_points: f64,
+    /// Sum of points of beaten oponents and half of points of drawn ones.
+    pub sonneborn_berger: f64,
+}
+
+pub struct Tournament {
+    format: TournamentFormat,
+    names: Vec<String>,
+    rounds: Vec<TournamentRound>,
+}
+
+// Circle method, the first player stays and others rotate around it.
+fn create_round_robin_schedule(players: usize) -> Vec<Vec<Pairing>> {
+    let mut circle: Vec<Option<usize>> = (0..players).map(Some).collect();
+    if players % 2 == 1 {
+        circle.push(None);
+    }
+
+    let size = circle.len();
+    let mut schedule = Vec::new();
+
+    for _ in 1..size {
+        let pairings = (0..size / 2)
+            .map(|index| match (circle[index], circle[size - 1 - index]) {
+                (Some(first), Some(second)) => Pairing::Match([first, second]),
+                (Some(player), None) | (None, Some(player)) => Pairing::Bye(player),
+                (None, None) => unreachable!("Only one place of the circle is empty."),
+            })
+            .collect();
+        schedule.push(pairings);
+
+        circle[1..].rotate_right(1);
+    }
+
+    schedule
+}
+
+// Seeds in bracket order, so the two best seeds can meet only in the final.
+fn get_bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+
+    while order.len() < size {
+        let next_size = order.len() * 2;
+        order = order
+            .iter()
+            .flat_map(|&seed| [seed, next_size - 1 - seed])
+            .collect();
+    }
+
+    order
+}
+
+fn pair_knockout_players(players: &[Option<usize>]) -> Vec<Pairing> {
+    players
+        .chunks(2)
+        .filter_map(|pair| match pair {
+            [Some(first), Some(second)] => Some(Pairing::Match([*first, *second])),
+            [Some(player), None] | [None, Some(player)] | [Some(player)] => {
+                Some(Pairing::Bye(*player))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+impl Tournament {
+    /// Players are seeded in the given order.
+    pub fn new(format: TournamentFormat, names: Vec<String>) -> Self {
+        Self {
+            format,
+            names,
+            rounds: Vec::new(),
+        }
+    }
+
+    pub fn get_format(&self) -> TournamentFormat {
+        self.format
+    }
+
+    pub fn get_name(&self, player: usize) -> &str {
+        &self.names[player]
+    }
+
+    pub fn get_rounds(&self) -> &[TournamentRound] {
+        &self.rounds
+    }
+
+    /// Pairs players of the next round, `None` when the tournament is over.
+    pub fn start_round(&mut self) -> Option<&TournamentRound> {
+        if self.rounds.last().is_some_and(|round| !round.is_finished()) {
+            return None;
+        }
+
+        let pairings = match self.format {
+            TournamentFormat::RoundRobin => create_round_robin_schedule(self.names.len())
+                .into_iter()
+                .nth(self.rounds.len())?,
+            TournamentFormat::Knockout => {
+                let players: Vec<Option<usize>> = match self.rounds.last() {
+                    None => {
+                        let size = self.names.len().next_power_of_two();
+                        get_bracket_order(size)
+                            .into_iter()
+                            .map(|seed| (seed < self.names.len()).then_some(seed))
+                            .collect()
+                    }
+                    Some(round) => round.get_advancing(),
+                };
+
+                if players.iter().flatten().count() < 2 {
+                    return None;
+                }
+
+                pair_knockout_players(&players)
+            }
+        };
+
+        self.rounds
+            .push(TournamentRound::new(self.rounds.len() as u32 + 1, pairings));
+        self.rounds.last()
+    }
+
+    /// Records result of the match of the current round.
+    pub fn record_result(&mut self, result: MatchResult) {
+        if let Some(round) = self.rounds.last_mut() {
+            let index = round
+                .pairings
+                .iter()
+                .position(|pairing| *pairing == Pairing::Match(result.players));
+
+            if let Some(index) = index {
+                round.results[index] = Some(result);
+            }
+        }
+    }
+
+    fn get_results(&self) -> impl Iterator<Item = &MatchResult> {
+        self.rounds
+            .iter()
+            .flat_map(|round| round.results.iter().flatten())
+    }
+
+    /// Players ordered by points and tiebreaks, the best first.
+    pub fn get_standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = (0..self.names.len())
+            .map(|player| Standing {
+                player,
+                matches: 0,
+                points: 0.0,
+                game_points: 0.0,
+                sonneborn_berger: 0.0,
+            })
+            .collect();
+
+        for result in self.get_results() {
+            for player in result.players {
+                let standing = &mut standings[player];
+                standing.matches += 1;
+                standing.points += result.get_points(player);
+                standing.game_points += result.get_game_points(player);
+            }
+        }
+
+        let points: Vec<f64> = standings.iter().map(|standing| standing.points).collect();
+
+        for result in self.get_results() {
+            for player in result.players {
+                standings[player].sonneborn_berger +=
+                    result.get_points(player) * points[result.get_oponent(player)];
+            }
+        }
+
+        standings.sort_by(|first, second| {
+            second
+                .points
+                .total_cmp(&first.points)
+                .then(second.game_points.total_cmp(&first.game_points))
+                .then(second.sonneborn_berger.total_cmp(&first.sonneborn_berger))
+                .then(first.player.cmp(&second.player))
+        });
+
+        standings
+    }
+
+    /// Winner of the finished tournament.
+    pub fn get_winner(&self) -> Option<usize> {
+        match self.format {
+            TournamentFormat::RoundRobin => {
+                self.get_standings().first().map(|standing| standing.player)
+            }
+            TournamentFormat::Knockout => {
+                let advancing: Vec<usize> = self
+                    .rounds
+                    .last()?
+                    .get_advancing()
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+                match advancing[..] {
+                    [winner] => Some(winner),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    pub fn format_result(&self, result: &MatchResult) -> String {
+        let mut formatted = format!(
+            "{} {}-{} {}",
+            self.names[result.players[0]],
+            result.wins[0],
+            result.wins[1],
+            self.names[result.players[1]]
+        );
+
+        if result.draws > 0 {
+            formatted += &format!(" ({} draws)", result.draws);
+        }
+
+        if result.double_forfeit {
+            formatted += ", both forfeited";
+        } else if let Some(forfeit) = result.forfeit {
+            formatted += &format!(", {} forfeited", self.names[forfeit]);
+        } else if result.decided_by_seed {
+            formatted += ", decided by seed";
+        }
+
+        formatted
+    }
+
+    pub fn format_pairing(&self, pairing: &Pairing) -> String {
+        match pairing {
+            Pairing::Match([first, second]) => {
+                format!("{} vs {}", self.names[*first], self.names[*second])
+            }
+            Pairing::Bye(player) => format!("{} has a bye", self.names[*player]),
+        }
+    }
+
+    /// Standings table of round-robin or bracket of knockout, one line each.
+    pub fn format_summary(&self) -> Vec<String> {
+        match self.format {
+            TournamentFormat::RoundRobin => self
+                .get_standings()
+                .iter()
+                .enumerate()
+                .map(|(rank, standing)| {
+                    format!(
+                        "{}. {}: {} points from {} matches, game points {}, Sonneborn-Berger {}",
+                        rank + 1,
+                        self.names[standing.player],
+                        standing.points,
+                        standing.matches,
+                        standing.game_points,
+                        standing.sonneborn_berger
+                    )
+                })
+                .collect(),
+            TournamentFormat::Knockout => self
+                .rounds
+                .iter()
+                .map(|round| {
+                    let matches = round
+                        .pairings
+                        .iter()
+                        .zip(&round.results)
+                        .map(|(pairing, result)| match result {
+                            Some(result) => self.format_result(result),
+                            None => self.format_pairing(pairing),
+                        })
+                        .collect::<Vec<String>>()
+                        .join("; ");
+
+                    format!("Round {}: {}", round.number, matches)
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+// Browser client of the tic tac toe game. Speaks the same line protocol as the
+// terminal client, every WebSocket message is one line.
+"use strict";
+
+const PRIVATE_MESSAGE_COMMAND = "/pm ";
+const PLACE_ON_PLAYBOARD_COMMAND = "/place ";
+const COLLAPSE_COMMAND = "/collapse ";
+const PLAY_AGAIN_COMMAND = "/playagain";
+const AGREE_COMMAND = "/agree";
+const AUTHENTICATION_CHALLENGE = "/challenge ";
+const AUTHENTICATION_ACCEPTED = "/authenticated ";
+const AUTHENTICATION_REJECTED = "/rejected";
+
+const PLAYBOARD_ROW_COL_SIZE = 3;
+
+const variant = document.body.dataset.variant;
+const startOrder = document.body.dataset.startOrder;
+const mySymbol = startOrder === "first" ? "X" : "O";
+const oponentSymbol = startOrder === "first" ? "O" : "X";
+
+// Mirrors GameSession of the terminal player.
+const game = {
+    cells: Array(PLAYBOARD_ROW_COL_SIZE * PLAYBOARD_ROW_COL_SIZE).fill(""),
+    myTurn: startOrder === "first",
+    running: true,
+    newGameRequested: false,
+    newGameDesirable: false,
+    // Password is being asked for or verified, nothing else may be sent.
+    locked: false,
+    authenticating: false,
+    password: null,
+    clientChallenge: null,
+};
+
+const socket = new WebSocket(
+    (location.protocol === "https:" ? "wss://" : "ws://") + location.host + "/ws"
+);
+
+function log(text) {
+    const logElement = document.getElementById("log");
+    logElement.textContent += text + "\n";
+    logElement.scrollTop = logElement.scrollHeight;
+}
+
+function setStatus(text) {
+    document.getElementById("status").textContent = text;
+}
+
+function updateStatus() {
+    if (!game.running) {
+        setStatus("Game finished, ask for another one with Play again.");
+    } else if (game.myTurn) {
+        setStatus("Your turn, you play " + mySymbol + ".");
+    } else {
+        setStatus("Waiting for your oponent.");
+    }
+}
+
+function send(line) {
+    socket.send(line);
+}
+
+// Row and col index from 1, as in the terminal client.
+function parseCell(line) {
+    const match = /\(\s*(\d+)\s*,\s*(\d+)\s*\)/.exec(line);
+    if (!match) {
+        return null;
+    }
+
+    const row = Number(match[1]);
+    const col = Number(match[2]);
+    if (row < 1 || row > PLAYBOARD_ROW_COL_SIZE || col < 1 || col > PLAYBOARD_ROW_COL_SIZE) {
+        return null;
+    }
+
+    return (row - 1) * PLAYBOARD_ROW_COL_SIZE + (col - 1);
+}
+
+function getWinner() {
+    const size = PLAYBOARD_ROW_COL_SIZE;
+    const lines = [];
+
+    for (let i = 0; i < size; i++) {
+        lines.push([...Array(size).keys()].map((j) => i * size + j));
+        lines.push([...Array(size).keys()].map((j) => j * size + i));
+    }
+    lines.push([...Array(size).keys()].map((j) => j * size + j));
+    lines.push([...Array(size).keys()].map((j) => j * size + (size - 1 - j)));
+
+    for (const line of lines) {
+        const symbol = game.cells[line[0]];
+        if (symbol && line.every((index) => game.cells[index] === symbol)) {
+            return symbol;
+        }
+    }
+
+    return null;
+}
+
+function renderPlayboard() {
+    const playboard = document.getElementById("playboard");
+    playboard.replaceChildren();
+
+    game.cells.forEach((symbol, index) => {
+        const cell = document.createElement("button");
+        cell.textContent = symbol;
+        cell.onclick = () => placeMine(index);
+        playboard.appendChild(cell);
+    });
+}
+
+// Only classic variant is played on the board, other ones by commands.
+function placeOnBoard(index, symbol) {
+    if (variant !== "classic") {
+        return;
+    }
+
+    game.cells[index] = symbol;
+    renderPlayboard();
+
+    const winner = getWinner();
+    if (winner) {
+        game.running = false;
+        log(winner === mySymbol ? "You won." : "Your oponent won.");
+    } else if (game.cells.every((cell) => cell)) {
+        game.running = false;
+        log("Draw.");
+    }
+}
+
+function placeMine(index) {
+    if (!game.running || !game.myTurn || game.cells[index]) {
+        return;
+    }
+
+    const row = Math.floor(index / PLAYBOARD_ROW_COL_SIZE) + 1;
+    const col = (index % PLAYBOARD_ROW_COL_SIZE) + 1;
+    sendCommand(PLACE_ON_PLAYBOARD_COMMAND + "(" + row + ", " + col + ")");
+}
+
+function restartGame() {
+    log("New game started.");
+    game.cells.fill("");
+    game.running = true;
+    game.newGameRequested = false;
+    game.newGameDesirable = false;
+    renderPlayboard();
+}
+
+function sendCommand(line) {
+    line = line.trim();
+
+    if (game.locked) {
+        log("Enter the game password first.");
+        return;
+    }
+
+    if (line.startsWith(PLACE_ON_PLAYBOARD_COMMAND) || line.startsWith(COLLAPSE_COMMAND)) {
+        if (!game.running || !game.myTurn) {
+            log(game.running ? "It is not your turn." : "Game is not running.");
+            return;
+        }
+
+        if (line.startsWith(PLACE_ON_PLAYBOARD_COMMAND)) {
+            const index = parseCell(line);
+            if (variant === "classic" && (index === null || game.cells[index])) {
+                log("Invalid place " + line.slice(PLACE_ON_PLAYBOARD_COMMAND.length) + ".");
+                return;
+            }
+
+            log("You placed on " + line.slice(PLACE_ON_PLAYBOARD_COMMAND.length) + ".");
+            game.myTurn = false;
+            placeOnBoard(index, mySymbol);
+        }
+    } else if (line === PLAY_AGAIN_COMMAND) {
+        log("You asked for a new game.");
+        game.newGameDesirable = true;
+    } else if (line === AGREE_COMMAND) {
+        if (!game.newGameRequested) {
+            return;
+        }
+        restartGame();
+    } else if (line.startsWith(PRIVATE_MESSAGE_COMMAND)) {
+        log("You: " + line.slice(PRIVATE_MESSAGE_COMMAND.length));
+    }
+
+    send(line);
+    updateStatus();
+}
+
+function handleRemote(line) {
+    line = line.trim();
+
+    if (line.startsWith(PRIVATE_MESSAGE_COMMAND)) {
+        log("Your oponent: " + line.slice(PRIVATE_MESSAGE_COMMAND.length));
+    } else if (line.startsWith(PLACE_ON_PLAYBOARD_COMMAND)) {
+        log("Your oponent placed on " + line.slice(PLACE_ON_PLAYBOARD_COMMAND.length) + ".");
+        game.myTurn = true;
+        placeOnBoard(parseCell(line), oponentSymbol);
+    } else if (line.startsWith(COLLAPSE_COMMAND)) {
+        log("Your oponent collapsed on " + line.slice(COLLAPSE_COMMAND.length) + ".");
+    } else if (line === PLAY_AGAIN_COMMAND) {
+        log("Your oponent asks for a new game, press Agree.");
+        game.newGameRequested = true;
+    } else if (line === AGREE_COMMAND && game.newGameDesirable) {
+        restartGame();
+    }
+
+    updateStatus();
+}
+
+function encodeHex(bytes) {
+    return [...new Uint8Array(bytes)].map((byte) => byte.toString(16).padStart(2, "0")).join("");
+}
+
+async function createResponse(password, role, challenge) {
+    const encoder = new TextEncoder();
+    const key = await crypto.subtle.importKey(
+        "raw", encoder.encode(password), { name: "HMAC", hash: "SHA-256" }, false, ["sign"]
+    );
+
+    return encodeHex(await crypto.subtle.sign("HMAC", key, encoder.encode(role + challenge)));
+}
+
+// Same challenge-response as the terminal client, password is never sent.
+function authenticate(serverChallenge) {
+    if (!window.crypto || !crypto.subtle) {
+        game.locked = true;
+        document.getElementById("playboard").hidden = true;
+        setStatus("Game is protected by password, which browser can prove only on https or localhost.");
+        return;
+    }
+
+    game.locked = true;
+    document.getElementById("playboard").hidden = true;
+
+    setStatus("Game is protected by password.");
+    const form = document.getElementById("password_form");
+    form.hidden = false;
+
+    form.onsubmit = async (event) => {
+        event.preventDefault();
+        form.hidden = true;
+
+        const password = document.getElementById("password").value;
+        const clientChallenge = new Uint8Array(32);
+        crypto.getRandomValues(clientChallenge);
+
+        game.password = password;
+        game.clientChallenge = encodeHex(clientChallenge);
+        game.authenticating = true;
+
+        send("/response " + (await createResponse(password, "client", serverChallenge)) + " " + game.clientChallenge);
+    };
+}
+
+async function verifyServer(line) {
+    game.authenticating = false;
+
+    if (line === AUTHENTICATION_REJECTED) {
+        setStatus("Wrong password.");
+        return;
+    }
+
+    const expected = await createResponse(game.password, "server", game.clientChallenge);
+    if (line.slice(AUTHENTICATION_ACCEPTED.length) !== expected) {
+        setStatus("Server does not know the password.");
+        socket.close();
+        return;
+    }
+
+    game.locked = false;
+    log("Password verified.");
+    showPlayboard();
+}
+
+function showPlayboard() {
+    if (variant === "classic") {
+        document.getElementById("playboard").hidden = false;
+        renderPlayboard();
+    }
+    updateStatus();
+}
+
+socket.onopen = () => {
+    log("Connected, you play " + mySymbol + " in " + variant + " variant.");
+    if (variant !== "classic") {
+        log("Play by commands, e.g. " + PLACE_ON_PLAYBOARD_COMMAND + "(1, 1).");
+    }
+    showPlayboard();
+};
+
+socket.onmessage = (event) => {
+    for (const line of event.data.split("\n")) {
+        if (line.startsWith(AUTHENTICATION_CHALLENGE)) {
+            authenticate(line.slice(AUTHENTICATION_CHALLENGE.length));
+        } else if (game.authenticating) {
+            verifyServer(line.trim());
+        } else if (line.trim()) {
+            handleRemote(line);
+        }
+    }
+};
+
+socket.onclose = () => setStatus("Disconnected.");
+
+document.getElementById("command_form").onsubmit = (event) => {
+    event.preventDefault();
+    const input = document.getElementById("command");
+    if (input.value.trim()) {
+        sendCommand(input.value);
+    }
+    input.value = "";
+};
+
+document.getElementById("play_again").onclick = () => sendCommand(PLAY_AGAIN_COMMAND);
+document.getElementById("agree").onclick = () => sendCommand(AGREE_COMMAND);
@@ -0,0 +1,257 @@
+//! Game connections of browser players.
+//!
+//! Web listener serves small HTML/JS client and upgrades its requests to WebSocket.
+//! Every text message carries one line of the game protocol, so the WebSocket is
+//! bridged into plain line stream and the game runs as with the terminal client.
+
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{
+    duplex, split, AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite,
+    AsyncWriteExt, BufReader, DuplexStream,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::{Message, Role};
+use tokio_tungstenite::WebSocketStream;
+
+use crate::game_options::{GameVariant, StartOrder};
+use crate::host_type_objects::GameStream;
+use crate::host_type_objects_utility::accept_connection;
+use crate::settings::network_options::{
+    WEB_CLIENT_PATH, WEB_CLIENT_SCRIPT_PATH, WEB_REQUEST_MAX_SIZE, WEB_REQUEST_TIMEOUT,
+    WEB_SOCKET_PATH,
+};
+
+const INDEX_HTML: &str = include_str!("web_client/index.html");
+const GAME_CLIENT_JS: &str = include_str!("web_client/game_client.js");
+
+// Lines waiting in the bridge between WebSocket and the game.
+const BRIDGE_BUFFER_SIZE: usize = 4096;
+
+/// Browser connection ready to play, with address of the browser.
+pub(crate) type WebGameConnection = (Box<dyn GameStream>, SocketAddr);
+
+pub(crate) struct WebRequest {
+    pub method: String,
+    pub path: String,
+    // Names are lowercase.
+    pub headers: HashMap<String, String>,
+}
+
+impl WebRequest {
+    fn is_web_socket_upgrade(&self) -> bool {
+        self.headers
+            .get("upgrade")
+            .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+pub(crate) fn invalid_request(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+// Only the head is read, body is left in the stream.
+pub(crate) async fn read_request<S: AsyncBufRead + Unpin>(
+    stream: &mut S,
+) -> io::Result<WebRequest> {
+    let mut head = stream.take(WEB_REQUEST_MAX_SIZE as u64);
+
+    let mut request_line = String::new();
+    head.read_line(&mut request_line).await?;
+
+    let (method, path) = match request_line.split_whitespace().collect::<Vec<&str>>()[..] {
+        [method, target, _] => (
+            method.to_string(),
+            target.split('?').next().unwrap_or(target).to_string(),
+        ),
+        _ => return Err(invalid_request("malformed request")),
+    };
+
+    let mut headers = HashMap::new();
+
+    loop {
+        let mut line = String::new();
+        if head.read_line(&mut line).await? == 0 {
+            return Err(invalid_request("request head is incomplete or too long"));
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    Ok(WebRequest {
+        method,
+        path,
+        headers,
+    })
+}
+
+pub(crate) async fn write_response<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await
+}
+
+// Passes lines between the game and the browser until either side closes, every line
+// is sent as one text message.
+fn bridge_web_socket<S>(web_socket: WebSocketStream<S>) -> DuplexStream
+where
+    S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let (game_side, bridge_side) = duplex(BRIDGE_BUFFER_SIZE);
+
+    tokio::spawn(async move {
+        let (mut web_socket_sink, mut web_socket_stream) = web_socket.split();
+        let (read_half, mut write_half) = split(bridge_side);
+        let mut lines = BufReader::new(read_half).lines();
+
+        loop {
+            tokio::select! {
+                message = web_socket_stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        for line in text.lines() {
+                            let line = format!("{}\n", line);
+                            if write_half.write_all(line.as_bytes()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // Pings are answered by tungstenite itself.
+                    Some(Ok(_)) => {}
+                },
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => {
+                        if web_socket_sink.send(Message::Text(line)).await.is_err() {
+                            break;
+                        }
+                    }
+                    _ => break,
+                },
+            }
+        }
+
+        let _ = web_socket_sink.close().await;
+    });
+
+    game_side
+}
+
+// Serves the page or upgrades the connection, game stream is returned for the latter.
+async fn handle_web_request(
+    stream: TcpStream,
+    index_html: &str,
+) -> io::Result<Option<Box<dyn GameStream>>> {
+    let mut stream = BufReader::new(stream);
+    let request = read_request(&mut stream).await?;
+
+    if request.method != "GET" {
+        write_response(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "Only GET is supported.",
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    match request.path.as_str() {
+        WEB_SOCKET_PATH if request.is_web_socket_upgrade() => {
+            let key = request
+                .headers
+                .get("sec-websocket-key")
+                .ok_or_else(|| invalid_request("WebSocket key is missing"))?;
+
+            let response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                derive_accept_key(key.as_bytes())
+            );
+            stream.write_all(response.as_bytes()).await?;
+            stream.flush().await?;
+
+            let web_socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+
+            Ok(Some(Box::new(bridge_web_socket(web_socket))))
+        }
+        WEB_CLIENT_PATH | "/index.html" => {
+            write_response(
+                &mut stream,
+                "200 OK",
+                "text/html; charset=utf-8",
+                index_html,
+            )
+            .await?;
+            Ok(None)
+        }
+        WEB_CLIENT_SCRIPT_PATH => {
+            write_response(
+                &mut stream,
+                "200 OK",
+                "text/javascript; charset=utf-8",
+                GAME_CLIENT_JS,
+            )
+            .await?;
+            Ok(None)
+        }
+        _ => {
+            write_response(&mut stream, "404 Not Found", "text/plain", "Not found.").await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Client page for the browser player playing with given start order.
+pub fn get_web_client_page(variant: GameVariant, start_order: StartOrder) -> String {
+    INDEX_HTML
+        .replace("{{variant}}", &variant.to_string())
+        .replace("{{start_order}}", &start_order.to_string())
+}
+
+/// Serves the browser client until the task is aborted. Browsers joining the game are
+/// passed to `game_sender`, the first one accepted by the server plays.
+pub(crate) async fn run_web_listener(
+    listener: TcpListener,
+    index_html: String,
+    game_sender: UnboundedSender<WebGameConnection>,
+) {
+    let index_html = Arc::new(index_html);
+
+    while let Ok((stream, addr)) = accept_connection(&listener).await {
+        let index_html = index_html.clone();
+        let game_sender = game_sender.clone();
+
+        // Slow browser does not hold back the other ones.
+        tokio::spawn(async move {
+            if let Ok(Ok(Some(stream))) =
+                timeout(WEB_REQUEST_TIMEOUT, handle_web_request(stream, &index_html)).await
+            {
+                let _ = game_sender.send((stream, addr));
+            }
+        });
+    }
+}
@@ -0,0 +1,152 @@
+// Scores, CSV rows and start order of headless games between AI players.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tic_tac_toe_game::ai_player::{AiPlayer, AiPlayerFactory};
+use tic_tac_toe_game::arena::{run_arena, ArenaGame, ArenaPlayers, ArenaStats};
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+
+// Built-in player which notes its start order and who of the players moved first.
+struct RecordingPlayer {
+    player: Box<dyn AiPlayer>,
+    index: usize,
+    start_orders: Arc<Mutex<Vec<(usize, StartOrder)>>>,
+    first_movers: Arc<Mutex<Vec<usize>>>,
+}
+
+impl AiPlayer for RecordingPlayer {
+    fn get_name(&self) -> String {
+        self.player.get_name()
+    }
+
+    fn new_game(&mut self, variant: GameVariant, start_order: StartOrder) -> io::Result<()> {
+        self.start_orders
+            .lock()
+            .unwrap()
+            .push((self.index, start_order));
+        self.player.new_game(variant, start_order)
+    }
+
+    fn choose_move(&mut self, moves: &[String]) -> io::Result<String> {
+        if moves.is_empty() {
+            self.first_movers.lock().unwrap().push(self.index);
+        }
+        self.player.choose_move(moves)
+    }
+}
+
+fn stats(wins: u32, draws: u32, losses: u32) -> ArenaStats {
+    ArenaStats {
+        games: wins + draws + losses,
+        wins,
+        draws,
+        losses,
+        ..ArenaStats::default()
+    }
+}
+
+fn create_players(
+    start_orders: &Arc<Mutex<Vec<(usize, StartOrder)>>>,
+    first_movers: &Arc<Mutex<Vec<usize>>>,
+) -> ArenaPlayers {
+    let players = ["mcts playouts=200 seed=1", "threat"];
+
+    [0, 1].map(|index| {
+        Box::new(RecordingPlayer {
+            player: AiPlayerFactory::create_ai_player(players[index]).unwrap(),
+            index,
+            start_orders: start_orders.clone(),
+            first_movers: first_movers.clone(),
+        }) as Box<dyn AiPlayer>
+    })
+}
+
+#[test]
+fn score_has_confidence_interval() {
+    assert_eq!(stats(0, 0, 0).get_score(), (0.0, 0.0));
+    // Nothing is known from one game.
+    assert_eq!(stats(1, 0, 0).get_score(), (1.0, 1.0));
+    assert_eq!(stats(0, 1, 0).get_score(), (0.5, 1.0));
+
+    // Equal results leave no doubt.
+    assert_eq!(stats(4, 0, 0).get_score(), (1.0, 0.0));
+    assert_eq!(stats(0, 0, 4).get_score(), (0.0, 0.0));
+    assert_eq!(stats(0, 4, 0).get_score(), (0.5, 0.0));
+
+    let (score, interval) = stats(1, 0, 1).get_score();
+    assert_eq!(score, 0.5);
+    assert!((interval - 0.98).abs() < 1e-9, "{}", interval);
+
+    let (score, interval) = stats(3, 2, 1).get_score();
+    assert!((score - 4.0 / 6.0).abs() < 1e-9, "{}", score);
+    assert!(interval > 0.0 && interval < 1.0, "{}", interval);
+}
+
+#[test]
+fn csv_fields_with_commas_and_quotes_are_quoted() {
+    let game = ArenaGame {
+        number: 3,
+        variant: GameVariant::Classic,
+        first: 1,
+        moves: vec!["/place (1, 1)".to_string(), "/place (2, 2)".to_string()],
+        winner: Some(1),
+        forfeit: Some("invalid move /place (4, 1): out of playboard".to_string()),
+        duration: Duration::from_millis(12),
+    };
+    let names = [
+        "engine ./bot --name \"deep, blue\"".to_string(),
+        "threat".to_string(),
+    ];
+
+    assert_eq!(
+        game.format_csv_row(&names),
+        "3,classic,threat,\"engine ./bot --name \"\"deep, blue\"\"\",1-0,threat,2,\
+         \"invalid move /place (4, 1): out of playboard\",12"
+    );
+}
+
+#[test]
+fn start_order_alternates_every_game() {
+    let start_orders = Arc::new(Mutex::new(Vec::new()));
+    let first_movers = Arc::new(Mutex::new(Vec::new()));
+    let mut players = create_players(&start_orders, &first_movers);
+    let mut games = Vec::new();
+
+    let stats = run_arena(&mut players, 4, GameVariant::Classic, |game| {
+        games.push(game.clone())
+    });
+
+    assert_eq!(stats.games, 4);
+    for (index, game) in games.iter().enumerate() {
+        assert_eq!(game.number, index as u32 + 1);
+        assert_eq!(game.first, index % 2);
+        assert!(game.forfeit.is_none(), "{:?}", game.forfeit);
+    }
+    assert_eq!(*first_movers.lock().unwrap(), vec![0, 1, 0, 1]);
+    assert_eq!(
+        *start_orders.lock().unwrap(),
+        vec![
+            (0, StartOrder::First),
+            (1, StartOrder::Second),
+            (0, StartOrder::Second),
+            (1, StartOrder::First),
+            (0, StartOrder::First),
+            (1, StartOrder::Second),
+            (0, StartOrder::Second),
+            (1, StartOrder::First),
+        ]
+    );
+
+    // The same players play the same games again.
+    let mut players = create_players(&Arc::default(), &Arc::default());
+    let mut replayed = Vec::new();
+    run_arena(&mut players, 4, GameVariant::Classic, |game| {
+        replayed.push(game.moves.clone())
+    });
+    assert_eq!(
+        replayed,
+        games.into_iter().map(|game| game.moves).collect::<Vec<_>>()
+    );
+}
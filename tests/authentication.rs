@@ -0,0 +1,95 @@
+// Challenge-response authentication of both sides over in-memory connection.
+
+use std::io::ErrorKind;
+use tokio::io::{duplex, AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use tic_tac_toe_game::authentication::{authenticate_client, authenticate_to_server};
+
+const CONNECTION_BUFFER_SIZE: usize = 1024;
+
+async fn authenticate(
+    server_password: &str,
+    client_password: &str,
+) -> (std::io::Result<()>, std::io::Result<()>) {
+    let (server_stream, client_stream) = duplex(CONNECTION_BUFFER_SIZE);
+    let mut server_stream = BufReader::new(server_stream);
+    let mut client_stream = BufReader::new(client_stream);
+
+    tokio::join!(
+        authenticate_client(&mut server_stream, server_password),
+        authenticate_to_server(&mut client_stream, client_password)
+    )
+}
+
+#[tokio::test]
+async fn same_password_is_accepted_by_both_sides() {
+    let (server_result, client_result) = authenticate("secret", "secret").await;
+
+    assert!(server_result.is_ok(), "{:?}", server_result);
+    assert!(client_result.is_ok(), "{:?}", client_result);
+}
+
+#[tokio::test]
+async fn wrong_password_is_rejected_by_both_sides() {
+    let (server_result, client_result) = authenticate("secret", "guess").await;
+
+    let server_err = server_result.unwrap_err();
+    assert_eq!(server_err.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(server_err.to_string(), "wrong password");
+
+    let client_err = client_result.unwrap_err();
+    assert_eq!(client_err.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(client_err.to_string(), "wrong password");
+}
+
+#[tokio::test]
+async fn truncated_response_is_rejected() {
+    let (server_stream, client_stream) = duplex(CONNECTION_BUFFER_SIZE);
+    let mut server_stream = BufReader::new(server_stream);
+    let mut client_stream = BufReader::new(client_stream);
+
+    let client = async {
+        let mut challenge = String::new();
+        client_stream.read_line(&mut challenge).await.unwrap();
+        assert!(challenge.starts_with("/challenge "), "{}", challenge);
+
+        // Half of the response and no challenge, then the connection is closed.
+        client_stream.write_all(b"/response 4f2a").await.unwrap();
+        client_stream.shutdown().await.unwrap();
+    };
+
+    let (server_result, _) =
+        tokio::join!(authenticate_client(&mut server_stream, "secret"), client);
+
+    let server_err = server_result.unwrap_err();
+    assert_eq!(server_err.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(server_err.to_string(), "malformed authentication response");
+}
+
+#[tokio::test]
+async fn truncated_server_proof_is_rejected() {
+    let (server_stream, client_stream) = duplex(CONNECTION_BUFFER_SIZE);
+    let mut server_stream = BufReader::new(server_stream);
+    let mut client_stream = BufReader::new(client_stream);
+
+    let server = async {
+        server_stream.write_all(b"/challenge 00ff\n").await.unwrap();
+
+        let mut response = String::new();
+        server_stream.read_line(&mut response).await.unwrap();
+        assert!(response.starts_with("/response "), "{}", response);
+
+        server_stream
+            .write_all(b"/authenticated 4f2")
+            .await
+            .unwrap();
+        server_stream.shutdown().await.unwrap();
+    };
+
+    let (client_result, _) =
+        tokio::join!(authenticate_to_server(&mut client_stream, "secret"), server);
+
+    let client_err = client_result.unwrap_err();
+    assert_eq!(client_err.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(client_err.to_string(), "server does not know the password");
+}
@@ -0,0 +1,118 @@
+// Engine protocol between the game and engines run as separate processes.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use tic_tac_toe_game::ai_player::AiPlayer;
+use tic_tac_toe_game::engine_player::{
+    format_position_line, parse_position_line, serve_engine_protocol, EngineProcess,
+};
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::threat_player::ThreatPlayer;
+
+// Built-in players of this crate are served as engines by its binary.
+fn get_engine_command(player: &str) -> String {
+    format!(
+        "{} engine {}",
+        env!("CARGO_BIN_EXE_tic_tac_toe_game"),
+        player
+    )
+}
+
+fn moves(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn position_is_parsed_as_formatted() {
+    for position in [
+        moves(&[]),
+        moves(&["/place (1, 1)"]),
+        moves(&[
+            "/place (1, 1) (1, 2)",
+            "/place (1, 2) (2, 2)",
+            "/collapse (1, 2)",
+        ]),
+    ] {
+        let line = format_position_line(&position);
+
+        assert!(line.starts_with("position"), "{}", line);
+        assert_eq!(parse_position_line(&line), Some(position));
+    }
+
+    assert_eq!(
+        format_position_line(&moves(&["/place (1, 1)", "/place (2, 2)"])),
+        "position /place (1, 1); /place (2, 2)"
+    );
+    assert_eq!(parse_position_line("position  "), Some(moves(&[])));
+    assert_eq!(parse_position_line("positions /place (1, 1)"), None);
+    assert_eq!(parse_position_line("go"), None);
+}
+
+#[test]
+fn best_move_answers_position() {
+    let mut output = Vec::new();
+
+    serve_engine_protocol(
+        &mut ThreatPlayer::new(),
+        "newgame classic second\nposition /place (1, 1); /place (2, 2); /place (1, 2)\ngo\nquit\n"
+            .as_bytes(),
+        &mut output,
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "bestmove /place (1, 3)\n"
+    );
+}
+
+#[test]
+fn engine_process_plays_and_chats() {
+    let config_path = std::env::temp_dir().join(format!(
+        "tic_tac_toe_game_engine_personality_{}",
+        std::process::id()
+    ));
+    fs::write(
+        &config_path,
+        "[chatty]\nchat_start = Hello.\nchat_probability = 1\nchat_move = Your turn.\n",
+    )
+    .unwrap();
+
+    let mut engine = EngineProcess::spawn(&get_engine_command(&format!(
+        "personality chatty {}",
+        config_path.display()
+    )))
+    .unwrap();
+
+    engine
+        .new_game(GameVariant::Classic, StartOrder::Second)
+        .unwrap();
+    let game_move = engine
+        .choose_move(&moves(&["/place (1, 1)", "/place (2, 2)", "/place (1, 2)"]))
+        .unwrap();
+
+    assert_eq!(game_move, "/place (1, 3)");
+    assert_eq!(engine.take_chat(), Some("Your turn.".to_string()));
+    assert_eq!(engine.take_chat(), None);
+
+    drop(engine);
+    fs::remove_file(&config_path).unwrap();
+}
+
+#[test]
+fn silent_engine_forfeits() {
+    // Cat never answers with a move.
+    let mut engine =
+        EngineProcess::spawn_with_move_timeout("cat", Duration::from_millis(200)).unwrap();
+
+    let started = Instant::now();
+    let err = engine.choose_move(&moves(&[])).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert!(started.elapsed() < Duration::from_secs(5));
+    // Engine is not asked anything more.
+    assert!(engine
+        .new_game(GameVariant::Classic, StartOrder::First)
+        .is_err());
+}
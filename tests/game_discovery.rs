@@ -0,0 +1,67 @@
+// Game announced by the server is found by the discovering player over loopback.
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tic_tac_toe_game::game_discovery::{announce_game, discover_games, GameAnnouncement};
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+
+// Announcements are sent every second, so each listening gets at least one.
+const LISTEN_DURATION: Duration = Duration::from_millis(1500);
+
+fn find_announcement(port: u16) -> GameAnnouncement {
+    discover_games(LISTEN_DURATION)
+        .unwrap()
+        .into_iter()
+        .find(|game| game.announcement.port == port)
+        .map(|game| {
+            assert!(game.ip_addr.is_loopback(), "{}", game.ip_addr);
+            game.announcement
+        })
+        .expect("Announced game was not discovered.")
+}
+
+#[test]
+fn announced_game_is_discovered() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let arc_open = Arc::new(AtomicBool::new(true));
+
+    let announcement = GameAnnouncement {
+        game_name: "Friday game; bring snacks".to_string(),
+        nickname: "host=me".to_string(),
+        // Unlikely to be announced by anything else on the machine.
+        port: 45871,
+        variant: GameVariant::Quantum,
+        start_order: StartOrder::Second,
+        open: true,
+        tls: true,
+        password: true,
+    };
+
+    let announcement_task = runtime.spawn(announce_game(
+        announcement.clone(),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        arc_open.clone(),
+    ));
+
+    // Separator in the name is replaced, '=' is kept.
+    assert_eq!(
+        find_announcement(announcement.port),
+        GameAnnouncement {
+            game_name: "Friday game, bring snacks".to_string(),
+            ..announcement.clone()
+        }
+    );
+
+    arc_open.store(false, Ordering::Relaxed);
+
+    let discovered = find_announcement(announcement.port);
+    assert!(!discovered.open);
+    assert!(discovered.tls);
+    assert!(discovered.password);
+    assert_eq!(discovered.nickname, "host=me");
+
+    announcement_task.abort();
+}
@@ -0,0 +1,129 @@
+// Rated nickname of the hub stays with the connection which earned the rating.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tic_tac_toe_game::game_hub::{run_hub, HubEvent};
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    fn connect(port: u16) -> Client {
+        let stream =
+            TcpStream::connect(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)).unwrap();
+        stream.set_read_timeout(Some(EVENT_TIMEOUT)).unwrap();
+
+        let mut client = Client {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+        };
+        assert!(client.read_line().starts_with("/pm Welcome"));
+        client
+    }
+
+    fn read_line(&mut self) -> String {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+
+    fn send(&mut self, line: &str) -> String {
+        writeln!(self.writer, "{}", line).unwrap();
+        self.read_line()
+    }
+}
+
+fn get_free_port() -> u16 {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn wait_for(events: &mpsc::Receiver<HubEvent>, found: impl Fn(&HubEvent) -> bool) -> HubEvent {
+    loop {
+        let event = events.recv_timeout(EVENT_TIMEOUT).unwrap();
+        if found(&event) {
+            return event;
+        }
+    }
+}
+
+#[test]
+fn rated_nickname_cannot_be_taken_by_other_connection() {
+    let port = get_free_port();
+    let (sender, events) = mpsc::channel();
+    let sender = Mutex::new(sender);
+
+    // Hub runs until the test ends.
+    thread::spawn(move || {
+        run_hub(
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            Some(port),
+            None,
+            Arc::new(move |event| {
+                let _ = sender.lock().unwrap().send(event);
+            }),
+        )
+    });
+    wait_for(&events, |event| matches!(event, HubEvent::Listening { .. }));
+
+    let mut alice = Client::connect(port);
+    let mut bob = Client::connect(port);
+    assert_eq!(
+        alice.send("/register alice"),
+        "/pm Registered as alice (1500)."
+    );
+    assert_eq!(bob.send("/register bob"), "/pm Registered as bob (1500).");
+
+    writeln!(alice.writer, "/queue").unwrap();
+    writeln!(bob.writer, "/queue").unwrap();
+    wait_for(&events, |event| {
+        matches!(event, HubEvent::GameStarted { .. })
+    });
+
+    // Alice loses by disconnecting, both nicknames are rated now.
+    drop(alice);
+    wait_for(&events, |event| {
+        matches!(event, HubEvent::GameFinished { .. })
+    });
+
+    let mut guest = Client::connect(port);
+    assert_eq!(
+        guest.send("/register alice"),
+        "/pm Nickname alice has rating of another player, choose another one."
+    );
+    assert_eq!(
+        guest.send("/register bob"),
+        "/pm Nickname bob has rating of another player, choose another one."
+    );
+    assert_eq!(
+        guest.send("/register carol"),
+        "/pm Registered as carol (1500)."
+    );
+
+    // Bob keeps the rating when registering back.
+    while !bob.read_line().starts_with("/pm Game finished") {}
+    let registered = bob.send("/register bobby");
+    assert!(
+        registered.starts_with("/pm Registered as bobby"),
+        "{}",
+        registered
+    );
+    let registered = bob.send("/register bob");
+    assert!(
+        registered.starts_with("/pm Registered as bob (15"),
+        "{}",
+        registered
+    );
+    assert_ne!(registered, "/pm Registered as bob (1500).");
+}
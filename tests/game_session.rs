@@ -0,0 +1,226 @@
+// Rules of the network game checked on pairs of sessions without sockets.
+
+use tic_tac_toe_game::game_event::{GameEvent, Player};
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::game_session::{GameSession, Score, SessionEffect};
+
+fn new_sessions() -> [GameSession; 2] {
+    [
+        GameSession::new(StartOrder::First, GameVariant::Classic),
+        GameSession::new(StartOrder::Second, GameVariant::Classic),
+    ]
+}
+
+// Handles command of the player and delivers sent lines to the other one, returns
+// effects of both sessions.
+fn play(
+    sessions: &mut [GameSession; 2],
+    player: usize,
+    line: &str,
+) -> (Vec<SessionEffect>, Vec<SessionEffect>) {
+    let effects = sessions[player].handle_local(line);
+    let mut remote_effects = Vec::new();
+
+    for effect in &effects {
+        if let SessionEffect::Send(line) = effect {
+            remote_effects.extend(sessions[1 - player].handle_remote(line));
+        }
+    }
+
+    (effects, remote_effects)
+}
+
+fn is_sent(effects: &[SessionEffect]) -> bool {
+    effects
+        .iter()
+        .any(|effect| matches!(effect, SessionEffect::Send(_)))
+}
+
+fn is_emitted(effects: &[SessionEffect], event: &GameEvent) -> bool {
+    effects.contains(&SessionEffect::Emit(event.clone()))
+}
+
+#[test]
+fn players_move_in_turns() {
+    let mut sessions = new_sessions();
+
+    assert!(sessions[0].get_game_status().my_turn);
+    assert!(!sessions[1].get_game_status().my_turn);
+
+    let (effects, _) = play(&mut sessions, 1, "/place (1, 1)");
+    assert!(is_emitted(&effects, &GameEvent::NotYourTurn));
+    assert!(!is_sent(&effects));
+
+    let (effects, _) = play(&mut sessions, 0, "/place (1, 1)");
+    assert!(is_sent(&effects));
+    assert!(!sessions[0].get_game_status().my_turn);
+    assert!(sessions[1].get_game_status().my_turn);
+
+    let (effects, _) = play(&mut sessions, 0, "/place (1, 2)");
+    assert!(is_emitted(&effects, &GameEvent::NotYourTurn));
+    assert_eq!(sessions[1].get_history().len(), 1);
+}
+
+#[test]
+fn invalid_local_move_is_not_sent() {
+    let mut sessions = new_sessions();
+
+    for line in ["/place (4, 1)", "/place abc", "/collapse (1, 1)"] {
+        let (effects, _) = play(&mut sessions, 0, line);
+
+        assert!(!is_sent(&effects), "{}", line);
+        assert!(effects
+            .iter()
+            .any(|effect| matches!(effect, SessionEffect::Emit(GameEvent::InvalidMove(_)))));
+    }
+
+    assert!(sessions[0].get_game_status().my_turn);
+    assert!(sessions[0].get_history().is_empty());
+}
+
+#[test]
+fn remote_move_out_of_turn_is_rejected() {
+    let mut session = GameSession::new(StartOrder::First, GameVariant::Classic);
+
+    for line in ["/place (1, 1)", "/collapse (1, 1)"] {
+        let effects = session.handle_remote(line);
+
+        assert!(effects
+            .iter()
+            .any(|effect| matches!(effect, SessionEffect::Emit(GameEvent::InvalidMove(_)))));
+    }
+
+    assert!(session.get_history().is_empty());
+    assert!(session.get_playboard().is_cell_free(0, 0));
+    assert!(session.get_game_status().my_turn);
+}
+
+#[test]
+fn win_is_scored_for_both_players() {
+    let mut sessions = new_sessions();
+
+    for (player, line) in [
+        (0, "/place (1, 1)"),
+        (1, "/place (2, 1)"),
+        (0, "/place (1, 2)"),
+        (1, "/place (2, 2)"),
+    ] {
+        play(&mut sessions, player, line);
+    }
+
+    let (effects, remote_effects) = play(&mut sessions, 0, "/place (1, 3)");
+    assert!(is_emitted(
+        &effects,
+        &GameEvent::GameFinished {
+            winner: Some(Player::You)
+        }
+    ));
+    assert!(is_emitted(
+        &remote_effects,
+        &GameEvent::GameFinished {
+            winner: Some(Player::Oponent)
+        }
+    ));
+
+    let status = [0, 1].map(|player| sessions[player].get_game_status());
+    assert!(!status[0].game_running && !status[1].game_running);
+    assert_eq!(
+        status[0].score,
+        Score {
+            wins: 1,
+            losses: 0,
+            draws: 0
+        }
+    );
+    assert_eq!(
+        status[1].score,
+        Score {
+            wins: 0,
+            losses: 1,
+            draws: 0
+        }
+    );
+
+    // Finished game takes no more moves.
+    let (effects, _) = play(&mut sessions, 1, "/place (3, 3)");
+    assert!(is_emitted(&effects, &GameEvent::GameNotRunning));
+}
+
+#[test]
+fn draw_is_scored_for_both_players() {
+    let mut sessions = new_sessions();
+
+    // X O X / X O O / O X X
+    for (player, line) in [
+        (0, "/place (1, 1)"),
+        (1, "/place (1, 2)"),
+        (0, "/place (1, 3)"),
+        (1, "/place (2, 2)"),
+        (0, "/place (2, 1)"),
+        (1, "/place (2, 3)"),
+        (0, "/place (3, 2)"),
+        (1, "/place (3, 1)"),
+        (0, "/place (3, 3)"),
+    ] {
+        play(&mut sessions, player, line);
+    }
+
+    for session in &sessions {
+        let status = session.get_game_status();
+
+        assert!(!status.game_running);
+        assert_eq!(status.score.draws, 1);
+        assert_eq!(status.score.wins + status.score.losses, 0);
+    }
+}
+
+#[test]
+fn new_game_starts_when_oponent_agrees() {
+    let mut sessions = new_sessions();
+
+    play(&mut sessions, 0, "/place (1, 1)");
+
+    // Agreement without request is ignored.
+    let (effects, _) = play(&mut sessions, 1, "/agree");
+    assert!(effects.is_empty());
+    assert_eq!(sessions[1].get_history().len(), 1);
+
+    let (effects, remote_effects) = play(&mut sessions, 0, "/playagain");
+    assert!(is_emitted(
+        &effects,
+        &GameEvent::NewGameRequested {
+            player: Player::You
+        }
+    ));
+    assert!(is_emitted(
+        &remote_effects,
+        &GameEvent::NewGameRequested {
+            player: Player::Oponent
+        }
+    ));
+
+    let (effects, remote_effects) = play(&mut sessions, 1, "/agree");
+    assert!(is_emitted(&effects, &GameEvent::GameRestarted));
+    assert!(is_emitted(&remote_effects, &GameEvent::GameRestarted));
+
+    for session in &sessions {
+        assert!(session.get_history().is_empty());
+        assert!(session.get_playboard().is_cell_free(0, 0));
+        assert!(session.get_game_status().game_running);
+    }
+    // Turns go on alternating from the previous game.
+    assert!(!sessions[0].get_game_status().my_turn);
+    assert!(sessions[1].get_game_status().my_turn);
+}
+
+#[test]
+fn disconnect_is_reported() {
+    let mut session = GameSession::new(StartOrder::First, GameVariant::Classic);
+
+    assert!(session.get_game_status().connected);
+    assert_eq!(
+        session.handle_disconnect(),
+        vec![SessionEffect::Emit(GameEvent::Disconnected)]
+    );
+    assert!(!session.get_game_status().connected);
+}
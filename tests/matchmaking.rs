@@ -0,0 +1,94 @@
+// Players are paired within rating ranges they accept and rated after the game.
+
+use std::str::FromStr;
+
+use tic_tac_toe_game::game_options::GameVariant;
+use tic_tac_toe_game::matchmaking::{
+    update_ratings, MatchQueue, QueueEntry, QueueRequest, RatingRange,
+};
+
+fn entry(id: u64, rating: f64, rating_range: Option<&str>) -> QueueEntry {
+    QueueEntry {
+        id,
+        rating,
+        variant: GameVariant::Classic,
+        rating_range: rating_range.map(|range| RatingRange::from_str(range).unwrap()),
+    }
+}
+
+#[test]
+fn players_are_paired_within_rating_range() {
+    let mut queue = MatchQueue::default();
+
+    assert_eq!(queue.join(entry(1, 1500.0, Some("1400-1600"))), None);
+    // Out of the range of the waiting player.
+    assert_eq!(queue.join(entry(2, 1700.0, None)), None);
+    // Waiting player is out of the range of the new one.
+    assert_eq!(queue.join(entry(3, 1450.0, Some("1800-1900"))), None);
+    assert_eq!(queue.len(), 3);
+
+    // Longest waiting player accepted by both is chosen, bounds are inclusive.
+    assert_eq!(
+        queue.join(entry(4, 1600.0, Some("1500-1700"))),
+        Some(entry(1, 1500.0, Some("1400-1600")))
+    );
+    assert_eq!(
+        queue.join(entry(5, 1650.0, None)),
+        Some(entry(2, 1700.0, None))
+    );
+    assert_eq!(queue.len(), 1);
+}
+
+#[test]
+fn players_of_other_variant_are_not_paired() {
+    let mut queue = MatchQueue::default();
+
+    queue.join(entry(1, 1500.0, None));
+    assert_eq!(
+        queue.join(QueueEntry {
+            variant: GameVariant::Quantum,
+            ..entry(2, 1500.0, None)
+        }),
+        None
+    );
+
+    // Player queued again replaces their entry, left one is not paired.
+    assert_eq!(queue.join(entry(1, 1500.0, None)), None);
+    assert_eq!(queue.len(), 2);
+    assert!(queue.leave(1));
+    assert!(!queue.leave(1));
+    assert_eq!(queue.join(entry(3, 1500.0, None)), None);
+}
+
+#[test]
+fn queue_request_is_parsed() {
+    let request = QueueRequest::from_str("quantum 1400-1600").unwrap();
+
+    assert_eq!(request.variant, Some(GameVariant::Quantum));
+    assert_eq!(
+        request.rating_range,
+        Some(RatingRange {
+            min: 1400,
+            max: 1600
+        })
+    );
+    assert_eq!(request.to_string(), "quantum 1400-1600");
+    assert!(QueueRequest::from_str("1600-1400").is_err());
+    assert!(QueueRequest::from_str("chess").is_err());
+}
+
+#[test]
+fn ratings_are_updated_by_result() {
+    // Equal players exchange half of the K factor.
+    assert_eq!(update_ratings([1500.0, 1500.0], Some(0)), [1516.0, 1484.0]);
+    assert_eq!(update_ratings([1500.0, 1500.0], Some(1)), [1484.0, 1516.0]);
+    assert_eq!(update_ratings([1500.0, 1500.0], None), [1500.0, 1500.0]);
+
+    // Favourite gains little by winning and loses on draw.
+    let [favourite, underdog] = update_ratings([1900.0, 1500.0], Some(0));
+    assert!(favourite > 1900.0 && favourite < 1904.0, "{}", favourite);
+    assert_eq!(favourite + underdog, 3400.0);
+
+    let [favourite, underdog] = update_ratings([1900.0, 1500.0], None);
+    assert!(favourite < 1900.0 && underdog > 1500.0);
+}
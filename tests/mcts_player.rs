@@ -0,0 +1,77 @@
+// Search options of the Monte Carlo player, repeatable seeded search and larger boards.
+
+use std::str::FromStr;
+
+use tic_tac_toe_game::ai_player::AiPlayer;
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::mcts_player::{search_position, MctsOptions, MctsPlayer};
+use tic_tac_toe_game::threat_player::BoardOptions;
+
+fn moves(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn options_are_parsed_and_written_back() {
+    let options = MctsOptions::from_str("playouts=500 time=200 threads=2 seed=7").unwrap();
+    assert_eq!(options.playouts, Some(500));
+    assert_eq!(options.threads, 2);
+    assert_eq!(MctsOptions::from_str(&options.to_string()), Ok(options));
+
+    let options = MctsOptions::from_str("size=9 win=4").unwrap();
+    assert_eq!(options.board, BoardOptions::from_str("size=9 win=4").ok());
+    assert_eq!(MctsOptions::from_str(&options.to_string()), Ok(options));
+
+    for options in ["playouts=0", "threads=x", "depth=3", "size=2", "seed"] {
+        assert!(MctsOptions::from_str(options).is_err(), "{}", options);
+    }
+}
+
+#[test]
+fn seeded_search_is_repeatable() {
+    // Thread count of seeded search does not depend on the machine.
+    let options = MctsOptions::from_str("playouts=500 seed=7").unwrap();
+    assert_eq!(options.threads, 1);
+
+    let position = moves(&["/place (1, 1)"]);
+    let first = search_position(GameVariant::Classic, &position, &options);
+    let second = search_position(GameVariant::Classic, &position, &options);
+    assert_eq!(first, second);
+    assert!(first.get_best_move().is_some());
+
+    let options = MctsOptions::from_str("playouts=500 threads=3 seed=7").unwrap();
+    assert_eq!(
+        search_position(GameVariant::Classic, &position, &options),
+        search_position(GameVariant::Classic, &position, &options)
+    );
+}
+
+#[test]
+fn larger_board_line_is_completed() {
+    let options = MctsOptions::from_str("playouts=3000 seed=3 size=7 win=4").unwrap();
+    let position = moves(&[
+        "/place (4, 2)",
+        "/place (1, 1)",
+        "/place (4, 3)",
+        "/place (1, 2)",
+        "/place (4, 4)",
+        "/place (1, 5)",
+    ]);
+
+    let analysis = search_position(GameVariant::Classic, &position, &options);
+    let best_move = analysis.get_best_move().unwrap();
+    assert!(
+        ["/place (4, 1)", "/place (4, 5)"].contains(&best_move),
+        "{}",
+        best_move
+    );
+
+    let mut player = MctsPlayer::new(options);
+    assert!(player
+        .new_game(GameVariant::Quantum, StartOrder::First)
+        .is_err());
+    player
+        .new_game(GameVariant::Classic, StartOrder::First)
+        .unwrap();
+    assert!(player.choose_move(&[]).unwrap().starts_with("/place"));
+}
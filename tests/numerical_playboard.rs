@@ -0,0 +1,68 @@
+// Numbers owned by the players of the numerical variant and lines summing to 15.
+
+use tic_tac_toe_game::game_options::StartOrder;
+use tic_tac_toe_game::numerical_playboard::NumericalPlayboard;
+use tic_tac_toe_game::playboard::GameState;
+use tic_tac_toe_game::playboard_variant::PlayboardVariant;
+
+const X: StartOrder = StartOrder::First;
+const O: StartOrder = StartOrder::Second;
+
+fn place(playboard: &mut NumericalPlayboard, data: &str, start_order: StartOrder) -> GameState {
+    playboard.place(data, start_order).unwrap().1
+}
+
+#[test]
+fn first_player_owns_odd_and_second_even_numbers() {
+    let mut playboard = NumericalPlayboard::new();
+
+    assert!(playboard.place("(1, 1) 2", X).is_err());
+    assert!(playboard.place("(1, 1) 3", O).is_err());
+    assert!(playboard.place("(1, 1) 10", O).is_err());
+    assert!(playboard.place("(1, 1) 0", O).is_err());
+    assert!(playboard.place("(1, 1)", X).is_err());
+
+    assert_eq!(place(&mut playboard, "(1, 1) 9", X), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(2, 2) 8", O), GameState::Placed);
+    assert_eq!(playboard.get_cell_text_lines(0, 0).join(""), "9");
+    assert!(!playboard.is_cell_free(1, 1));
+}
+
+#[test]
+fn used_number_and_taken_cell_are_rejected() {
+    let mut playboard = NumericalPlayboard::new();
+
+    place(&mut playboard, "(1, 1) 5", X);
+
+    let err = playboard.place("(3, 3) 5", X).unwrap_err();
+    assert!(err.contains("Number 5 is not available"), "{}", err);
+    assert!(err.contains("1 3 7 9"), "{}", err);
+    assert!(playboard.place("(1, 1) 4", O).is_err());
+    assert!(playboard.is_cell_free(2, 2));
+}
+
+#[test]
+fn line_summing_to_fifteen_wins() {
+    let mut playboard = NumericalPlayboard::new();
+
+    // Numbers of both players count in the sum.
+    assert_eq!(place(&mut playboard, "(1, 1) 1", X), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(1, 2) 8", O), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(2, 2) 3", X), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(1, 3) 6", O), GameState::GameOver);
+}
+
+#[test]
+fn full_line_of_other_sum_does_not_win() {
+    let mut playboard = NumericalPlayboard::new();
+
+    place(&mut playboard, "(1, 1) 9", X);
+    place(&mut playboard, "(1, 2) 8", O);
+    assert_eq!(place(&mut playboard, "(1, 3) 7", X), GameState::Placed);
+
+    // Diagonals make 14 and 13, col 16.
+    place(&mut playboard, "(2, 2) 4", O);
+    assert_eq!(place(&mut playboard, "(3, 3) 1", X), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(3, 1) 2", O), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(2, 1) 5", X), GameState::Placed);
+}
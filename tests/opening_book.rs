@@ -0,0 +1,105 @@
+// Book shipped with the library is checked against the solver.
+
+use std::str::FromStr;
+
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::opening_book::{get_book_move, BookResult, OpeningBook};
+use tic_tac_toe_game::playboard::Playboard;
+
+fn cells(rows: &str) -> Vec<Option<StartOrder>> {
+    rows.chars()
+        .map(|symbol| match symbol {
+            'x' => Some(StartOrder::First),
+            'o' => Some(StartOrder::Second),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn classic_book_matches_solver() {
+    let mut shipped = Vec::new();
+    let mut generated = Vec::new();
+
+    OpeningBook::classic().save(&mut shipped).unwrap();
+    OpeningBook::generate(3, None)
+        .unwrap()
+        .save(&mut generated)
+        .unwrap();
+
+    assert_eq!(String::from_utf8(shipped), String::from_utf8(generated));
+}
+
+#[test]
+fn book_survives_save_and_load() {
+    let book = OpeningBook::generate(3, Some(4)).unwrap();
+    let mut saved = Vec::new();
+    book.save(&mut saved).unwrap();
+    let loaded = OpeningBook::load(saved.as_slice()).unwrap();
+
+    assert_eq!(loaded.get_size(), 3);
+    assert_eq!(loaded.len(), book.len());
+    assert_eq!(
+        loaded.lookup(&cells("x...o....")),
+        book.lookup(&cells("x...o...."))
+    );
+}
+
+#[test]
+fn book_of_other_version_is_rejected() {
+    assert!(OpeningBook::load("tic-tac-toe-book 1 3\n".as_bytes()).is_err());
+    assert!(OpeningBook::load("tic-tac-toe-book 2 9\n".as_bytes()).is_err());
+    assert!(OpeningBook::generate(5, None).is_err());
+}
+
+#[test]
+fn classic_empty_board_is_draw() {
+    let book_move = OpeningBook::classic().lookup(&cells(".........")).unwrap();
+
+    assert_eq!(book_move.result, BookResult::Draw);
+}
+
+#[test]
+fn classic_book_move_is_mapped_back_from_symmetry() {
+    // The same position turned around has the winning move turned too.
+    let book = OpeningBook::classic();
+    let book_move = book.lookup(&cells("xx.oo....")).unwrap();
+    let turned_move = book.lookup(&cells("....oo.xx")).unwrap();
+
+    assert_eq!(
+        (book_move.row, book_move.col, book_move.result),
+        (1, 3, BookResult::Win)
+    );
+    assert_eq!((turned_move.row, turned_move.col), (3, 1));
+
+    let playboard = Playboard::from_str(".../.oo/.xx").unwrap();
+    assert_eq!(book.lookup_playboard(&playboard), Some(turned_move));
+}
+
+#[test]
+fn classic_edge_reply_to_corner_loses() {
+    let moves = ["/place (1, 1)".to_string(), "/place (1, 2)".to_string()];
+    let book_move = get_book_move(GameVariant::Classic, &moves).unwrap();
+
+    assert_eq!(book_move.result, BookResult::Win);
+    assert!(get_book_move(GameVariant::Quantum, &moves).is_none());
+}
+
+#[test]
+fn book_move_wins_fastest_and_loses_slowest() {
+    let book = OpeningBook::classic();
+
+    // Many moves win, only the diagonal at once.
+    let book_move = book.lookup(&cells("x.o.x.o..")).unwrap();
+    assert_eq!(
+        (book_move.row, book_move.col, book_move.result),
+        (3, 3, BookResult::Win)
+    );
+
+    // Any move but the block loses at once.
+    let book_move = book.lookup(&cells("xoox...x.")).unwrap();
+    assert_eq!(
+        (book_move.row, book_move.col, book_move.result),
+        (3, 1, BookResult::Loss)
+    );
+}
@@ -0,0 +1,118 @@
+// Personalities are read from config files and talk through the engine protocol.
+
+use tic_tac_toe_game::ai_player::AiPlayer;
+use tic_tac_toe_game::engine_player::serve_engine_protocol;
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::personality::{
+    get_builtin_personalities, load_personalities, Personality, PersonalityPlayer, PlayStyle,
+};
+
+const CONFIG: &str = "
+# Test personalities.
+[sharp]
+depth = 2
+style = defensive
+chat_start = Hello.
+chat_win = Got you.
+chat_win = Gotcha.
+
+[chatty]
+blunder = 0.5
+chat_probability = 1
+chat_move = Nice weather.
+";
+
+fn moves(cells: &[&str]) -> Vec<String> {
+    cells
+        .iter()
+        .map(|cell| format!("/place {}", cell))
+        .collect()
+}
+
+fn sharp() -> Personality {
+    load_personalities(CONFIG.as_bytes()).unwrap().remove(0)
+}
+
+#[test]
+fn config_file_is_loaded() {
+    let personalities = load_personalities(CONFIG.as_bytes()).unwrap();
+
+    assert_eq!(personalities.len(), 2);
+    assert_eq!(personalities[0].name, "sharp");
+    assert_eq!(personalities[0].depth, 2);
+    assert_eq!(personalities[0].style, PlayStyle::Defensive);
+    assert_eq!(personalities[0].chat_win, vec!["Got you.", "Gotcha."]);
+    assert_eq!(personalities[1].blunder_probability, 0.5);
+    assert_eq!(personalities[1].style, PlayStyle::Balanced);
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    assert!(load_personalities("depth = 2\n".as_bytes()).is_err());
+    assert!(load_personalities("[a]\nblunder = 2\n".as_bytes()).is_err());
+    assert!(load_personalities("[a]\nmood = happy\n".as_bytes()).is_err());
+    assert!(load_personalities("[a]\ndepth\n".as_bytes()).is_err());
+}
+
+#[test]
+fn builtin_personalities_are_available() {
+    let names: Vec<String> = get_builtin_personalities()
+        .into_iter()
+        .map(|personality| personality.name)
+        .collect();
+
+    assert_eq!(names, vec!["rookie", "grumpy", "wall"]);
+    assert!(PersonalityPlayer::load("grumpy", None).is_ok());
+    assert!(PersonalityPlayer::load("nobody", None).is_err());
+}
+
+#[test]
+fn personality_wins_and_says_so() {
+    let mut player = PersonalityPlayer::new(sharp());
+
+    player
+        .new_game(GameVariant::Classic, StartOrder::First)
+        .unwrap();
+    assert_eq!(player.take_chat(), Some("Hello.".to_string()));
+
+    let game_move = player
+        .choose_move(&moves(&["(1, 1)", "(2, 1)", "(1, 2)", "(2, 2)"]))
+        .unwrap();
+    assert_eq!(game_move, "/place (1, 3)");
+    assert!(player
+        .take_chat()
+        .is_some_and(|chat| chat == "Got you." || chat == "Gotcha."));
+}
+
+#[test]
+fn personality_blocks_the_oponent() {
+    let mut player = PersonalityPlayer::new(sharp());
+    player
+        .new_game(GameVariant::Classic, StartOrder::Second)
+        .unwrap();
+
+    let game_move = player
+        .choose_move(&moves(&["(1, 1)", "(3, 3)", "(1, 2)"]))
+        .unwrap();
+    assert_eq!(game_move, "/place (1, 3)");
+}
+
+#[test]
+fn chat_is_written_by_engine_protocol() {
+    let chatty = load_personalities(CONFIG.as_bytes()).unwrap().remove(1);
+    let mut player = PersonalityPlayer::new(chatty);
+    let mut output = Vec::new();
+
+    serve_engine_protocol(
+        &mut player,
+        "newgame classic first\nposition\ngo\nquit\n".as_bytes(),
+        &mut output,
+    )
+    .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "chat Nice weather.");
+    assert!(lines[1].starts_with("bestmove /place"));
+}
@@ -0,0 +1,133 @@
+// Positions of the playboard are hashed, canonicalized and written in notation.
+
+use std::str::FromStr;
+
+use tic_tac_toe_game::game_options::StartOrder;
+use tic_tac_toe_game::playboard::{
+    get_canonical_cells, hash_cells, transform_cells, PlayBoardGridOptions, Playboard, Symmetry,
+};
+
+fn position(notation: &str) -> Playboard {
+    Playboard::from_str(notation).unwrap()
+}
+
+#[test]
+fn notation_is_parsed_back() {
+    for notation in ["x.o/.x./..o", "xox/oxo/oxo", "1.2/.9./..4"] {
+        assert_eq!(position(notation).get_notation(), notation);
+    }
+
+    assert_eq!(Playboard::new().get_notation(), ".../.../...");
+    assert_eq!(position("X../.O./...").get_notation(), "x../.o./...");
+}
+
+#[test]
+fn invalid_notation_is_rejected() {
+    for notation in ["", "x../...", "x.../.../...", "x../.y./...", "x../.0./..."] {
+        assert!(Playboard::from_str(notation).is_err(), "{}", notation);
+    }
+}
+
+#[test]
+fn hash_does_not_depend_on_order_of_moves() {
+    let mut first = Playboard::new();
+    first.place_on_grid(1, 1, StartOrder::First);
+    first.place_on_grid(2, 2, StartOrder::Second);
+    first.place_on_grid(3, 3, StartOrder::First);
+
+    let mut second = Playboard::new();
+    second.place_on_grid(3, 3, StartOrder::First);
+    second.place_on_grid(2, 2, StartOrder::Second);
+    second.place_on_grid(1, 1, StartOrder::First);
+
+    assert_eq!(first.get_hash(), second.get_hash());
+    assert_eq!(first.get_hash(), position("x../.o./..x").get_hash());
+    assert_ne!(first.get_hash(), position("o../.x./..o").get_hash());
+}
+
+#[test]
+fn hash_tells_symbols_and_numbers_apart() {
+    let mut numbers = Playboard::new();
+    numbers.place_grid_option(2, 2, PlayBoardGridOptions::Number(5));
+
+    assert_eq!(numbers.get_hash(), position(".../.5./...").get_hash());
+    assert_ne!(numbers.get_hash(), position(".../.4./...").get_hash());
+    assert_ne!(numbers.get_hash(), position(".../.x./...").get_hash());
+}
+
+#[test]
+fn invalid_place_and_clear_keep_hash_right() {
+    let mut playboard = position("x../.../...");
+    let hash = playboard.get_hash();
+
+    assert!(!playboard.place_grid_option(1, 1, PlayBoardGridOptions::O));
+    assert_eq!(playboard.get_hash(), hash);
+
+    playboard.clear_board();
+    assert_eq!(playboard.get_hash(), Playboard::new().get_hash());
+    assert_eq!(Playboard::new().get_hash(), 0);
+}
+
+#[test]
+fn symmetric_positions_are_canonically_equal() {
+    let playboard = position("xo./.../...");
+    let (canonical, _) = playboard.get_canonical();
+
+    for symmetry in Symmetry::ALL {
+        let turned = playboard.transform(symmetry);
+        let (turned_canonical, turned_symmetry) = turned.get_canonical();
+
+        assert_eq!(turned_canonical.get_notation(), canonical.get_notation());
+        assert_eq!(turned_canonical.get_hash(), canonical.get_hash());
+        assert_eq!(
+            turned.transform(turned_symmetry).get_notation(),
+            canonical.get_notation()
+        );
+    }
+
+    assert_ne!(
+        position("x../.o./...").get_canonical().0.get_hash(),
+        position("x../..o/...").get_canonical().0.get_hash()
+    );
+}
+
+#[test]
+fn symmetry_is_undone_by_its_inverse() {
+    let playboard = position("xo./..x/o..");
+
+    for symmetry in Symmetry::ALL {
+        let back = playboard.transform(symmetry).transform(symmetry.inverse());
+        assert_eq!(back.get_notation(), playboard.get_notation());
+    }
+
+    assert_eq!(
+        playboard.transform(Symmetry::RotateRight).get_notation(),
+        "o.x/..o/.x."
+    );
+}
+
+#[test]
+fn cells_of_larger_board_are_canonicalized_and_hashed() {
+    let playboard = position("x.o/.x./..o");
+    assert_eq!(hash_cells(playboard.get_grid()), playboard.get_hash());
+    assert_eq!(
+        get_canonical_cells(playboard.get_grid(), 3).0,
+        playboard.get_canonical().0.get_grid().to_vec()
+    );
+
+    // 4x4 board of the opening book.
+    let mut cells = vec![PlayBoardGridOptions::Free; 16];
+    cells[1] = PlayBoardGridOptions::X;
+    cells[6] = PlayBoardGridOptions::O;
+    let (canonical, _) = get_canonical_cells(&cells, 4);
+
+    for symmetry in Symmetry::ALL {
+        let turned = transform_cells(&cells, 4, symmetry);
+        let (turned_canonical, turned_symmetry) = get_canonical_cells(&turned, 4);
+
+        assert_eq!(turned_canonical, canonical);
+        assert_eq!(hash_cells(&turned_canonical), hash_cells(&canonical));
+        assert_eq!(transform_cells(&turned, 4, turned_symmetry), canonical);
+    }
+    assert_ne!(hash_cells(&canonical), hash_cells(&cells[..9]));
+}
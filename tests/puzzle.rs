@@ -0,0 +1,132 @@
+// Win in N puzzles are checked by the solver and built by the position editor.
+
+use std::str::FromStr;
+
+use tic_tac_toe_game::game_options::StartOrder;
+use tic_tac_toe_game::playboard::Playboard;
+use tic_tac_toe_game::puzzle::{
+    find_win_length, get_defence, load_puzzles, PositionEditor, Puzzle, PuzzleGame, PuzzleProgress,
+};
+
+fn position(notation: &str) -> Playboard {
+    Playboard::from_str(notation).unwrap()
+}
+
+#[test]
+fn win_length_is_the_fastest_forced_win() {
+    assert_eq!(
+        find_win_length(&position("xx./oo./..."), StartOrder::First),
+        Some(1)
+    );
+    // Blocking the line makes a fork.
+    assert_eq!(
+        find_win_length(&position("x.o/.o./..x"), StartOrder::First),
+        Some(2)
+    );
+    assert_eq!(
+        find_win_length(&position(".../.../..."), StartOrder::First),
+        None
+    );
+    assert_eq!(
+        find_win_length(&position("o../.x./..."), StartOrder::First),
+        None
+    );
+}
+
+#[test]
+fn defender_blocks_the_line() {
+    assert_eq!(
+        get_defence(&position("xx./o../..."), StartOrder::Second),
+        Some((1, 3))
+    );
+    // Own line wins before blocking.
+    assert_eq!(
+        get_defence(&position("xx./oo./x.."), StartOrder::Second),
+        Some((2, 3))
+    );
+}
+
+#[test]
+fn puzzles_are_loaded_and_written_back() {
+    let input = "# Win in one\n\nxx./oo./... first 1\nx.o/.o./..x first 2\n";
+    let puzzles = load_puzzles(input.as_bytes()).unwrap();
+
+    assert_eq!(puzzles.len(), 2);
+    assert_eq!(puzzles[1].get_on_turn(), StartOrder::First);
+    assert_eq!(puzzles[1].to_string(), "x.o/.o./..x first 2");
+    assert_eq!(
+        Puzzle::from_str(&puzzles[0].to_string()),
+        Ok(puzzles[0].clone())
+    );
+}
+
+#[test]
+fn wrong_puzzles_are_rejected() {
+    for line in [
+        "xx./oo./... first 2",
+        ".../.../... first 3",
+        "xxx/oo./... second 1",
+        "1../.../... first 1",
+        "xx./oo./... third 1",
+        "xx./oo./...",
+    ] {
+        assert!(Puzzle::from_str(line).is_err(), "{}", line);
+    }
+
+    let err = load_puzzles("xx./oo./... first 1\nxx./oo./... first 0\n".as_bytes()).unwrap_err();
+    assert!(err.starts_with("Line 2:"), "{}", err);
+}
+
+#[test]
+fn puzzle_is_solved_against_defence() {
+    let mut game = PuzzleGame::new(Puzzle::from_str("x.o/.o./..x first 2").unwrap());
+
+    // Fork cannot be stopped, any reply loses in one move.
+    assert_eq!(game.play(3, 1), Ok(PuzzleProgress::Replied(1, 2)));
+    assert_eq!(game.get_moves_left(), 1);
+    assert!(game.play(1, 2).is_err());
+    assert_eq!(game.play(3, 2), Ok(PuzzleProgress::Solved));
+    assert!(game.is_finished());
+}
+
+#[test]
+fn wrong_move_fails_the_puzzle() {
+    let mut game = PuzzleGame::new(Puzzle::from_str("x.o/.o./..x first 2").unwrap());
+
+    assert_eq!(game.play(1, 2), Ok(PuzzleProgress::Failed(Some((3, 1)))));
+    assert!(game.is_finished());
+    assert!(game.play(3, 2).is_err());
+
+    game.restart();
+    assert_eq!(game.get_moves_left(), 2);
+    assert_eq!(game.get_playboard().get_notation(), "x.o/.o./..x");
+}
+
+#[test]
+fn editor_builds_puzzle() {
+    let mut editor = PositionEditor::new();
+
+    for arguments in [
+        "x (1, 1)",
+        "o (1, 1)",
+        "x (3, 3)",
+        "x (1, 2)",
+        "clear (1, 2)",
+    ] {
+        editor.edit(arguments).unwrap();
+    }
+    assert_eq!(editor.get_playboard().get_notation(), "o../.../..x");
+
+    editor.edit("x.o/.o./..x").unwrap();
+    editor.edit("turn second").unwrap();
+    let puzzle = editor.create_puzzle().unwrap();
+    assert_eq!(puzzle.to_string(), "x.o/.o./..x second 1");
+
+    editor.edit("clear").unwrap();
+    assert_eq!(editor.get_playboard().get_notation(), ".../.../...");
+    assert!(editor.create_puzzle().is_err());
+
+    for arguments in ["x (4, 1)", "x", "turn third", "1../.../...", "y (1, 1)"] {
+        assert!(editor.edit(arguments).is_err(), "{}", arguments);
+    }
+}
@@ -0,0 +1,80 @@
+// Self-play training is reproducible and its policy plays as AI player.
+
+use std::io::Write;
+
+use tic_tac_toe_game::ai_player::AiPlayerFactory;
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::q_learning::{Policy, TrainingOptions};
+
+const TRAINING_EPISODES: u32 = 5000;
+
+fn train(seed: u64) -> Policy {
+    Policy::train(&TrainingOptions {
+        episodes: TRAINING_EPISODES,
+        seed: Some(seed),
+        ..TrainingOptions::default()
+    })
+    .0
+}
+
+#[test]
+fn seeded_training_learns_the_same_policy() {
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+
+    train(7).save(&mut first).unwrap();
+    train(7).save(&mut second).unwrap();
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn training_counts_all_games() {
+    let (policy, stats) = Policy::train(&TrainingOptions {
+        episodes: TRAINING_EPISODES,
+        seed: Some(1),
+        ..TrainingOptions::default()
+    });
+
+    assert_eq!(
+        stats.first_wins + stats.second_wins + stats.draws,
+        TRAINING_EPISODES
+    );
+    assert_eq!(stats.positions, policy.len());
+}
+
+#[test]
+fn policy_survives_save_and_load() {
+    let policy = train(3);
+    let mut saved = Vec::new();
+    policy.save(&mut saved).unwrap();
+
+    assert_eq!(Policy::load(saved.as_slice()).unwrap(), policy);
+    assert!(Policy::load("tic-tac-toe-policy 0\n".as_bytes()).is_err());
+    assert!(Policy::load("tic-tac-toe-policy 1\nxo. 1 2\n".as_bytes()).is_err());
+}
+
+#[test]
+fn loaded_policy_completes_the_line() {
+    let path = std::env::temp_dir().join(format!("policy-{}.txt", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    train(5).save(&mut file).unwrap();
+    file.flush().unwrap();
+
+    let mut player =
+        AiPlayerFactory::create_ai_player(&format!("policy {}", path.display())).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(player
+        .new_game(GameVariant::Quantum, StartOrder::First)
+        .is_err());
+    player
+        .new_game(GameVariant::Classic, StartOrder::First)
+        .unwrap();
+
+    let moves: Vec<String> = ["(1, 1)", "(2, 1)", "(1, 2)", "(2, 2)"]
+        .iter()
+        .map(|cell| format!("/place {}", cell))
+        .collect();
+    assert_eq!(player.choose_move(&moves).unwrap(), "/place (1, 3)");
+}
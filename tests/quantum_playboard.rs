@@ -0,0 +1,136 @@
+// Entanglement cycles of the quantum variant, their collapse and the winner of lines
+// completed by one collapse.
+
+use tic_tac_toe_game::game_options::StartOrder;
+use tic_tac_toe_game::playboard::GameState;
+use tic_tac_toe_game::playboard_variant::PlayboardVariant;
+use tic_tac_toe_game::quantum_playboard::QuantumPlayboard;
+
+const X: StartOrder = StartOrder::First;
+const O: StartOrder = StartOrder::Second;
+
+fn place(playboard: &mut QuantumPlayboard, data: &str, start_order: StartOrder) -> GameState {
+    playboard.place(data, start_order).unwrap().1
+}
+
+// Text of the classical cell, row and col indexed from 1.
+fn cell(playboard: &QuantumPlayboard, row: usize, col: usize) -> String {
+    playboard.get_cell_text_lines(row - 1, col - 1).join(" ")
+}
+
+// Row and col indexed from 1 as in cell.
+fn is_free(playboard: &QuantumPlayboard, row: usize, col: usize) -> bool {
+    playboard.is_cell_free(row - 1, col - 1)
+}
+
+// Both moves of the pair share the cells, the second player's mark collapses into
+// the second cell and the first player's into the first one.
+fn place_pair(playboard: &mut QuantumPlayboard, first: &str, second: &str) {
+    let cells = format!("{} {}", first, second);
+
+    assert_eq!(place(playboard, &cells, X), GameState::Placed);
+    assert_eq!(place(playboard, &cells, O), GameState::CollapsePending);
+    assert_eq!(playboard.collapse(second, X).unwrap().1, GameState::Placed);
+}
+
+#[test]
+fn closed_cycle_waits_for_collapse() {
+    let mut playboard = QuantumPlayboard::new();
+
+    assert_eq!(place(&mut playboard, "(1, 1) (1, 2)", X), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(1, 2) (2, 2)", O), GameState::Placed);
+    assert!(!playboard.is_collapse_pending());
+
+    assert_eq!(
+        place(&mut playboard, "(2, 2) (1, 1)", X),
+        GameState::CollapsePending
+    );
+    assert!(playboard.is_collapse_pending());
+    assert!(playboard.place("(3, 1) (3, 2)", O).is_err());
+}
+
+#[test]
+fn open_chain_is_not_cycle() {
+    let mut playboard = QuantumPlayboard::new();
+
+    assert_eq!(place(&mut playboard, "(1, 1) (1, 2)", X), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(1, 3) (2, 2)", O), GameState::Placed);
+    assert_eq!(place(&mut playboard, "(1, 2) (1, 3)", X), GameState::Placed);
+    assert!(playboard.collapse("(1, 2)", O).is_err());
+}
+
+#[test]
+fn collapse_cascades_through_cycle() {
+    let mut playboard = QuantumPlayboard::new();
+
+    place(&mut playboard, "(1, 1) (1, 2)", X);
+    place(&mut playboard, "(1, 2) (2, 2)", O);
+    place(&mut playboard, "(2, 2) (1, 1)", X);
+
+    // Only cells of the move closing the cycle are accepted.
+    assert!(playboard.collapse("(1, 2)", O).is_err());
+    assert_eq!(
+        playboard.collapse("(1, 1)", O).unwrap().1,
+        GameState::Placed
+    );
+
+    assert!(!playboard.is_collapse_pending());
+    assert_eq!(cell(&playboard, 1, 1), "X");
+    assert_eq!(cell(&playboard, 1, 2), "X");
+    assert_eq!(cell(&playboard, 2, 2), "O");
+
+    // Only cells of the cycle are taken.
+    for (row, col) in [(1, 1), (1, 2), (2, 2)] {
+        assert!(!is_free(&playboard, row, col), "({}, {})", row, col);
+    }
+    assert!(is_free(&playboard, 3, 3));
+}
+
+#[test]
+fn collapse_completing_line_wins() {
+    let mut playboard = QuantumPlayboard::new();
+
+    place_pair(&mut playboard, "(1, 1)", "(2, 1)");
+    place_pair(&mut playboard, "(1, 2)", "(2, 2)");
+    place(&mut playboard, "(1, 3) (3, 3)", X);
+    place(&mut playboard, "(1, 3) (3, 3)", O);
+
+    assert_eq!(
+        playboard.collapse("(3, 3)", X).unwrap().1,
+        GameState::GameOver
+    );
+    assert_eq!(playboard.winner(), Some(X));
+}
+
+#[test]
+fn simultaneous_lines_are_won_by_lower_max_subscript() {
+    // Row 1 of X is completed by move 5, row 2 of O by move 6.
+    let mut playboard = QuantumPlayboard::new();
+
+    place_pair(&mut playboard, "(1, 1)", "(2, 1)");
+    place_pair(&mut playboard, "(1, 2)", "(2, 2)");
+    place(&mut playboard, "(1, 3) (2, 3)", X);
+    place(&mut playboard, "(2, 3) (1, 3)", O);
+
+    assert_eq!(
+        playboard.collapse("(2, 3)", X).unwrap().1,
+        GameState::GameOver
+    );
+    assert_eq!(cell(&playboard, 1, 3), "X");
+    assert_eq!(playboard.winner(), Some(X));
+
+    // Row 1 of X is completed by move 6, row 2 of O by move 5.
+    let mut playboard = QuantumPlayboard::new();
+
+    place_pair(&mut playboard, "(1, 1)", "(2, 1)");
+    place_pair(&mut playboard, "(1, 2)", "(2, 2)");
+    place(&mut playboard, "(2, 3) (1, 3)", O);
+    place(&mut playboard, "(1, 3) (2, 3)", X);
+
+    assert_eq!(
+        playboard.collapse("(1, 3)", O).unwrap().1,
+        GameState::GameOver
+    );
+    assert_eq!(cell(&playboard, 2, 3), "O");
+    assert_eq!(playboard.winner(), Some(O));
+}
@@ -0,0 +1,137 @@
+// Game API reads the state of local game and plays moves of its player.
+#![cfg(feature = "rest-api")]
+
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tic_tac_toe_game::game_event::{GameEvent, Player};
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::host_type_objects::NetworkGame;
+use tic_tac_toe_game::rest_api::{ApiGameHandle, GameApi};
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct ApiGame {
+    api: GameApi,
+    handle: ApiGameHandle,
+    events: Receiver<GameEvent>,
+    // Both sides are kept running until the test ends.
+    _games: (NetworkGame, NetworkGame),
+}
+
+fn start_api_game() -> ApiGame {
+    let api = GameApi::start(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).unwrap();
+    let handle = api.add_game(GameVariant::Classic);
+
+    let (event_sender, events) = channel();
+    let event_sender = Mutex::new(event_sender);
+
+    let games = NetworkGame::local_pair(
+        StartOrder::First,
+        GameVariant::Classic,
+        handle.wrap_event_handler(Arc::new(move |event| {
+            let _ = event_sender.lock().unwrap().send(event);
+        })),
+        Arc::new(|_| {}),
+    );
+    handle.attach(&games.0);
+
+    ApiGame {
+        api,
+        handle,
+        events,
+        _games: games,
+    }
+}
+
+// Returns status line and JSON body of the response.
+fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (String, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.lines().next().unwrap().to_string();
+
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn game_state_is_returned() {
+    let game = start_api_game();
+    let path = format!("/games/{}", game.handle.get_id());
+
+    let (status, state) = request(game.api.get_addr(), "GET", &path, "");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(state["variant"], "classic");
+    assert_eq!(state["state"], "running");
+    assert_eq!(state["turn"], "you");
+    assert_eq!(state["board"].as_array().unwrap().len(), 3);
+    assert_eq!(state["board"][0][0], "");
+    assert!(state["history"].as_array().unwrap().is_empty());
+
+    let (status, _) = request(game.api.get_addr(), "GET", "/games/999", "");
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+
+    let (status, _) = request(game.api.get_addr(), "GET", "/games/first", "");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+}
+
+#[test]
+fn move_is_played_by_local_player() {
+    let game = start_api_game();
+    let path = format!("/games/{}/moves", game.handle.get_id());
+
+    let (status, body) = request(game.api.get_addr(), "POST", &path, r#"{"place": "(1, 1)"}"#);
+    assert_eq!(status, "HTTP/1.1 202 Accepted");
+    assert_eq!(body["command"], "/place (1, 1)");
+
+    loop {
+        let event = game
+            .events
+            .recv_timeout(EVENT_TIMEOUT)
+            .expect("Move was not played.");
+
+        if let GameEvent::Placed { player, place, .. } = event {
+            assert_eq!(player, Player::You);
+            assert_eq!(place, "(1, 1)");
+            break;
+        }
+    }
+
+    let (_, state) = request(
+        game.api.get_addr(),
+        "GET",
+        &format!("/games/{}", game.handle.get_id()),
+        "",
+    );
+    assert_eq!(state["board"][0][0], "X");
+    assert_eq!(state["turn"], "oponent");
+    assert_eq!(state["history"].as_array().unwrap().len(), 1);
+
+    // Oponent is on turn now.
+    let (status, _) = request(game.api.get_addr(), "POST", &path, r#"{"place": "(2, 2)"}"#);
+    assert_eq!(status, "HTTP/1.1 409 Conflict");
+
+    for body in [
+        "place (2, 2)",
+        r#"{"place": "(2, 2)", "collapse": "(2, 2)"}"#,
+    ] {
+        let (status, _) = request(game.api.get_addr(), "POST", &path, body);
+        assert_eq!(status, "HTTP/1.1 400 Bad Request", "{}", body);
+    }
+}
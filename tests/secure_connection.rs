@@ -0,0 +1,91 @@
+// Certificates of servers are pinned on the first connection over loopback.
+
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tokio::net::{TcpListener, TcpStream};
+
+use tic_tac_toe_game::secure_connection::{connect_tls_pinned, ServerIdentity};
+
+// Known hosts file of the test, removed when it exists from the last run.
+fn get_known_hosts_path(test_name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "tic_tac_toe_game_known_hosts_{}_{}",
+        test_name,
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&path);
+
+    path
+}
+
+// Serves TLS handshakes on loopback until the test ends.
+async fn start_server(identity: &ServerIdentity) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let acceptor = identity.create_acceptor().unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let _ = acceptor.accept(stream).await;
+            });
+        }
+    });
+
+    addr
+}
+
+async fn connect(addr: SocketAddr, known_hosts_path: &Path) -> std::io::Result<bool> {
+    let stream = TcpStream::connect(addr).await?;
+    let (_, trusted_certificate) =
+        connect_tls_pinned(stream, addr, known_hosts_path.to_path_buf()).await?;
+
+    Ok(trusted_certificate.newly_pinned)
+}
+
+#[tokio::test]
+async fn first_connection_pins_certificate() {
+    let known_hosts_path = get_known_hosts_path("pins");
+    let identity = ServerIdentity::generate().unwrap();
+    let addr = start_server(&identity).await;
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let (_, trusted_certificate) = connect_tls_pinned(stream, addr, known_hosts_path.clone())
+        .await
+        .unwrap();
+    assert!(trusted_certificate.newly_pinned);
+    assert_eq!(trusted_certificate.fingerprint, identity.get_fingerprint());
+    assert_eq!(
+        fs::read_to_string(&known_hosts_path).unwrap(),
+        format!("{} {}\n", addr, identity.get_fingerprint())
+    );
+
+    assert!(!connect(addr, &known_hosts_path).await.unwrap());
+
+    // Server on other port of the same address has its own entry.
+    let other_addr = start_server(&ServerIdentity::generate().unwrap()).await;
+    assert!(connect(other_addr, &known_hosts_path).await.unwrap());
+    assert!(!connect(addr, &known_hosts_path).await.unwrap());
+
+    fs::remove_file(&known_hosts_path).unwrap();
+}
+
+#[tokio::test]
+async fn changed_certificate_is_rejected() {
+    let known_hosts_path = get_known_hosts_path("changed");
+    let addr = start_server(&ServerIdentity::generate().unwrap()).await;
+    let pinned = ServerIdentity::generate().unwrap().get_fingerprint();
+
+    fs::write(&known_hosts_path, format!("{} {}\n", addr, pinned)).unwrap();
+
+    let err = connect(addr, &known_hosts_path).await.unwrap_err();
+    assert!(err.to_string().contains("changed from pinned"), "{}", err);
+    assert_eq!(
+        fs::read_to_string(&known_hosts_path).unwrap(),
+        format!("{} {}\n", addr, pinned)
+    );
+
+    fs::remove_file(&known_hosts_path).unwrap();
+}
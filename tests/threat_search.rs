@@ -0,0 +1,364 @@
+// Known tactical positions solved by the threat-space search engine.
+
+use std::str::FromStr;
+
+use tic_tac_toe_game::ai_player::{play_move, AiPlayer, AiPlayerFactory, RandomPlayer};
+use tic_tac_toe_game::game_options::{GameVariant, StartOrder};
+use tic_tac_toe_game::playboard::{GameState, Playboard};
+use tic_tac_toe_game::threat_player::{BoardOptions, ThreatBoard, ThreatKind, ThreatPlayer};
+
+const SEARCH_DEPTH: usize = 8;
+const GOMOKU_WIN_LENGTH: usize = 5;
+const PLAYED_GAMES: usize = 20;
+
+fn classic(rows: &[&str]) -> ThreatBoard {
+    ThreatBoard::from_rows(rows, 3).expect("Rows do not form a square.")
+}
+
+// 15x15 board with given symbols as (row, col).
+fn gomoku(x: &[(usize, usize)], o: &[(usize, usize)]) -> ThreatBoard {
+    let mut board = ThreatBoard::new(15, GOMOKU_WIN_LENGTH);
+
+    for &(row, col) in x {
+        assert!(board.place(row, col, StartOrder::First));
+    }
+    for &(row, col) in o {
+        assert!(board.place(row, col, StartOrder::Second));
+    }
+
+    board
+}
+
+// Replays the forced win and checks every reply was the only one.
+fn assert_forced_win(board: &ThreatBoard, attacker: StartOrder, line: &[(usize, usize)]) {
+    let mut board = board.clone();
+    let defender = attacker.oponent();
+
+    for (index, &(row, col)) in line.iter().enumerate() {
+        if index % 2 == 0 {
+            assert!(board.place(row, col, attacker));
+            assert!(board.get_winning_cells(defender).is_empty() || board.is_win(attacker));
+        } else {
+            assert_eq!(board.get_winning_cells(attacker), vec![(row, col)]);
+            assert!(board.place(row, col, defender));
+        }
+    }
+
+    assert!(board.is_win(attacker) || board.has_double_threat(attacker));
+}
+
+// Plays the game to the end, returns start order of the winner or None on draw.
+fn play_game(players: &mut [Box<dyn AiPlayer>; 2]) -> Option<StartOrder> {
+    let mut playboard = Playboard::new();
+    let mut moves = Vec::new();
+    let mut on_turn = StartOrder::First;
+
+    for (player, start_order) in players
+        .iter_mut()
+        .zip([StartOrder::First, StartOrder::Second])
+    {
+        player.new_game(GameVariant::Classic, start_order).unwrap();
+    }
+
+    loop {
+        let index = match on_turn {
+            StartOrder::First => 0,
+            StartOrder::Second => 1,
+        };
+        let game_move = players[index].choose_move(&moves).unwrap();
+
+        match play_move(&mut playboard, &game_move, on_turn).unwrap().1 {
+            GameState::GameOver => return Some(on_turn),
+            GameState::Draw => return None,
+            _ => {}
+        }
+
+        moves.push(game_move);
+        on_turn = on_turn.oponent();
+    }
+}
+
+#[test]
+fn classic_wins_in_one() {
+    let mut board = classic(&["xx.", "oo.", "..."]);
+
+    assert_eq!(board.get_winning_cells(StartOrder::First), vec![(0, 2)]);
+    assert_eq!(
+        board.choose_move(StartOrder::First, SEARCH_DEPTH),
+        Some((0, 2))
+    );
+}
+
+#[test]
+fn classic_blocks_oponent_line() {
+    let mut board = classic(&["oo.", "x..", "..x"]);
+
+    assert_eq!(
+        board.choose_move(StartOrder::First, SEARCH_DEPTH),
+        Some((0, 2))
+    );
+}
+
+#[test]
+fn classic_corner_opening_against_edge_is_won() {
+    let mut board = classic(&["xo.", "...", "..."]);
+    let line = board
+        .find_forced_win(StartOrder::First, SEARCH_DEPTH)
+        .expect("Forced win not found.");
+
+    assert_forced_win(&board, StartOrder::First, &line);
+}
+
+#[test]
+fn classic_fork_is_double_threat() {
+    let board = classic(&["x.x", ".o.", "x.o"]);
+
+    assert!(board.has_double_threat(StartOrder::First));
+    assert!(!board.has_double_threat(StartOrder::Second));
+}
+
+#[test]
+fn classic_defends_against_opposite_corners() {
+    // Only an edge keeps the first player from making a fork.
+    let mut board = classic(&["x..", ".o.", "..x"]);
+    let reply = board.choose_move(StartOrder::Second, SEARCH_DEPTH).unwrap();
+
+    assert!([(0, 1), (1, 0), (1, 2), (2, 1)].contains(&reply));
+}
+
+#[test]
+fn classic_center_against_corner_has_no_forced_win() {
+    let mut board = classic(&["o..", ".x.", "..."]);
+
+    assert!(board
+        .find_forced_win(StartOrder::First, SEARCH_DEPTH)
+        .is_none());
+}
+
+#[test]
+fn gomoku_open_four_is_double_threat() {
+    let board = gomoku(&[(7, 4), (7, 5), (7, 6), (7, 7)], &[]);
+    let threats = board.get_threats(StartOrder::First);
+
+    assert!(threats
+        .iter()
+        .any(|threat| threat.kind == ThreatKind::OpenFour && threat.cells == vec![(7, 3), (7, 8)]));
+    assert!(board.has_double_threat(StartOrder::First));
+}
+
+#[test]
+fn gomoku_blocked_four_is_single_threat() {
+    let mut board = gomoku(&[(7, 4), (7, 5), (7, 6), (7, 7)], &[(7, 3)]);
+
+    assert_eq!(board.get_winning_cells(StartOrder::First), vec![(7, 8)]);
+    assert_eq!(
+        board.choose_move(StartOrder::Second, SEARCH_DEPTH),
+        Some((7, 8))
+    );
+}
+
+#[test]
+fn gomoku_recognises_open_and_broken_threes() {
+    let board = gomoku(&[(3, 4), (3, 5), (3, 6), (9, 4), (9, 6), (9, 7)], &[]);
+    let threats = board.get_threats(StartOrder::First);
+
+    assert!(threats
+        .iter()
+        .any(|threat| threat.kind == ThreatKind::OpenThree
+            && threat.cells.contains(&(3, 3))
+            && threat.cells.contains(&(3, 7))));
+    assert!(threats
+        .iter()
+        .any(|threat| threat.kind == ThreatKind::BrokenThree
+            && threat.cells == vec![(9, 3), (9, 5), (9, 8)]));
+    assert!(!board.has_double_threat(StartOrder::First));
+}
+
+#[test]
+fn gomoku_broken_four_wins_by_gap() {
+    let mut board = gomoku(&[(5, 5), (6, 6), (8, 8), (9, 9)], &[(4, 4), (10, 10)]);
+
+    assert_eq!(board.get_winning_cells(StartOrder::First), vec![(7, 7)]);
+    assert_eq!(
+        board.choose_move(StartOrder::First, SEARCH_DEPTH),
+        Some((7, 7))
+    );
+}
+
+#[test]
+fn gomoku_four_four_by_continuous_fours() {
+    // Blocked threes on a row and a col meet after a four on the row above.
+    let board = gomoku(
+        &[
+            (7, 3),
+            (7, 4),
+            (7, 5),
+            (4, 6),
+            (5, 6),
+            (6, 2),
+            (6, 3),
+            (6, 4),
+        ],
+        &[(7, 2), (3, 6), (6, 1), (0, 0), (0, 14), (14, 0), (14, 14)],
+    );
+    let line = board
+        .clone()
+        .find_forced_win(StartOrder::First, SEARCH_DEPTH)
+        .expect("Forced win not found.");
+
+    assert!(line.len() > 1);
+    assert_forced_win(&board, StartOrder::First, &line);
+}
+
+#[test]
+fn gomoku_quiet_position_has_no_forced_win() {
+    let mut board = gomoku(&[(7, 7), (8, 8)], &[(7, 8), (8, 7)]);
+
+    assert!(board
+        .find_forced_win(StartOrder::First, SEARCH_DEPTH)
+        .is_none());
+    assert!(board
+        .find_forced_win(StartOrder::Second, SEARCH_DEPTH)
+        .is_none());
+}
+
+#[test]
+fn forced_block_making_defender_four_refutes_line() {
+    // Four on the diagonal is blocked on the row of the defender, who then wins before
+    // the double threat on the row of the attacker.
+    let rows = ["......", "......", "...x..", "...x..", ".o.o.o", ".....x"];
+    let mut board = ThreatBoard::from_rows(&rows, 4).unwrap();
+
+    assert!(board
+        .find_forced_win(StartOrder::First, SEARCH_DEPTH)
+        .is_none());
+
+    let rows = ["......", "......", "...x..", "...x..", ".o...o", ".....x"];
+    let board = ThreatBoard::from_rows(&rows, 4).unwrap();
+    let line = board
+        .clone()
+        .find_forced_win(StartOrder::First, SEARCH_DEPTH)
+        .expect("Forced win not found.");
+
+    assert_forced_win(&board, StartOrder::First, &line);
+}
+
+#[test]
+fn board_options_are_parsed() {
+    let gomoku = BoardOptions::from_str("size=15").unwrap();
+
+    assert_eq!(
+        gomoku,
+        BoardOptions {
+            size: 15,
+            win_length: GOMOKU_WIN_LENGTH
+        }
+    );
+    assert_eq!(BoardOptions::from_str(&gomoku.to_string()), Ok(gomoku));
+    assert_eq!(BoardOptions::from_str("size=4").unwrap().win_length, 4);
+
+    for options in [
+        "",
+        "win=5",
+        "size=2",
+        "size=20",
+        "size=15 win=16",
+        "size=x",
+        "depth=3",
+    ] {
+        assert!(BoardOptions::from_str(options).is_err(), "{}", options);
+    }
+}
+
+#[test]
+fn threat_player_plays_larger_board() {
+    let mut player = AiPlayerFactory::create_ai_player("threat size=15 win=5").unwrap();
+    player
+        .new_game(GameVariant::Classic, StartOrder::Second)
+        .unwrap();
+
+    assert_eq!(player.get_name(), "threat size=15 win=5");
+
+    // Four of the first player on row 8 is blocked at its left end.
+    let moves: Vec<String> = [
+        "(8, 5)", "(8, 4)", "(8, 6)", "(1, 1)", "(8, 7)", "(1, 2)", "(8, 8)",
+    ]
+    .iter()
+    .map(|cell| format!("/place {}", cell))
+    .collect();
+
+    assert_eq!(player.choose_move(&moves).unwrap(), "/place (8, 9)");
+    // Blocked three makes a four by either of the cells right of it.
+    let game_move = ThreatPlayer::for_board(BoardOptions::from_str("size=15").unwrap())
+        .choose_move(&moves[..6])
+        .unwrap();
+    assert!(
+        ["/place (8, 8)", "/place (8, 9)"].contains(&game_move.as_str()),
+        "{}",
+        game_move
+    );
+}
+
+#[test]
+fn evaluation_of_longest_win_length_does_not_overflow() {
+    let options = BoardOptions::from_str("size=19 win=19").unwrap();
+    assert_eq!(options.win_length, 19);
+
+    // Ten rows one symbol short of the line, every one weighs the most.
+    let mut board = ThreatBoard::new(19, 19);
+    for row in 0..10 {
+        for col in 0..18 {
+            assert!(board.place(row, col, StartOrder::First));
+        }
+    }
+
+    assert_eq!(board.evaluate(StartOrder::First), i64::MAX);
+    assert_eq!(board.evaluate(StartOrder::Second), -i64::MAX);
+
+    let mut player = ThreatPlayer::for_board(options);
+    player
+        .new_game(GameVariant::Classic, StartOrder::First)
+        .unwrap();
+    let moves = ["/place (10, 10)".to_string()];
+    assert!(player.choose_move(&moves).unwrap().starts_with("/place"));
+}
+
+#[test]
+fn threat_player_draws_against_itself() {
+    let mut players: [Box<dyn AiPlayer>; 2] =
+        [Box::new(ThreatPlayer::new()), Box::new(ThreatPlayer::new())];
+
+    assert_eq!(play_game(&mut players), None);
+}
+
+#[test]
+fn threat_player_never_loses_to_random() {
+    for game in 0..PLAYED_GAMES {
+        let (mut players, threat): ([Box<dyn AiPlayer>; 2], StartOrder) = if game % 2 == 0 {
+            (
+                [Box::new(ThreatPlayer::new()), Box::new(RandomPlayer::new())],
+                StartOrder::First,
+            )
+        } else {
+            (
+                [Box::new(RandomPlayer::new()), Box::new(ThreatPlayer::new())],
+                StartOrder::Second,
+            )
+        };
+
+        let winner = play_game(&mut players);
+        assert!(winner.is_none() || winner == Some(threat));
+    }
+}
+
+#[test]
+fn threat_player_plays_only_classic_variant() {
+    let mut player = ThreatPlayer::new();
+
+    assert!(player
+        .new_game(GameVariant::Quantum, StartOrder::First)
+        .is_err());
+    assert!(player
+        .new_game(GameVariant::Classic, StartOrder::First)
+        .is_ok());
+}
@@ -0,0 +1,228 @@
+// Round-robin schedule, knockout bracket, match decisions and standings of tournaments.
+
+use std::collections::HashSet;
+
+use tic_tac_toe_game::tournament::{MatchResult, Pairing, Tournament, TournamentFormat};
+
+fn new_tournament(format: TournamentFormat, players: usize) -> Tournament {
+    let names = (1..=players)
+        .map(|player| format!("player{}", player))
+        .collect();
+    Tournament::new(format, names)
+}
+
+// Single game won by the given player, drawn without winner.
+fn game_result(players: [usize; 2], winner: Option<usize>) -> MatchResult {
+    let mut result = MatchResult::new(players);
+
+    match winner {
+        Some(winner) => result.wins[if players[0] == winner { 0 } else { 1 }] = 1,
+        None => result.draws = 1,
+    }
+
+    result
+}
+
+fn get_matches(pairings: &[Pairing]) -> Vec<[usize; 2]> {
+    pairings
+        .iter()
+        .filter_map(|pairing| match pairing {
+            Pairing::Match(players) => Some(*players),
+            Pairing::Bye(_) => None,
+        })
+        .collect()
+}
+
+// Plays all rounds, every match won by the player chosen by the winner function.
+fn play_rounds(
+    tournament: &mut Tournament,
+    winner: impl Fn([usize; 2]) -> Option<usize>,
+) -> Vec<Vec<Pairing>> {
+    let mut rounds = Vec::new();
+
+    while let Some(round) = tournament.start_round() {
+        let pairings = round.pairings.clone();
+
+        for players in get_matches(&pairings) {
+            tournament.record_result(game_result(players, winner(players)));
+        }
+        rounds.push(pairings);
+    }
+
+    rounds
+}
+
+#[test]
+fn round_robin_pairs_every_two_players_once() {
+    for players in 2..=7 {
+        let mut tournament = new_tournament(TournamentFormat::RoundRobin, players);
+        let rounds = play_rounds(&mut tournament, |players| players.into_iter().min());
+        let mut met = HashSet::new();
+
+        for pairings in &rounds {
+            let mut playing = HashSet::new();
+
+            for pairing in pairings {
+                let round_players = match pairing {
+                    Pairing::Match(players) => players.to_vec(),
+                    Pairing::Bye(player) => vec![*player],
+                };
+                assert!(round_players.iter().all(|player| playing.insert(*player)));
+            }
+            for [first, second] in get_matches(pairings) {
+                assert!(met.insert((first.min(second), first.max(second))));
+            }
+        }
+
+        assert_eq!(
+            met.len(),
+            players * (players - 1) / 2,
+            "{} players",
+            players
+        );
+    }
+}
+
+#[test]
+fn bye_rotates_with_odd_number_of_players() {
+    let mut tournament = new_tournament(TournamentFormat::RoundRobin, 5);
+    let rounds = play_rounds(&mut tournament, |_| None);
+
+    let byes: HashSet<usize> = rounds
+        .iter()
+        .map(|pairings| {
+            let byes: Vec<usize> = pairings
+                .iter()
+                .filter_map(|pairing| match pairing {
+                    Pairing::Bye(player) => Some(*player),
+                    Pairing::Match(_) => None,
+                })
+                .collect();
+            assert_eq!(byes.len(), 1);
+            byes[0]
+        })
+        .collect();
+
+    assert_eq!(rounds.len(), 5);
+    assert_eq!(byes.len(), 5);
+}
+
+#[test]
+fn bracket_seeds_first_against_last() {
+    let mut tournament = new_tournament(TournamentFormat::Knockout, 8);
+    assert_eq!(
+        tournament.start_round().unwrap().pairings,
+        vec![
+            Pairing::Match([0, 7]),
+            Pairing::Match([3, 4]),
+            Pairing::Match([1, 6]),
+            Pairing::Match([2, 5]),
+        ]
+    );
+
+    // Missing seeds give byes to the top ones.
+    let mut tournament = new_tournament(TournamentFormat::Knockout, 6);
+    assert_eq!(
+        tournament.start_round().unwrap().pairings,
+        vec![
+            Pairing::Bye(0),
+            Pairing::Match([3, 4]),
+            Pairing::Bye(1),
+            Pairing::Match([2, 5]),
+        ]
+    );
+}
+
+#[test]
+fn knockout_winner_advances() {
+    let mut tournament = new_tournament(TournamentFormat::Knockout, 4);
+
+    // Round not finished yet.
+    tournament.start_round();
+    assert!(tournament.start_round().is_none());
+    tournament.record_result(game_result([0, 3], Some(3)));
+    tournament.record_result(game_result([1, 2], Some(1)));
+
+    assert_eq!(
+        tournament.start_round().unwrap().pairings,
+        vec![Pairing::Match([3, 1])]
+    );
+    tournament.record_result(game_result([3, 1], Some(1)));
+
+    assert!(tournament.start_round().is_none());
+    assert_eq!(tournament.get_winner(), Some(1));
+}
+
+#[test]
+fn match_is_decided_by_games_forfeit_or_seed() {
+    let mut result = MatchResult::new([4, 2]);
+    result.wins = [2, 1];
+    assert_eq!(result.get_winner(), Some(4));
+
+    result.forfeit = Some(4);
+    assert_eq!(result.get_winner(), Some(2));
+
+    let mut result = MatchResult::new([4, 2]);
+    result.wins = [1, 1];
+    result.draws = 1;
+    assert_eq!(result.get_winner(), None);
+    result.decided_by_seed = true;
+    assert_eq!(result.get_winner(), Some(2));
+
+    result.double_forfeit = true;
+    assert_eq!(result.get_winner(), None);
+}
+
+#[test]
+fn match_lost_by_both_leaves_bracket_slot_empty() {
+    let mut tournament = new_tournament(TournamentFormat::Knockout, 4);
+
+    tournament.start_round();
+    let mut result = MatchResult::new([0, 3]);
+    result.double_forfeit = true;
+    tournament.record_result(result);
+    tournament.record_result(game_result([1, 2], Some(2)));
+
+    // Winner of the other match is left alone.
+    assert!(tournament.start_round().is_none());
+    assert_eq!(tournament.get_winner(), Some(2));
+
+    let mut tournament = new_tournament(TournamentFormat::Knockout, 8);
+    tournament.start_round();
+    let mut result = MatchResult::new([0, 7]);
+    result.double_forfeit = true;
+    tournament.record_result(result);
+    for players in [[3, 4], [1, 6], [2, 5]] {
+        tournament.record_result(game_result(players, Some(players[0])));
+    }
+
+    // Bracket halves stay apart.
+    assert_eq!(
+        tournament.start_round().unwrap().pairings,
+        vec![Pairing::Bye(3), Pairing::Match([1, 2])]
+    );
+}
+
+#[test]
+fn equal_points_are_ranked_by_sonneborn_berger() {
+    let mut tournament = new_tournament(TournamentFormat::RoundRobin, 4);
+
+    // Players 1 and 2 have one point each, player 2 beat the stronger player 3.
+    play_rounds(&mut tournament, |players| {
+        match (players[0].min(players[1]), players[0].max(players[1])) {
+            (0, 1) | (0, 2) | (1, 2) => Some(players[0].min(players[1])),
+            (0, 3) => None,
+            (1, 3) => Some(3),
+            _ => Some(2),
+        }
+    });
+
+    let standings = tournament.get_standings();
+    let order: Vec<usize> = standings.iter().map(|standing| standing.player).collect();
+    assert_eq!(order, vec![0, 3, 2, 1]);
+    assert_eq!(standings[2].points, standings[3].points);
+    assert_eq!(standings[2].game_points, standings[3].game_points);
+    assert_eq!(standings[2].sonneborn_berger, 1.5);
+    assert_eq!(standings[3].sonneborn_berger, 1.0);
+    assert_eq!(tournament.get_winner(), Some(0));
+}
@@ -0,0 +1,143 @@
+// Browser player is simulated by headless WebSocket client playing against the server.
+
+use futures_util::{SinkExt, StreamExt};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::runtime::Runtime;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use tic_tac_toe_game::game_event::{GameEvent, Player};
+use tic_tac_toe_game::game_options::{ConnectionOptions, GameVariant, StartOrder};
+use tic_tac_toe_game::host_type_objects::{HostTypeObject, NetworkGame};
+use tic_tac_toe_game::playboard::GameState;
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct WebServer {
+    web_port: u16,
+    events: Receiver<GameEvent>,
+    game: thread::JoinHandle<NetworkGame>,
+}
+
+// Server waits in its own thread, it blocks until the oponent joins.
+fn start_server(start_order: StartOrder) -> WebServer {
+    let port = portpicker::pick_unused_port().expect("No free port.");
+    let web_port = portpicker::pick_unused_port().expect("No free port.");
+
+    let (event_sender, events) = channel();
+    let event_sender = Mutex::new(event_sender);
+
+    let game = thread::spawn(move || {
+        NetworkGame::listen(
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            Some(port),
+            start_order,
+            GameVariant::Classic,
+            ConnectionOptions {
+                web_port: Some(web_port),
+                ..ConnectionOptions::default()
+            },
+            Arc::new(move |event| {
+                let _ = event_sender.lock().unwrap().send(event);
+            }),
+        )
+    });
+
+    let server = WebServer {
+        web_port,
+        events,
+        game,
+    };
+    wait_for_event(&server.events, |event| {
+        matches!(event, GameEvent::WaitingForBrowserOponent { .. })
+    });
+
+    server
+}
+
+fn wait_for_event(events: &Receiver<GameEvent>, predicate: impl Fn(&GameEvent) -> bool) {
+    loop {
+        let event = events
+            .recv_timeout(EVENT_TIMEOUT)
+            .expect("Expected game event did not come.");
+
+        if predicate(&event) {
+            return;
+        }
+    }
+}
+
+#[test]
+fn serves_client_page_for_oponent_of_server() {
+    let server = start_server(StartOrder::Second);
+
+    let response = Runtime::new().unwrap().block_on(async {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, server.web_port))
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        response
+    });
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains(r#"data-variant="classic""#));
+    assert!(response.contains(r#"data-start-order="first""#));
+    assert!(response.contains("game_client.js"));
+}
+
+// Network game owns its runtime, so the browser runs on another one and blocks on
+// every step.
+#[test]
+fn browser_plays_against_terminal_player() {
+    let server = start_server(StartOrder::Second);
+    let runtime = Runtime::new().unwrap();
+
+    let url = format!("ws://127.0.0.1:{}/ws", server.web_port);
+    let (mut web_socket, _) = runtime.block_on(connect_async(url)).unwrap();
+
+    wait_for_event(&server.events, |event| {
+        matches!(event, GameEvent::Connected { .. })
+    });
+    let mut game = server.game.join().unwrap();
+
+    runtime
+        .block_on(web_socket.send(Message::Text("/place (1, 1)".to_string())))
+        .unwrap();
+    wait_for_event(&server.events, |event| {
+        *event
+            == GameEvent::Placed {
+                player: Player::Oponent,
+                place: "(1, 1)".to_string(),
+                game_state: GameState::Placed,
+            }
+    });
+
+    game.send_message("/place (2, 2)".to_string());
+    let message = runtime.block_on(web_socket.next()).unwrap().unwrap();
+    assert_eq!(message, Message::Text("/place (2, 2)".to_string()));
+
+    runtime
+        .block_on(web_socket.send(Message::Text("/pm hello from browser".to_string())))
+        .unwrap();
+    wait_for_event(&server.events, |event| {
+        *event == GameEvent::PrivateMessage("hello from browser".to_string())
+    });
+
+    runtime.block_on(web_socket.close(None)).unwrap();
+    wait_for_event(&server.events, |event| *event == GameEvent::Disconnected);
+
+    game.stop();
+}